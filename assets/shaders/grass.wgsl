@@ -0,0 +1,76 @@
+// Grass, ferns and wildflowers: the standard mesh vertex shader, with every vertex bent
+// downwind by how high it stands above the plant's base. Gusts sweep through in step with the
+// trees, see `GUST_SPEED` and `GUST_WAVE` in weather.rs.
+
+#import bevy_pbr::{
+    mesh_functions,
+    forward_io::{Vertex, VertexOutput},
+    view_transformations::position_world_to_clip,
+    mesh_view_bindings::globals,
+}
+
+struct Swaying {
+    // Downwind direction in x and y, and the wind's strength in z
+    wind: vec4<f32>,
+    // Gust speed and wavelength in x and y, and how far tips bend in a full gale in z
+    gusts: vec4<f32>,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(100)
+var<uniform> swaying: Swaying;
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+    var world_position = mesh_functions::mesh_position_local_to_world(
+        world_from_local,
+        vec4<f32>(vertex.position, 1.0),
+    );
+
+    // Tips bend furthest, and most as a gust passes; a little flutter in strong wind
+    let downwind = swaying.wind.xy;
+    let strength = swaying.wind.z;
+    let base = world_from_local[3].xyz;
+    let gust = sin(globals.time * swaying.gusts.x - dot(base.xz, downwind) * swaying.gusts.y) * 0.5 + 0.5;
+    let flutter = sin(globals.time * 9.0 + dot(base.xz, vec2<f32>(7.1, 5.3))) * 0.2 * strength;
+    let height = max(world_position.y - base.y, 0.0);
+    let bend = swaying.gusts.z * (0.05 + strength * (0.5 + 0.5 * gust + flutter)) * height * height;
+    world_position.x += downwind.x * bend;
+    world_position.z += downwind.y * bend;
+    // Sink the tip a little as it bends, so blades don't stretch
+    world_position.y -= bend * bend / max(height, 0.01) * 0.5;
+
+    out.world_position = world_position;
+    out.position = position_world_to_clip(world_position.xyz);
+    out.world_normal = mesh_functions::mesh_normal_local_to_world(vertex.normal, vertex.instance_index);
+
+#ifdef VERTEX_UVS_A
+    out.uv = vertex.uv;
+#endif
+#ifdef VERTEX_UVS_B
+    out.uv_b = vertex.uv_b;
+#endif
+#ifdef VERTEX_TANGENTS
+    out.world_tangent = mesh_functions::mesh_tangent_local_to_world(
+        world_from_local,
+        vertex.tangent,
+        vertex.instance_index,
+    );
+#endif
+#ifdef VERTEX_COLORS
+    out.color = vertex.color;
+#endif
+#ifdef VERTEX_OUTPUT_INSTANCE_INDEX
+    out.instance_index = vertex.instance_index;
+#endif
+#ifdef VISIBILITY_RANGE_DITHER
+    out.visibility_range_dither = mesh_functions::get_visibility_range_dither_level(
+        vertex.instance_index,
+        world_from_local[3],
+    );
+#endif
+
+    return out;
+}
@@ -0,0 +1,42 @@
+// The moon: the standard unlit material, lit from the sun's side of the sky so it shows its
+// phase. The dark side glows faintly with earthshine.
+
+#import bevy_pbr::{
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::main_pass_post_lighting_processing,
+    forward_io::{VertexOutput, FragmentOutput},
+}
+
+struct MoonPhase {
+    sun: vec3<f32>,
+    illumination: f32,
+    earthshine: f32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(100)
+var<uniform> moon: MoonPhase;
+
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+
+    // Sunlight falls on the moon from between us and the sun: face on at full moon, from
+    // behind at new moon, and from the side at the quarters
+    let toward_us = pbr_input.V;
+    let across = moon.sun - toward_us * dot(moon.sun, toward_us);
+    let sideways = select(vec3<f32>(0.0, 1.0, 0.0), normalize(across), length(across) > 0.001);
+    let face_on = clamp(2.0 * moon.illumination - 1.0, -1.0, 1.0);
+    let sunlight = toward_us * face_on + sideways * sqrt(1.0 - face_on * face_on);
+
+    // A soft terminator between the sunlit side and the dark one
+    let lit = smoothstep(-0.05, 0.05, dot(pbr_input.N, sunlight));
+    let color = pbr_input.material.base_color;
+
+    var out: FragmentOutput;
+    out.color = vec4<f32>(color.rgb * mix(moon.earthshine, 1.0, lit), color.a);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+    return out;
+}
@@ -0,0 +1,62 @@
+// Open water for the pond: the standard material, with its normal rippled by a few waves
+// running with the wind and rings spreading from raindrops.
+
+#import bevy_pbr::{
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::{alpha_discard, apply_pbr_lighting, main_pass_post_lighting_processing},
+    forward_io::{VertexOutput, FragmentOutput},
+    mesh_view_bindings::globals,
+}
+
+struct Water {
+    ripple_scale: f32,
+    ripple_speed: f32,
+    wind: f32,
+    rain: f32,
+    downwind: vec2<f32>,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(100)
+var<uniform> water: Water;
+
+// Slope of one wave running along `direction`, `size` times the base ripple scale.
+fn wave(position: vec2<f32>, direction: vec2<f32>, size: f32, time: f32) -> vec2<f32> {
+    let frequency = water.ripple_scale / size;
+    let phase = dot(position, direction) * frequency - time * water.ripple_speed / sqrt(size);
+    return direction * cos(phase) * size;
+}
+
+// Slope of the rings spreading from raindrops, one drop at a time in each cell of a grid.
+fn raindrops(position: vec2<f32>, time: f32) -> vec2<f32> {
+    let cell = floor(position * 2.0);
+    let offset = fract(position * 2.0) - 0.5;
+    let start = fract(sin(dot(cell, vec2<f32>(12.9898, 78.233))) * 43758.5453);
+    let age = fract(time * 0.9 + start);
+    let distance = length(offset);
+    let ring = sin((distance - age * 0.5) * 60.0) * (1.0 - age) * (1.0 - smoothstep(0.3, 0.5, distance));
+    return offset / max(distance, 0.001) * ring;
+}
+
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+    let position = in.world_position.xz;
+    let time = globals.time;
+    // Waves mostly run downwind, and build with it
+    let across = vec2<f32>(-water.downwind.y, water.downwind.x);
+    let waves = wave(position, water.downwind, 1.0, time)
+        + wave(position, normalize(water.downwind + across * 0.6), 0.6, time)
+        + wave(position, across, 0.35, time);
+    let slope = waves * (0.03 + 0.12 * water.wind) + raindrops(position, time) * 0.15 * water.rain;
+    pbr_input.N = normalize(vec3<f32>(-slope.x, 1.0, -slope.y));
+
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+    return out;
+}
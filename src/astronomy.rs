@@ -0,0 +1,240 @@
+//! Where the real sun and moon are in the sky, for a place on Earth and a moment in time.
+//!
+//! Uses the low-precision solar and lunar coordinates from the Astronomical Almanac, good to
+//! about a hundredth of a degree for the sun and a few tenths for the moon between 1950 and
+//! 2050, which is plenty to light a forest.
+
+use std::f64::consts::TAU;
+
+/// Seconds in a day.
+const SECONDS_PER_DAY: f64 = 86_400.0;
+/// Days from the Unix epoch to the J2000 epoch, 2000-01-01 12:00 UTC.
+const J2000: f64 = 10_957.5;
+/// Days from the Unix epoch to 2000-01-01 00:00 UTC.
+const YEAR_2000: f64 = 10_957.0;
+/// Mean length of a calendar year in days.
+const DAYS_PER_YEAR: f64 = 365.2425;
+
+/// The sun's position as seen from one place, all angles in radians.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SolarPosition {
+    /// Height above the horizon; negative at night
+    pub elevation: f32,
+    /// Compass bearing, clockwise from north
+    pub azimuth: f32,
+    /// How far the sun has turned past local solar noon, in `-PI..PI`
+    pub hour_angle: f32,
+    /// Declination: how far north of the celestial equator the sun is
+    pub declination: f32,
+}
+
+impl SolarPosition {
+    /// The sun's position `unix_seconds` after 1970-01-01 00:00 UTC, seen from `latitude` and
+    /// `longitude` in degrees (north and east positive).
+    pub fn at(unix_seconds: f64, latitude: f32, longitude: f32) -> Self {
+        let days = days_since_j2000(unix_seconds);
+        let (right_ascension, declination) = equatorial(days, sun_ecliptic_longitude(days), 0.0);
+        let sky = Horizontal::at(days, right_ascension, declination, latitude, longitude);
+
+        Self {
+            elevation: sky.elevation as f32,
+            azimuth: sky.azimuth as f32,
+            hour_angle: sky.hour_angle as f32,
+            declination: declination as f32,
+        }
+    }
+}
+
+/// The moon's position as seen from one place, angles in radians.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LunarPosition {
+    /// Height above the horizon; negative once it has set
+    pub elevation: f32,
+    /// Compass bearing, clockwise from north
+    pub azimuth: f32,
+    /// Fraction of the way from one new moon to the next: 0.25 is first quarter, 0.5 full
+    pub phase: f32,
+}
+
+impl LunarPosition {
+    /// The moon's position `unix_seconds` after 1970-01-01 00:00 UTC, seen from `latitude`
+    /// and `longitude` in degrees (north and east positive).
+    pub fn at(unix_seconds: f64, latitude: f32, longitude: f32) -> Self {
+        let days = days_since_j2000(unix_seconds);
+
+        // Mean longitude, mean anomaly and argument of latitude, with the largest periodic
+        // terms
+        let mean_longitude = (218.316 + 13.176_396 * days).to_radians();
+        let mean_anomaly = (134.963 + 13.064_993 * days).to_radians();
+        let argument_of_latitude = (93.272 + 13.229_350 * days).to_radians();
+        let ecliptic_longitude = mean_longitude + 6.289_f64.to_radians() * mean_anomaly.sin();
+        let ecliptic_latitude = 5.128_f64.to_radians() * argument_of_latitude.sin();
+
+        let (right_ascension, declination) =
+            equatorial(days, ecliptic_longitude, ecliptic_latitude);
+        let sky = Horizontal::at(days, right_ascension, declination, latitude, longitude);
+        // How far the moon has moved ahead of the sun around the ecliptic
+        let elongation = ecliptic_longitude - sun_ecliptic_longitude(days);
+
+        Self {
+            elevation: sky.elevation as f32,
+            azimuth: sky.azimuth as f32,
+            phase: (elongation / TAU).rem_euclid(1.0) as f32,
+        }
+    }
+}
+
+fn days_since_j2000(unix_seconds: f64) -> f64 {
+    unix_seconds / SECONDS_PER_DAY - J2000
+}
+
+/// The sun's ecliptic longitude, from its mean longitude and mean anomaly.
+fn sun_ecliptic_longitude(days: f64) -> f64 {
+    let mean_longitude = (280.460 + 0.985_647_4 * days).to_radians();
+    let mean_anomaly = (357.528 + 0.985_600_3 * days).to_radians();
+    mean_longitude
+        + 1.915_f64.to_radians() * mean_anomaly.sin()
+        + 0.020_f64.to_radians() * (2.0 * mean_anomaly).sin()
+}
+
+/// Converts ecliptic coordinates to right ascension and declination.
+fn equatorial(days: f64, longitude: f64, latitude: f64) -> (f64, f64) {
+    let obliquity = (23.439 - 0.000_000_4 * days).to_radians();
+    let right_ascension = (longitude.sin() * obliquity.cos() - latitude.tan() * obliquity.sin())
+        .atan2(longitude.cos());
+    let declination = (latitude.sin() * obliquity.cos()
+        + latitude.cos() * obliquity.sin() * longitude.sin())
+    .asin();
+    (right_ascension, declination)
+}
+
+/// Where something on the celestial sphere appears from one place on Earth.
+struct Horizontal {
+    elevation: f64,
+    azimuth: f64,
+    hour_angle: f64,
+}
+
+impl Horizontal {
+    fn at(
+        days: f64,
+        right_ascension: f64,
+        declination: f64,
+        latitude: f32,
+        longitude: f32,
+    ) -> Self {
+        // Local sidereal time, then the angle west of the meridian
+        let sidereal = (280.460_618_37 + 360.985_647_366_29 * days).to_radians()
+            + f64::from(longitude).to_radians();
+        let hour_angle = (sidereal - right_ascension + TAU / 2.0).rem_euclid(TAU) - TAU / 2.0;
+
+        let latitude = f64::from(latitude).to_radians();
+        let elevation = (latitude.sin() * declination.sin()
+            + latitude.cos() * declination.cos() * hour_angle.cos())
+        .asin();
+        let azimuth = (-hour_angle.sin())
+            .atan2(declination.tan() * latitude.cos() - latitude.sin() * hour_angle.cos())
+            .rem_euclid(TAU);
+
+        Self {
+            elevation,
+            azimuth,
+            hour_angle,
+        }
+    }
+}
+
+/// Days since January 1st at `unix_seconds`, ignoring leap days. Good to within a day, which
+/// is all the calendar needs.
+pub fn day_of_year(unix_seconds: f64) -> f32 {
+    ((unix_seconds / SECONDS_PER_DAY - YEAR_2000).rem_euclid(DAYS_PER_YEAR)) as f32
+}
+
+/// Seconds since the Unix epoch according to the system clock.
+pub fn now() -> f64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map_or(0.0, |since| since.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-06-20 20:10 UTC, around solar noon in Seattle on the summer solstice.
+    const SOLSTICE_NOON_SEATTLE: f64 = 1_718_914_200.0;
+    /// 2024-03-20 12:07 UTC, solar noon at Greenwich on the day of the March equinox, nine
+    /// hours after the equinox itself.
+    const EQUINOX_NOON_GREENWICH: f64 = 1_710_936_420.0;
+    /// 2024-04-08 18:42 UTC, totality of the solar eclipse in Dallas.
+    const ECLIPSE_DALLAS: f64 = 1_712_601_720.0;
+    /// 2024-04-15 19:13 UTC, first quarter moon.
+    const FIRST_QUARTER: f64 = 1_713_208_380.0;
+    /// 2024-04-23 23:49 UTC, full moon.
+    const FULL_MOON: f64 = 1_713_916_140.0;
+    const SEATTLE: (f32, f32) = (47.6, -122.3);
+    const DALLAS: (f32, f32) = (32.8, -96.8);
+
+    fn degrees(radians: f32) -> f32 {
+        radians.to_degrees()
+    }
+
+    #[test]
+    fn test_solstice_noon_sun_is_high_and_south() {
+        let sun = SolarPosition::at(SOLSTICE_NOON_SEATTLE, SEATTLE.0, SEATTLE.1);
+        // 90 - latitude + axial tilt
+        assert!((degrees(sun.elevation) - 65.8).abs() < 0.5, "{sun:?}");
+        assert!((degrees(sun.azimuth) - 180.0).abs() < 5.0, "{sun:?}");
+        assert!(degrees(sun.hour_angle).abs() < 2.0, "{sun:?}");
+        assert!((degrees(sun.declination) - 23.44).abs() < 0.1, "{sun:?}");
+    }
+
+    #[test]
+    fn test_equinox_sun_is_overhead_at_the_equator() {
+        let sun = SolarPosition::at(EQUINOX_NOON_GREENWICH, 0.0, 0.0);
+        assert!(degrees(sun.elevation) > 89.0, "{sun:?}");
+        assert!(degrees(sun.declination).abs() < 0.3, "{sun:?}");
+    }
+
+    #[test]
+    fn test_sun_rises_in_the_east_and_sets_in_the_west() {
+        let six_hours = 6.0 * 3600.0;
+        let morning = SolarPosition::at(EQUINOX_NOON_GREENWICH - six_hours, 0.0, 0.0);
+        let evening = SolarPosition::at(EQUINOX_NOON_GREENWICH + six_hours, 0.0, 0.0);
+        let midnight = SolarPosition::at(EQUINOX_NOON_GREENWICH + 2.0 * six_hours, 0.0, 0.0);
+        assert!(degrees(morning.elevation).abs() < 1.0);
+        assert!((degrees(morning.azimuth) - 90.0).abs() < 1.0, "{morning:?}");
+        assert!(
+            (degrees(evening.azimuth) - 270.0).abs() < 1.0,
+            "{evening:?}"
+        );
+        assert!(morning.hour_angle < 0.0 && evening.hour_angle > 0.0);
+        assert!(degrees(midnight.elevation) < -89.0, "{midnight:?}");
+    }
+
+    #[test]
+    fn test_moon_phases() {
+        let phase = |unix_seconds| LunarPosition::at(unix_seconds, 0.0, 0.0).phase;
+        let new = phase(ECLIPSE_DALLAS);
+        assert!(!(0.02..=0.98).contains(&new), "{new}");
+        assert!((phase(FIRST_QUARTER) - 0.25).abs() < 0.02);
+        assert!((phase(FULL_MOON) - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_eclipsing_moon_covers_the_sun() {
+        let sun = SolarPosition::at(ECLIPSE_DALLAS, DALLAS.0, DALLAS.1);
+        let moon = LunarPosition::at(ECLIPSE_DALLAS, DALLAS.0, DALLAS.1);
+        let separation = (sun.elevation.sin() * moon.elevation.sin()
+            + sun.elevation.cos() * moon.elevation.cos() * (sun.azimuth - moon.azimuth).cos())
+        .acos();
+        // Within the moon's parallax, which geocentric coordinates leave out
+        assert!(degrees(separation) < 1.5, "{sun:?} {moon:?}");
+    }
+
+    #[test]
+    fn test_day_of_year() {
+        assert!((day_of_year(SOLSTICE_NOON_SEATTLE) - 171.8).abs() < 1.0);
+        assert!((day_of_year(EQUINOX_NOON_GREENWICH) - 79.5).abs() < 1.0);
+    }
+}
@@ -1,20 +1,226 @@
-use crate::GameState;
-use crate::loading::AudioAssets;
+//! The ambient soundscape: looping layers of sound under the birds, each faded in and out by
+//! its own volume envelope as the day turns and the weather changes. Quiet air at dawn gives
+//! way to rustling leaves by day, a warmer hush at dusk and a low rumble at night, with the
+//! wind, a creek, and traffic carrying from the distant city on top.
+//!
+//! Rain is mixed by the weather, see [`crate::weather`].
+
+use std::time::Duration;
+
 use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
 
+use crate::GameState;
+use crate::loading::AudioAssets;
+use crate::scene::DayClock;
+use crate::terrain::smoothstep;
+use crate::weather::{Weather, WeatherConditions};
+
 pub struct InternalAudioPlugin;
 
 impl Plugin for InternalAudioPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((AudioPlugin, SpatialAudioPlugin))
-            .add_systems(OnEnter(GameState::Playing), start_ambient);
+            .add_systems(OnEnter(GameState::Playing), start_soundscape)
+            .add_systems(Update, mix_soundscape.run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// Volume of a sound that can't be heard, in decibels.
+pub(crate) const SILENT: f32 = -60.0;
+/// How long each volume change takes. Long enough that the beds blend into each other.
+const CROSSFADE: Duration = Duration::from_secs(2);
+/// Layers are only retuned once their volume has changed by this many decibels.
+const AUDIBLE_CHANGE: f32 = 0.5;
+
+/// Loudest each layer gets, as amplitudes.
+const BED_VOLUME: f32 = 0.3;
+const WIND_VOLUME: f32 = 0.45;
+const CREEK_VOLUME: f32 = 0.15;
+const TRAFFIC_VOLUME: f32 = 0.06;
+
+/// Loudness in decibels of an amplitude from 0 to 1.
+pub(crate) fn decibels(amplitude: f32) -> f32 {
+    (20.0 * amplitude.log10()).max(SILENT)
+}
+
+/// One looping layer of the soundscape.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Layer {
+    Dawn,
+    Day,
+    Dusk,
+    Night,
+    Wind,
+    Creek,
+    Traffic,
+}
+
+impl Layer {
+    const ALL: [Self; 7] = [
+        Self::Dawn,
+        Self::Day,
+        Self::Dusk,
+        Self::Night,
+        Self::Wind,
+        Self::Creek,
+        Self::Traffic,
+    ];
+
+    fn source(self, audio_assets: &AudioAssets) -> Handle<AudioSource> {
+        match self {
+            Self::Dawn => audio_assets.dawn.clone(),
+            Self::Day => audio_assets.day.clone(),
+            Self::Dusk => audio_assets.dusk.clone(),
+            Self::Night => audio_assets.night.clone(),
+            Self::Wind => audio_assets.wind.clone(),
+            Self::Creek => audio_assets.creek.clone(),
+            Self::Traffic => audio_assets.traffic.clone(),
+        }
+    }
+
+    /// How loud the layer is, as an amplitude, at this point in the day (see
+    /// [`DayClock::progress`]) and in this weather.
+    fn volume(self, progress: f32, conditions: &WeatherConditions) -> f32 {
+        // Rain drowns out the quieter beds
+        let masked = 1.0 - 0.6 * conditions.rain;
+        match self {
+            Self::Dawn => BED_VOLUME * time_of_day(progress, 0.0, 0.03, 0.1) * masked,
+            Self::Day => BED_VOLUME * time_of_day(progress, 0.25, 0.17, 0.25) * masked,
+            Self::Dusk => BED_VOLUME * time_of_day(progress, 0.5, 0.03, 0.1) * masked,
+            Self::Night => BED_VOLUME * time_of_day(progress, 0.75, 0.17, 0.25) * masked,
+            Self::Wind => WIND_VOLUME * conditions.wind,
+            // Runs higher while it rains
+            Self::Creek => CREEK_VOLUME * (0.6 + 0.4 * conditions.rain),
+            // Carries farthest in the still of the night, and wind and rain cover it up
+            Self::Traffic => {
+                let night = time_of_day(progress, 0.75, 0.2, 0.3);
+                let covered = (1.0 - conditions.wind) * masked;
+                TRAFFIC_VOLUME * (0.3 + 0.7 * night) * covered
+            }
+        }
+    }
+}
+
+/// 1 within `full` of the `center` of the day, fading to 0 at `edge` either side. Wraps
+/// around midnight, so dawn can center on sunrise at 0.
+fn time_of_day(progress: f32, center: f32, full: f32, edge: f32) -> f32 {
+    let offset = (progress - center).rem_euclid(1.0);
+    let distance = offset.min(1.0 - offset);
+    1.0 - smoothstep(full, edge, distance)
+}
+
+/// The playing layers and the volume each was last set to, in decibels.
+#[derive(Resource)]
+struct Soundscape {
+    layers: Vec<(Layer, Handle<AudioInstance>, f32)>,
+}
+
+/// Starts every layer silent; [`mix_soundscape`] fades them in.
+fn start_soundscape(mut commands: Commands, audio_assets: Res<AudioAssets>, audio: Res<Audio>) {
+    let layers = Layer::ALL
+        .into_iter()
+        .map(|layer| {
+            let handle = audio
+                .play(layer.source(&audio_assets))
+                .looped()
+                .with_volume(Decibels(SILENT))
+                .handle();
+            (layer, handle, SILENT)
+        })
+        .collect();
+    commands.insert_resource(Soundscape { layers });
+}
+
+/// Crossfades the layers toward their volume for the time of day and the weather.
+fn mix_soundscape(
+    day_clock: Res<DayClock>,
+    weather: Res<Weather>,
+    soundscape: Option<ResMut<Soundscape>>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+) {
+    let Some(mut soundscape) = soundscape else {
+        return;
+    };
+    let progress = day_clock.progress();
+    let conditions = weather.conditions();
+    let tween = AudioTween::linear(CROSSFADE);
+
+    for (layer, handle, last) in soundscape.layers.iter_mut() {
+        let volume = decibels(layer.volume(progress, &conditions));
+        if (volume - *last).abs() < AUDIBLE_CHANGE {
+            continue;
+        }
+        if let Some(instance) = instances.get_mut(handle) {
+            instance.set_decibels(Decibels(volume), tween.clone());
+            *last = volume;
+        }
     }
 }
 
-fn start_ambient(audio_assets: Res<AudioAssets>, audio: Res<Audio>) {
-    audio
-        .play(audio_assets.ambient.clone())
-        .looped()
-        .with_volume(0.015);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::WeatherKind;
+
+    const SUNRISE: f32 = 0.0;
+    const NOON: f32 = 0.25;
+    const SUNSET: f32 = 0.5;
+    const MIDNIGHT: f32 = 0.75;
+
+    /// The loudest of the dawn, day, dusk and night beds.
+    fn loudest_bed(progress: f32) -> Layer {
+        let clear = WeatherKind::Clear.conditions();
+        [Layer::Dawn, Layer::Day, Layer::Dusk, Layer::Night]
+            .into_iter()
+            .max_by(|a, b| {
+                a.volume(progress, &clear)
+                    .total_cmp(&b.volume(progress, &clear))
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_beds_follow_the_day() {
+        assert_eq!(loudest_bed(SUNRISE), Layer::Dawn);
+        assert_eq!(loudest_bed(0.98), Layer::Dawn, "Dawn starts before sunrise");
+        assert_eq!(loudest_bed(NOON), Layer::Day);
+        assert_eq!(loudest_bed(SUNSET), Layer::Dusk);
+        assert_eq!(loudest_bed(MIDNIGHT), Layer::Night);
+    }
+
+    #[test]
+    fn test_beds_crossfade() {
+        // Some bed is always playing, and the hand-off never drops out
+        let clear = WeatherKind::Clear.conditions();
+        for step in 0..200 {
+            let progress = step as f32 / 200.0;
+            let total: f32 = [Layer::Dawn, Layer::Day, Layer::Dusk, Layer::Night]
+                .into_iter()
+                .map(|layer| layer.volume(progress, &clear))
+                .sum();
+            assert!(total > BED_VOLUME * 0.5, "{progress}: {total}");
+        }
+    }
+
+    #[test]
+    fn test_weather_in_the_mix() {
+        let clear = WeatherKind::Clear.conditions();
+        let windy = WeatherKind::Windy.conditions();
+        let storm = WeatherKind::HeavyRain.conditions();
+        assert!(Layer::Wind.volume(NOON, &windy) > Layer::Wind.volume(NOON, &clear));
+        assert!(Layer::Creek.volume(NOON, &storm) > Layer::Creek.volume(NOON, &clear));
+        assert!(
+            Layer::Traffic.volume(MIDNIGHT, &windy) < Layer::Traffic.volume(MIDNIGHT, &clear),
+            "Wind covers the traffic"
+        );
+        assert!(Layer::Traffic.volume(MIDNIGHT, &clear) > Layer::Traffic.volume(NOON, &clear));
+    }
+
+    #[test]
+    fn test_decibels() {
+        assert_eq!(decibels(1.0), 0.0);
+        assert!((decibels(0.5) + 6.0).abs() < 0.1);
+        assert_eq!(decibels(0.0), SILENT);
+    }
 }
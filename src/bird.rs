@@ -2,307 +2,191 @@ use bevy::prelude::*;
 use bevy_kira_audio::SpatialRadius;
 use bevy_kira_audio::prelude::*;
 use rand::Rng;
+use rand::seq::IndexedRandom;
 
 use crate::GameState;
-use crate::loading::AudioAssets;
-use crate::scene::{DayClock, Tree};
+use crate::movement::{PhysicalTranslation, PreviousPhysicalTranslation, Velocity};
+use crate::scene::{DayClock, Destination, FoodSource, Moon, PerchPoints, SeasonClock, Tree};
+use crate::species::{BirdSpecies, SpeciesCatalog};
+use crate::weather::{HEAVY_RAIN, Weather};
+
+mod alarm;
+mod avoidance;
+mod behaviors;
+mod body;
+mod climbing;
+mod flight;
+mod flocking;
+mod hawking;
+mod mind;
+mod needs;
+mod perching;
+mod scolding;
+mod song;
+mod voices;
+
+use alarm::{Fear, flee, give_alarm_calls, hear_alarm, hide, mob, score_threats, sense_predators};
+use avoidance::avoid_obstacles;
+use behaviors::{
+    PERCH, depart, drink, forage, perch, rest, score_depart, score_drink, score_forage,
+    score_perch, score_rest, score_shelter, shelter,
+};
+use body::{BirdBodies, animate_birds, attach_body, face_travel_direction, setup_bird_bodies};
+use climbing::{DRUM_INTERVAL, Drumming, HOP_INTERVAL, climb_trunks};
+use flight::{chase_targets, fly_along_paths, plan_flight_paths};
+use flocking::{FollowsLeader, apply_flocking, sync_flock_members};
+use hawking::{hawk, score_hawk};
+use mind::{AddBehavior, Mind, MindPlugin, MindSettings, MindSystems, decide};
+use needs::{Needs, update_needs};
+use perching::{Perch, Spots, release_perch, reserve_perch_on, reserve_random_perch};
+use scolding::{scold, score_scold};
+use song::{Answering, SING, hear_song, score_sing, sing};
+use voices::{CALL_RADIUS, VoiceManager, Voices, manage_voices};
 
 pub struct BirdPlugin;
 
 impl Plugin for BirdPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<DidFixedTimestepRunThisFrame>()
-            .init_resource::<BirdSpawnTimer>()
-            .add_systems(FixedPreUpdate, set_fixed_timestep_flag)
-            .add_systems(PreUpdate, clear_fixed_timestep_flag)
+        app.init_resource::<BirdSpawnTimer>()
+            .init_resource::<VoiceManager>()
+            .add_observer(release_perch)
+            .add_observer(hear_song)
+            .add_observer(hear_alarm)
+            .add_systems(Startup, setup_bird_bodies)
+            .add_plugins(MindPlugin)
+            .add_behavior(score_perch, perch)
+            .add_behavior(score_rest, rest)
+            .add_behavior(score_forage, forage)
+            .add_behavior(score_hawk, hawk)
+            .add_behavior(score_drink, drink)
+            .add_behavior(score_depart, depart)
+            .add_behavior(score_sing, sing)
+            .add_behavior(score_scold, scold)
+            .add_behavior(score_shelter, shelter)
+            .add_behavior(score_threats, (flee, hide, mob))
             .add_systems(
-                FixedUpdate,
-                advance_bird_physics.run_if(in_state(GameState::Playing)),
-            )
-            .add_systems(
-                RunFixedMainLoop,
-                interpolate_bird_transforms
-                    .in_set(RunFixedMainLoopSystems::AfterFixedMainLoop)
-                    .run_if(in_state(GameState::Playing)),
+                Update,
+                hush_changed_minds.in_set(MindSystems::Decide).after(decide),
             )
             .add_systems(
                 Update,
                 (
                     spawn_birds,
-                    bird_ai,
-                    send_inactive_birds_home,
+                    (sense_predators, update_needs, land_birds)
+                        .chain()
+                        .before(MindSystems::Think),
+                    (
+                        give_alarm_calls,
+                        climb_trunks,
+                        sync_flock_members,
+                        plan_flight_paths,
+                        fly_along_paths,
+                        chase_targets,
+                        apply_flocking,
+                        avoid_obstacles,
+                        (animate_birds, face_travel_direction),
+                    )
+                        .chain()
+                        .after(MindSystems::Act),
                     despawn_distant_birds,
+                    manage_voices.after(MindSystems::Act),
                 )
                     .run_if(in_state(GameState::Playing)),
             );
     }
 }
 
-// -- Fixed timestep flag --
-
-#[derive(Resource, Default, Deref, DerefMut)]
-struct DidFixedTimestepRunThisFrame(bool);
-
-fn clear_fixed_timestep_flag(mut flag: ResMut<DidFixedTimestepRunThisFrame>) {
-    flag.0 = false;
-}
-
-fn set_fixed_timestep_flag(mut flag: ResMut<DidFixedTimestepRunThisFrame>) {
-    flag.0 = true;
-}
+// -- Bird components --
 
-// -- Species --
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum BirdSpecies {
-    // Diurnal
-    MourningDove,
-    DownyWoodpecker,
-    NorthernFlicker,
-    StellersJay,
-    CaliforniaScrubJay,
-    BlackCappedChickadee,
-    WhiteBreastedNuthatch,
-    WhiteCrownedSparrow,
-    RedWingedBlackbird,
-    CassinsFinch,
-    HouseFinch,
-    PineSiskin,
-    AmericanGoldfinch,
-    EveningGrosbeak,
-    // Nocturnal
-    GreatHornedOwl,
-    BarnOwl,
-    WesternScreechOwl,
+#[derive(Component, Debug)]
+pub struct Bird {
+    species: BirdSpecies,
+    /// Spots visited so far; the bird leaves the clearing after `max_visits`
+    visits: u32,
+    max_visits: u32,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum ActivityPeriod {
-    /// Active from sunrise through sunset, peak at dawn/dusk. Most songbirds.
-    Diurnal,
-    /// Strictly daytime -- needs good light. Woodpeckers, visual foragers.
-    StrictlyDiurnal,
-    /// Dawn and dusk specialists, quiet midday. Some sparrows, finches.
-    Crepuscular,
-    /// Active from dusk through dawn. Owls.
-    Nocturnal,
+/// What a bird's body is doing. Behaviors (see [`mind`]) decide which state a bird is in;
+/// flight, flocking and animation follow from it.
+#[derive(Component, Debug)]
+enum BirdState {
+    Approaching {
+        target: Vec3,
+    },
+    Perching {
+        timer: Timer,
+    },
+    Vocalizing {
+        timer: Timer,
+    },
+    /// Feeding at a seed patch, the feeder or a tree trunk
+    Foraging {
+        timer: Timer,
+    },
+    /// Hopping up a tree trunk while feeding, stopping to drum. Woodpeckers.
+    Climbing {
+        timer: Timer,
+        hop: Timer,
+        drum: Timer,
+    },
+    /// Drinking at the bird bath
+    Drinking {
+        timer: Timer,
+    },
+    FlyingToNext {
+        target: Vec3,
+    },
+    Departing {
+        target: Vec3,
+    },
+    /// Heading for cover in a canopy, away from a predator
+    Fleeing {
+        target: Vec3,
+    },
+    /// Sitting still and silent until the danger has passed
+    Hiding {
+        timer: Timer,
+    },
+    /// Swooping at a predator to drive it off
+    Mobbing {
+        predator: Entity,
+        target: Vec3,
+        timer: Timer,
+    },
+    /// Chasing an insect to catch it on the wing
+    Hawking {
+        insect: Entity,
+        target: Vec3,
+        timer: Timer,
+    },
 }
 
-impl BirdSpecies {
-    // TODO: i think strum has a helper for this
-    const ALL: &[BirdSpecies] = &[
-        BirdSpecies::MourningDove,
-        BirdSpecies::DownyWoodpecker,
-        BirdSpecies::NorthernFlicker,
-        BirdSpecies::StellersJay,
-        BirdSpecies::CaliforniaScrubJay,
-        BirdSpecies::BlackCappedChickadee,
-        BirdSpecies::WhiteBreastedNuthatch,
-        BirdSpecies::WhiteCrownedSparrow,
-        BirdSpecies::RedWingedBlackbird,
-        BirdSpecies::CassinsFinch,
-        BirdSpecies::HouseFinch,
-        BirdSpecies::PineSiskin,
-        BirdSpecies::AmericanGoldfinch,
-        BirdSpecies::EveningGrosbeak,
-        BirdSpecies::GreatHornedOwl,
-        BirdSpecies::BarnOwl,
-        BirdSpecies::WesternScreechOwl,
-    ];
-
-    fn color(&self) -> Color {
-        match self {
-            Self::MourningDove => Color::srgb(0.6, 0.5, 0.4),
-            Self::DownyWoodpecker => Color::srgb(0.2, 0.2, 0.2),
-            Self::NorthernFlicker => Color::srgb(0.7, 0.5, 0.3),
-            Self::StellersJay => Color::srgb(0.1, 0.2, 0.6),
-            Self::CaliforniaScrubJay => Color::srgb(0.3, 0.4, 0.7),
-            Self::BlackCappedChickadee => Color::srgb(0.8, 0.8, 0.7),
-            Self::WhiteBreastedNuthatch => Color::srgb(0.5, 0.5, 0.6),
-            Self::WhiteCrownedSparrow => Color::srgb(0.6, 0.55, 0.45),
-            Self::RedWingedBlackbird => Color::srgb(0.1, 0.1, 0.1),
-            Self::CassinsFinch => Color::srgb(0.7, 0.3, 0.3),
-            Self::HouseFinch => Color::srgb(0.8, 0.3, 0.2),
-            Self::PineSiskin => Color::srgb(0.6, 0.6, 0.3),
-            Self::AmericanGoldfinch => Color::srgb(0.9, 0.8, 0.1),
-            Self::EveningGrosbeak => Color::srgb(0.7, 0.6, 0.1),
-            Self::GreatHornedOwl => Color::srgb(0.45, 0.35, 0.25),
-            Self::BarnOwl => Color::srgb(0.85, 0.8, 0.7),
-            Self::WesternScreechOwl => Color::srgb(0.5, 0.45, 0.4),
-        }
-    }
-
-    fn radius(&self) -> f32 {
-        match self {
-            Self::MourningDove => 0.25,
-            Self::DownyWoodpecker => 0.18,
-            Self::NorthernFlicker => 0.22,
-            Self::StellersJay => 0.24,
-            Self::CaliforniaScrubJay => 0.22,
-            Self::BlackCappedChickadee => 0.15,
-            Self::WhiteBreastedNuthatch => 0.16,
-            Self::WhiteCrownedSparrow => 0.17,
-            Self::RedWingedBlackbird => 0.20,
-            Self::CassinsFinch => 0.16,
-            Self::HouseFinch => 0.16,
-            Self::PineSiskin => 0.14,
-            Self::AmericanGoldfinch => 0.14,
-            Self::EveningGrosbeak => 0.20,
-            Self::GreatHornedOwl => 0.35,
-            Self::BarnOwl => 0.28,
-            Self::WesternScreechOwl => 0.20,
-        }
-    }
-
-    /// Flight speed in units/second
-    fn speed(&self) -> f32 {
-        match self {
-            Self::MourningDove => 1.5,
-            Self::DownyWoodpecker => 1.0,
-            Self::NorthernFlicker => 1.1,
-            Self::StellersJay => 1.3,
-            Self::CaliforniaScrubJay => 1.2,
-            Self::BlackCappedChickadee => 0.9,
-            Self::WhiteBreastedNuthatch => 0.8,
-            Self::WhiteCrownedSparrow => 1.0,
-            Self::RedWingedBlackbird => 1.4,
-            Self::CassinsFinch => 1.1,
-            Self::HouseFinch => 1.0,
-            Self::PineSiskin => 0.9,
-            Self::AmericanGoldfinch => 1.0,
-            Self::EveningGrosbeak => 1.2,
-            Self::GreatHornedOwl => 1.0,
-            Self::BarnOwl => 1.3,
-            Self::WesternScreechOwl => 0.8,
-        }
-    }
-
-    fn activity(&self) -> ActivityPeriod {
-        match self {
-            // Woodpeckers need daylight for visual foraging and drumming
-            Self::DownyWoodpecker | Self::NorthernFlicker => ActivityPeriod::StrictlyDiurnal,
-            // Dawn/dusk chorus singers
-            Self::WhiteCrownedSparrow | Self::MourningDove => ActivityPeriod::Crepuscular,
-            // Owls
-            Self::GreatHornedOwl | Self::BarnOwl | Self::WesternScreechOwl => {
-                ActivityPeriod::Nocturnal
-            }
-            // Most songbirds are active throughout the day
-            _ => ActivityPeriod::Diurnal,
-        }
-    }
-
-    /// Returns true if this species would be active at the given sun elevation.
-    /// sun_elevation: positive = daytime, negative = nighttime
-    fn is_active(&self, sun_elevation: f32) -> bool {
-        match self.activity() {
-            ActivityPeriod::Diurnal => {
-                // Active when sun is above horizon (with a little twilight grace)
-                sun_elevation > -0.02
-            }
-            ActivityPeriod::StrictlyDiurnal => {
-                // Need good light -- sun well above horizon
-                sun_elevation > 0.15
-            }
-            ActivityPeriod::Crepuscular => {
-                // Active during day, but especially dawn/dusk
-                sun_elevation > -0.04
-            }
-            ActivityPeriod::Nocturnal => {
-                // Active from dusk through dawn -- when sun is below horizon or near it
-                sun_elevation < 0.1
-            }
-        }
+impl BirdState {
+    fn is_flying(&self) -> bool {
+        self.target().is_some()
     }
 
-    fn call_handles(
-        &self,
-        audio_assets: &AudioAssets,
-    ) -> Vec<Handle<bevy_kira_audio::AudioSource>> {
+    fn target(&self) -> Option<Vec3> {
         match self {
-            Self::MourningDove => vec![audio_assets.mourning_dove_song.clone()],
-            Self::DownyWoodpecker => vec![
-                audio_assets.downy_woodpecker_calls.clone(),
-                audio_assets.downy_woodpecker_drum.clone(),
-            ],
-            Self::NorthernFlicker => vec![
-                audio_assets.northern_flicker_call.clone(),
-                audio_assets.northern_flicker_call_2.clone(),
-                audio_assets.northern_flicker_drum.clone(),
-            ],
-            Self::StellersJay => vec![
-                audio_assets.stellers_jay_call.clone(),
-                audio_assets.stellers_jay_calls.clone(),
-            ],
-            Self::CaliforniaScrubJay => vec![audio_assets.california_scrub_jay_calls.clone()],
-            Self::BlackCappedChickadee => vec![
-                audio_assets.black_capped_chickadee_song.clone(),
-                audio_assets.black_capped_chickadee_call.clone(),
-            ],
-            Self::WhiteBreastedNuthatch => vec![
-                audio_assets.white_breasted_nuthatch_song.clone(),
-                audio_assets.white_breasted_nuthatch_call_1.clone(),
-                audio_assets.white_breasted_nuthatch_call_2.clone(),
-            ],
-            Self::WhiteCrownedSparrow => vec![
-                audio_assets.white_crowned_sparrow_song_1.clone(),
-                audio_assets.white_crowned_sparrow_song_2.clone(),
-                audio_assets.white_crowned_sparrow_call.clone(),
-            ],
-            Self::RedWingedBlackbird => vec![
-                audio_assets.red_winged_blackbird_song.clone(),
-                audio_assets.red_winged_blackbird_calls.clone(),
-            ],
-            Self::CassinsFinch => vec![
-                audio_assets.cassins_finch_song.clone(),
-                audio_assets.cassins_finch_call.clone(),
-            ],
-            Self::HouseFinch => vec![
-                audio_assets.house_finch_song.clone(),
-                audio_assets.house_finch_call.clone(),
-            ],
-            Self::PineSiskin => vec![audio_assets.pine_siskin_song_calls.clone()],
-            Self::AmericanGoldfinch => vec![audio_assets.american_goldfinch_song_call.clone()],
-            Self::EveningGrosbeak => vec![audio_assets.evening_grosbeak_calls.clone()],
-            Self::GreatHornedOwl => vec![audio_assets.great_horned_owl_call.clone()],
-            Self::BarnOwl => vec![audio_assets.barn_owl_call.clone()],
-            Self::WesternScreechOwl => vec![audio_assets.western_screech_owl_call.clone()],
+            Self::Approaching { target }
+            | Self::FlyingToNext { target }
+            | Self::Departing { target }
+            | Self::Fleeing { target }
+            | Self::Mobbing { target, .. }
+            | Self::Hawking { target, .. } => Some(*target),
+            Self::Perching { .. }
+            | Self::Vocalizing { .. }
+            | Self::Foraging { .. }
+            | Self::Climbing { .. }
+            | Self::Drinking { .. }
+            | Self::Hiding { .. } => None,
         }
     }
 }
 
-// -- Bird components --
-
+/// The call a bird is giving, while it has a voice.
 #[derive(Component)]
-pub struct Bird {
-    species: BirdSpecies,
-    trees_visited: u32,
-    max_trees: u32,
-}
-
-#[derive(Component)]
-enum BirdState {
-    Approaching { target: Vec3 },
-    Perching { timer: Timer },
-    Vocalizing { timer: Timer },
-    FlyingToNext { target: Vec3 },
-    Departing { target: Vec3 },
-}
-
-#[derive(Component, Default, Deref, DerefMut)]
-struct PhysicalTranslation(Vec3);
-
-#[derive(Component, Default, Deref, DerefMut)]
-struct PreviousPhysicalTranslation(Vec3);
-
-#[derive(Component, Default, Deref, DerefMut)]
-struct Velocity(Vec3);
-
-#[derive(Component)]
-struct BirdCallHandles(Vec<Handle<bevy_kira_audio::AudioSource>>);
-
-#[derive(Component)]
-struct ActiveCall(#[allow(dead_code)] Handle<AudioInstance>);
+struct ActiveCall(Handle<AudioInstance>);
 
 // -- Spawn timer --
 
@@ -321,16 +205,30 @@ impl Default for BirdSpawnTimer {
 
 const MAX_BIRDS: usize = 8;
 
+/// Predators show up this often, regardless of whether they sing.
+const PREDATOR_ARRIVAL_RATE: f32 = 0.1;
+
+/// Seconds until the next arrival. Birds arrive about twice as often during the dawn chorus
+/// (`chorus` = 1) and half as often when every active species is silent (`chorus` = 0).
+fn spawn_interval(rng: &mut impl Rng, chorus: f32) -> f32 {
+    rng.random_range(8.0..15.0) / (0.5 + 1.5 * chorus)
+}
+
 #[allow(clippy::too_many_arguments)]
 fn spawn_birds(
     mut commands: Commands,
     time: Res<Time>,
     mut spawn_timer: ResMut<BirdSpawnTimer>,
-    audio_assets: Res<AudioAssets>,
+    catalog: Res<SpeciesCatalog>,
     day_clock: Res<DayClock>,
+    season: Res<SeasonClock>,
+    moon: Res<Moon>,
+    weather: Res<Weather>,
     birds: Query<&Bird>,
-    trees: Query<&Transform, With<Tree>>,
-    mut meshes: ResMut<Assets<Mesh>>,
+    tree_transforms: Query<&Transform, With<Tree>>,
+    mut spots: Spots,
+    bodies: Res<BirdBodies>,
+    mind_settings: Res<MindSettings>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
     spawn_timer.timer.tick(time.delta());
@@ -339,298 +237,289 @@ fn spawn_birds(
     }
 
     let sun_elev = day_clock.sun_elevation();
+    let progress = day_clock.progress();
 
+    // Nothing new arrives in a downpour
     let bird_count = birds.iter().count();
-    if bird_count >= MAX_BIRDS {
+    if bird_count >= MAX_BIRDS || weather.conditions().rain >= HEAVY_RAIN {
         let mut rng = rand::rng();
         spawn_timer.timer = Timer::from_seconds(rng.random_range(5.0..10.0), TimerMode::Once);
         return;
     }
 
-    let tree_positions: Vec<Vec3> = trees.iter().map(|t| t.translation).collect();
-    if tree_positions.is_empty() {
-        return;
-    }
-
     let mut rng = rand::rng();
 
-    // Filter to species active at current time of day
-    let active_species: Vec<BirdSpecies> = BirdSpecies::ALL
+    // Birds arrive at a tree; only trees with a free perch slot can be chosen
+    let open_trees: Vec<Entity> = spots
         .iter()
-        .copied()
-        .filter(|s| s.is_active(sun_elev))
+        .filter(|(_, perches, destination)| {
+            **destination == Destination::Canopy && perches.has_free_slot()
+        })
+        .map(|(tree, _, _)| tree)
         .collect();
-    if active_species.is_empty() {
+    let Some(&target_tree) = open_trees.choose(&mut rng) else {
         spawn_timer.timer = Timer::from_seconds(rng.random_range(3.0..6.0), TimerMode::Once);
         return;
-    }
-
-    let species = active_species[rng.random_range(0..active_species.len())];
-    let call_handles = species.call_handles(&audio_assets);
+    };
+    let Ok(tree_transform) = tree_transforms.get(target_tree) else {
+        return;
+    };
 
-    // Pick a target tree
-    let target_tree = tree_positions[rng.random_range(0..tree_positions.len())];
+    // Species around this time of year and active at this time of day, weighted by how much
+    // they sing right now. Only one predator hunts the clearing at a time.
+    let song_season = season.song_season();
+    let predator_present = birds.iter().any(|bird| catalog[bird.species].predator);
+    let active_species: Vec<(BirdSpecies, f32)> = catalog
+        .iter()
+        .filter(|(_, data)| {
+            data.is_present(season.day)
+                && data.is_active(sun_elev, moon.brightness())
+                && !(data.predator && predator_present)
+        })
+        .map(|(id, data)| {
+            let rate = data.singing_rate(progress, song_season);
+            if data.predator {
+                (id, rate.max(PREDATOR_ARRIVAL_RATE))
+            } else {
+                (id, rate)
+            }
+        })
+        .collect();
+    let Ok(&(species, _)) = active_species.choose_weighted(&mut rng, |(_, rate)| *rate) else {
+        spawn_timer.timer = Timer::from_seconds(rng.random_range(3.0..6.0), TimerMode::Once);
+        return;
+    };
+    let species_data = &catalog[species];
+    let chorus = active_species
+        .iter()
+        .map(|(_, rate)| *rate)
+        .fold(0.0, f32::max);
 
     // Spawn from a random edge outside the visible area
     let angle: f32 = rng.random_range(0.0..std::f32::consts::TAU);
     let spawn_distance = 25.0;
     let spawn_pos = Vec3::new(
         angle.cos() * spawn_distance,
-        target_tree.y + rng.random_range(-0.5..1.0),
+        tree_transform.translation.y + rng.random_range(-0.5..1.0),
         angle.sin() * spawn_distance,
     );
 
-    let bird_mesh = meshes.add(Sphere::new(species.radius()).mesh().uv(12, 8));
     let bird_material = materials.add(StandardMaterial {
-        base_color: species.color(),
+        base_color: species_data.color,
         perceptual_roughness: 0.7,
         ..default()
     });
 
-    let max_trees = rng.random_range(2..=4);
+    // Social species arrive as a flock, but never push us past MAX_BIRDS
+    let flock_size = if species_data.social {
+        rng.random_range(flocking::FLOCK_SIZE)
+            .min(MAX_BIRDS - bird_count)
+    } else {
+        1
+    };
+
+    let max_visits = rng.random_range(3..=6);
+    let mut leader = None;
+
+    for _ in 0..flock_size {
+        let offset = if leader.is_some() {
+            Vec3::new(
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-0.3..0.3),
+                rng.random_range(-1.0..1.0),
+            )
+        } else {
+            Vec3::ZERO
+        };
+        let pos = spawn_pos + offset;
+
+        // Flock members share the leader's tree while it has room
+        let entity = commands.spawn_empty().id();
+        let Some((perch, target)) = reserve_perch_on(&mut rng, &mut spots, entity, target_tree)
+            .or_else(|| reserve_random_perch(&mut rng, &mut spots, entity, None))
+        else {
+            commands.entity(entity).despawn();
+            break;
+        };
+
+        let mut bird = commands.entity(entity);
+        bird.insert((
+            Name::new(species_data.name.clone()),
+            Transform::from_translation(pos),
+            Bird {
+                species,
+                visits: 0,
+                max_visits,
+            },
+            BirdState::Approaching { target },
+            Mind::doing(PERCH, &mind_settings, &mut rng),
+            perch,
+            PhysicalTranslation(pos),
+            PreviousPhysicalTranslation(pos),
+            Velocity::default(),
+            Fear::default(),
+            Needs::arriving(&mut rng),
+            SpatialAudioEmitter { instances: vec![] },
+            SpatialRadius {
+                radius: CALL_RADIUS,
+            },
+        ));
+        attach_body(
+            &mut bird,
+            &bodies,
+            species_data.size_class(),
+            bird_material.clone(),
+        );
 
-    commands.spawn((
-        Mesh3d(bird_mesh),
-        MeshMaterial3d(bird_material),
-        Transform::from_translation(spawn_pos),
-        Bird {
-            species,
-            trees_visited: 0,
-            max_trees,
-        },
-        BirdState::Approaching {
-            target: target_tree,
-        },
-        PhysicalTranslation(spawn_pos),
-        PreviousPhysicalTranslation(spawn_pos),
-        Velocity::default(),
-        BirdCallHandles(call_handles),
-        SpatialAudioEmitter { instances: vec![] },
-        SpatialRadius { radius: 60.0 },
-    ));
+        match leader {
+            Some(leader) => {
+                bird.insert(FollowsLeader(leader));
+            }
+            None => leader = Some(bird.id()),
+        }
+    }
 
     // Reset spawn timer with random interval
-    spawn_timer.timer = Timer::from_seconds(rng.random_range(8.0..15.0), TimerMode::Once);
+    spawn_timer.timer = Timer::from_seconds(spawn_interval(&mut rng, chorus), TimerMode::Once);
 }
 
-// -- AI --
+// -- Landing --
 
-fn bird_ai(
-    mut commands: Commands,
-    time: Res<Time>,
-    audio: Res<Audio>,
-    trees: Query<&Transform, With<Tree>>,
+/// Birds that reach their spot settle in to whatever it offers, and birds fleeing a predator
+/// hide once they reach cover. Settled birds hold still, or ride their branch as it sways.
+fn land_birds(
+    catalog: Res<SpeciesCatalog>,
+    spots: Query<(&Destination, &PerchPoints)>,
     mut birds: Query<(
-        Entity,
-        &mut Bird,
+        &Bird,
         &mut BirdState,
         &mut Velocity,
         &PhysicalTranslation,
-        &BirdCallHandles,
-        &mut SpatialAudioEmitter,
+        Option<&Perch>,
     )>,
-    mut audio_instances: ResMut<Assets<AudioInstance>>,
 ) {
-    let tree_positions: Vec<Vec3> = trees.iter().map(|t| t.translation).collect();
-    if tree_positions.is_empty() {
-        return;
-    }
-
     let mut rng = rand::rng();
 
-    for (entity, mut bird, mut state, mut velocity, phys_pos, call_handles, mut emitter) in
-        birds.iter_mut()
-    {
-        match state.as_mut() {
-            BirdState::Approaching { target } | BirdState::FlyingToNext { target } => {
-                let to_target = *target - phys_pos.0;
-                let distance = to_target.length();
-
-                if distance < 0.5 {
-                    // Arrived at tree
-                    velocity.0 = Vec3::ZERO;
-                    let perch_time = rng.random_range(1.0..3.0);
-                    *state = BirdState::Perching {
-                        timer: Timer::from_seconds(perch_time, TimerMode::Once),
-                    };
-                } else {
-                    let direction = to_target.normalize();
-                    // Gentle sine wave on Y for flapping feel
-                    let flap_offset =
-                        (time.elapsed_secs() * 4.0).sin() * 0.3 * bird.species.speed();
-                    velocity.0 =
-                        direction * bird.species.speed() + Vec3::new(0.0, flap_offset, 0.0);
-                }
-            }
-
-            BirdState::Perching { timer } => {
-                timer.tick(time.delta());
-                velocity.0 = Vec3::ZERO;
-
-                if timer.is_finished() {
-                    // Start vocalizing
-                    let call_idx = rng.random_range(0..call_handles.0.len());
-                    let handle = audio
-                        .play(call_handles.0[call_idx].clone())
-                        .with_volume(1.0)
-                        .handle();
-                    emitter.instances.push(handle.clone());
-                    commands.entity(entity).insert(ActiveCall(handle));
-
-                    let vocalize_time = rng.random_range(4.0..12.0);
-                    *state = BirdState::Vocalizing {
-                        timer: Timer::from_seconds(vocalize_time, TimerMode::Once),
-                    };
-                }
-            }
-
-            BirdState::Vocalizing { timer } => {
-                timer.tick(time.delta());
-                velocity.0 = Vec3::ZERO;
-
-                if timer.is_finished() {
-                    // Stop the call
-                    for instance_handle in emitter.instances.iter() {
-                        if let Some(instance) = audio_instances.get_mut(instance_handle) {
-                            instance.stop(AudioTween::default());
-                        }
-                    }
-                    emitter.instances.clear();
-                    commands.entity(entity).remove::<ActiveCall>();
-
-                    bird.trees_visited += 1;
-
-                    if bird.trees_visited >= bird.max_trees {
-                        // Depart
-                        let angle: f32 = rng.random_range(0.0..std::f32::consts::TAU);
-                        let depart_target = Vec3::new(
-                            angle.cos() * 30.0,
-                            rng.random_range(3.0..8.0),
-                            angle.sin() * 30.0,
-                        );
-                        *state = BirdState::Departing {
-                            target: depart_target,
-                        };
-                    } else {
-                        // Fly to next tree
-                        let next_tree = tree_positions[rng.random_range(0..tree_positions.len())];
-                        *state = BirdState::FlyingToNext { target: next_tree };
-                    }
-                }
+    for (bird, mut state, mut velocity, position, perch) in birds.iter_mut() {
+        let arrived = state
+            .target()
+            .is_some_and(|target| position.0.distance(target) < ARRIVAL_DISTANCE);
+        match *state {
+            // Velocity along the way comes from `fly_along_paths`
+            BirdState::Approaching { .. } | BirdState::FlyingToNext { .. } if arrived => {
+                let destination = perch
+                    .and_then(|perch| spots.get(perch.spot).ok())
+                    .map(|(destination, _)| *destination);
+                *state = settle(&mut rng, destination, catalog[bird.species].climbs);
             }
-
-            BirdState::Departing { target } => {
-                let to_target = *target - phys_pos.0;
-                let distance = to_target.length();
-
-                if distance < 1.0 {
-                    velocity.0 = Vec3::ZERO;
-                    // Will be cleaned up by despawn_distant_birds
-                } else {
-                    let direction = to_target.normalize();
-                    let flap_offset =
-                        (time.elapsed_secs() * 4.0).sin() * 0.3 * bird.species.speed();
-                    velocity.0 =
-                        direction * bird.species.speed() * 1.2 + Vec3::new(0.0, flap_offset, 0.0);
-                }
+            // Safe in the canopy
+            BirdState::Fleeing { .. } if arrived => {
+                *state = BirdState::Hiding {
+                    timer: Timer::from_seconds(rng.random_range(3.0..6.0), TimerMode::Once),
+                };
             }
+            _ => {}
+        }
+        if !state.is_flying() {
+            let branch = perch.and_then(|perch| {
+                let (_, perches) = spots.get(perch.spot).ok()?;
+                perches
+                    .on_branch(perch.slot)
+                    .then(|| perches.position(perch.slot))
+                    .filter(|at| at.distance(position.0) < SETTLING_DISTANCE)
+            });
+            velocity.0 = branch.map_or(Vec3::ZERO, |at| riding(position.0, at));
         }
     }
 }
 
-// -- Physics --
+/// Velocity that keeps a settled bird on its swaying perch at `at`.
+fn riding(position: Vec3, at: Vec3) -> Vec3 {
+    (at - position) * BRANCH_GRIP
+}
 
-fn advance_bird_physics(
-    fixed_time: Res<Time<Fixed>>,
-    mut query: Query<
-        (
-            &mut PhysicalTranslation,
-            &mut PreviousPhysicalTranslation,
-            &Velocity,
-        ),
-        With<Bird>,
-    >,
+/// Whatever a bird was singing or drumming stops when it changes its mind.
+fn hush_changed_minds(
+    mut commands: Commands,
+    mut birds: Query<(Entity, &Mind, &mut SpatialAudioEmitter)>,
+    mut voices: Voices,
 ) {
-    for (mut current, mut previous, velocity) in query.iter_mut() {
-        previous.0 = current.0;
-        current.0 += velocity.0 * fixed_time.delta_secs();
+    for (entity, mind, mut emitter) in birds.iter_mut() {
+        if !mind.changed() {
+            continue;
+        }
+        voices.hush(&mut emitter);
+        commands.entity(entity).remove::<(ActiveCall, Drumming)>();
+        // Birds that decide to do something else let the song they meant to answer go
+        if !mind.is_doing(SING) {
+            commands.entity(entity).remove::<Answering>();
+        }
     }
 }
 
-fn interpolate_bird_transforms(
-    fixed_time: Res<Time<Fixed>>,
-    mut query: Query<
-        (
-            &mut Transform,
-            &PhysicalTranslation,
-            &PreviousPhysicalTranslation,
-        ),
-        With<Bird>,
-    >,
-) {
-    let alpha = fixed_time.overstep_fraction();
-    for (mut transform, current, previous) in query.iter_mut() {
-        transform.translation = previous.0.lerp(current.0, alpha);
+/// What a bird does after landing at a spot: feed, climb, drink, or perch and maybe sing.
+fn settle(rng: &mut impl Rng, destination: Option<Destination>, climbs: bool) -> BirdState {
+    match destination {
+        Some(Destination::Food(FoodSource::Trunk)) if climbs => BirdState::Climbing {
+            timer: Timer::from_seconds(rng.random_range(8.0..16.0), TimerMode::Once),
+            hop: Timer::from_seconds(rng.random_range(HOP_INTERVAL), TimerMode::Once),
+            drum: Timer::from_seconds(rng.random_range(DRUM_INTERVAL), TimerMode::Once),
+        },
+        Some(Destination::Food(_)) => BirdState::Foraging {
+            timer: Timer::from_seconds(rng.random_range(5.0..12.0), TimerMode::Once),
+        },
+        Some(Destination::Water) => BirdState::Drinking {
+            timer: Timer::from_seconds(rng.random_range(2.0..5.0), TimerMode::Once),
+        },
+        Some(Destination::Canopy) | None => BirdState::Perching {
+            timer: Timer::from_seconds(rng.random_range(1.0..3.0), TimerMode::Once),
+        },
     }
 }
 
-// -- Time-of-day departure --
+/// Birds stop reacting to their neighbors within this distance of their perch.
+const SETTLING_DISTANCE: f32 = 1.0;
+/// A bird this close to its perch has landed.
+const ARRIVAL_DISTANCE: f32 = 0.1;
+/// How quickly a settled bird closes the gap to its perch as the branch sways, per second.
+const BRANCH_GRIP: f32 = 8.0;
 
-fn send_inactive_birds_home(
-    day_clock: Res<DayClock>,
-    mut birds: Query<(&Bird, &mut BirdState, &mut SpatialAudioEmitter)>,
-    mut audio_instances: ResMut<Assets<AudioInstance>>,
-) {
-    let sun_elev = day_clock.sun_elevation();
-
-    for (bird, mut state, mut emitter) in birds.iter_mut() {
-        // Skip birds already departing
-        if matches!(*state, BirdState::Departing { .. }) {
-            continue;
-        }
+/// How strongly a bird still responds to its neighbors, given the remaining distance to its perch.
+fn settling_factor(distance: f32) -> f32 {
+    (distance / SETTLING_DISTANCE).clamp(0.2, 1.0)
+}
 
-        if !bird.species.is_active(sun_elev) {
-            // Stop any active calls
-            for handle in emitter.instances.iter() {
-                if let Some(instance) = audio_instances.get_mut(handle) {
-                    instance.stop(AudioTween::default());
-                }
-            }
-            emitter.instances.clear();
-
-            let mut rng = rand::rng();
-            let angle: f32 = rng.random_range(0.0..std::f32::consts::TAU);
-            *state = BirdState::Departing {
-                target: Vec3::new(
-                    angle.cos() * 30.0,
-                    rng.random_range(3.0..8.0),
-                    angle.sin() * 30.0,
-                ),
-            };
-        }
-    }
+/// A random point outside the visible area to fly off to.
+fn departure_target(rng: &mut impl Rng) -> Vec3 {
+    let angle: f32 = rng.random_range(0.0..std::f32::consts::TAU);
+    Vec3::new(
+        angle.cos() * 30.0,
+        rng.random_range(3.0..8.0),
+        angle.sin() * 30.0,
+    )
 }
 
 // -- Cleanup --
 
 fn despawn_distant_birds(
     mut commands: Commands,
-    birds: Query<(Entity, &PhysicalTranslation, &BirdState), With<Bird>>,
-    mut audio_instances: ResMut<Assets<AudioInstance>>,
-    emitters: Query<&SpatialAudioEmitter>,
+    mut birds: Query<
+        (
+            Entity,
+            &PhysicalTranslation,
+            &BirdState,
+            &mut SpatialAudioEmitter,
+        ),
+        With<Bird>,
+    >,
+    mut voices: Voices,
 ) {
-    for (entity, phys_pos, state) in birds.iter() {
+    for (entity, phys_pos, state, mut emitter) in birds.iter_mut() {
         if let BirdState::Departing { .. } = state {
             let distance = phys_pos.0.length();
             if distance > 28.0 {
                 // Stop any audio before despawning
-                if let Ok(emitter) = emitters.get(entity) {
-                    for handle in &emitter.instances {
-                        if let Some(instance) = audio_instances.get_mut(handle) {
-                            instance.stop(AudioTween::default());
-                        }
-                    }
-                }
+                voices.hush(&mut emitter);
                 commands.entity(entity).despawn();
             }
         }
@@ -641,77 +530,6 @@ fn despawn_distant_birds(
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_all_bird_species_count() {
-        // Ensure ALL species list is complete
-        assert_eq!(BirdSpecies::ALL.len(), 17, "Expected 17 bird species");
-    }
-
-    #[test]
-    fn test_bird_species_variants_unique() {
-        // Verify all species variants are different by counting
-        // (can't use HashSet since BirdSpecies doesn't derive Hash)
-        let count = BirdSpecies::ALL.len();
-        assert!(count > 0, "Should have at least one bird species");
-
-        // Check for duplicates by comparing each pair
-        for i in 0..count {
-            for j in (i + 1)..count {
-                assert_ne!(
-                    BirdSpecies::ALL[i],
-                    BirdSpecies::ALL[j],
-                    "Found duplicate species in ALL array"
-                );
-            }
-        }
-    }
-
-    #[test]
-    fn test_bird_species_has_color() {
-        // Verify each species has a color configured
-        for species in BirdSpecies::ALL {
-            let color = species.color();
-            // Colors should have valid RGB components
-            let linear = color.to_linear();
-            assert!(
-                linear.red >= 0.0 && linear.red <= 1.0,
-                "Species has invalid red component"
-            );
-            assert!(
-                linear.green >= 0.0 && linear.green <= 1.0,
-                "Species has invalid green component"
-            );
-            assert!(
-                linear.blue >= 0.0 && linear.blue <= 1.0,
-                "Species has invalid blue component"
-            );
-        }
-    }
-
-    #[test]
-    fn test_bird_species_has_radius() {
-        // Verify each species has a reasonable radius
-        for species in BirdSpecies::ALL {
-            let radius = species.radius();
-            assert!(
-                radius > 0.0 && radius < 1.0,
-                "Bird radius should be between 0 and 1"
-            );
-        }
-    }
-
-    #[test]
-    fn test_bird_species_has_speed() {
-        // Verify each species has a reasonable flight speed
-        for species in BirdSpecies::ALL {
-            let speed = species.speed();
-            assert!(
-                speed > 0.0 && speed < 10.0,
-                "Bird speed should be reasonable"
-            );
-        }
-    }
-
     #[test]
     fn test_bird_spawn_timer_default() {
         let timer = BirdSpawnTimer::default();
@@ -727,68 +545,13 @@ mod tests {
     }
 
     #[test]
-    fn test_activity_period_variants_exist() {
-        // Verify all activity period variants can be constructed
-        let _diurnal = ActivityPeriod::Diurnal;
-        let _strictly_diurnal = ActivityPeriod::StrictlyDiurnal;
-        let _crepuscular = ActivityPeriod::Crepuscular;
-        let _nocturnal = ActivityPeriod::Nocturnal;
-    }
-
-    #[test]
-    fn test_bird_activity_returns_valid_period() {
-        // Verify each species returns a valid activity period
-        for species in BirdSpecies::ALL {
-            let period = species.activity();
-            match period {
-                ActivityPeriod::Diurnal
-                | ActivityPeriod::StrictlyDiurnal
-                | ActivityPeriod::Crepuscular
-                | ActivityPeriod::Nocturnal => {}
-            }
+    fn test_dawn_chorus_brings_more_birds() {
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            assert!(
+                spawn_interval(&mut rng, 1.0) < spawn_interval(&mut rng, 0.0),
+                "Birds should arrive more often during the chorus"
+            );
         }
     }
-
-    #[test]
-    fn test_nocturnal_birds_exist() {
-        // Verify we have at least some nocturnal birds
-        let nocturnal_count = BirdSpecies::ALL
-            .iter()
-            .filter(|s| matches!(s.activity(), ActivityPeriod::Nocturnal))
-            .count();
-        assert!(
-            nocturnal_count >= 3,
-            "Should have at least 3 nocturnal species (owls)"
-        );
-    }
-
-    #[test]
-    fn test_diurnal_birds_exist() {
-        // Verify we have at least some diurnal birds
-        let diurnal_count = BirdSpecies::ALL
-            .iter()
-            .filter(|s| !matches!(s.activity(), ActivityPeriod::Nocturnal))
-            .count();
-        assert!(
-            diurnal_count > 0,
-            "Should have at least one diurnal species"
-        );
-    }
-
-    #[test]
-    fn test_bird_is_active_logic() {
-        // Test that nocturnal birds are active at night
-        let nocturnal = BirdSpecies::GreatHornedOwl;
-        assert!(
-            nocturnal.is_active(-0.5),
-            "Nocturnal bird should be active at night"
-        );
-
-        // Test that diurnal birds are active during day
-        let diurnal = BirdSpecies::MourningDove;
-        assert!(
-            diurnal.is_active(0.5),
-            "Diurnal bird should be active during day"
-        );
-    }
 }
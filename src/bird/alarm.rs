@@ -0,0 +1,484 @@
+use std::ops::Range;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use rand::Rng;
+use rand::seq::IteratorRandom;
+
+use super::mind::{Behavior, Mind, URGENT};
+use super::perching::{Perch, Spots, reserve_perch_on, reserve_random_perch};
+use super::voices::Voices;
+use super::{ActiveCall, Bird, BirdState, PhysicalTranslation, departure_target};
+use crate::scene::{Destination, Tree};
+use crate::species::{CallKind, SpeciesCatalog};
+
+/// Fly to cover away from a predator and hide there.
+pub(super) const FLEE: Behavior = Behavior("flee");
+/// Freeze in place until the danger has passed.
+pub(super) const HIDE: Behavior = Behavior("hide");
+/// Swoop at a predator to drive it off.
+pub(super) const MOB: Behavior = Behavior("mob");
+
+/// Birds notice predators within this distance.
+const THREAT_RADIUS: f32 = 12.0;
+/// Alarm calls carry about as far as songs.
+const ALARM_RADIUS: f32 = 15.0;
+/// Fear of birds that hear an alarm call without seeing the predator themselves.
+const ALARM_FEAR: f32 = 0.45;
+/// Fear fades by this much per second once the predator is out of sight.
+const FEAR_DECAY: f32 = 0.08;
+
+/// Birds with an alarm call give it above this fear level.
+const ALARM_THRESHOLD: f32 = 0.3;
+/// Seconds between alarm calls from the same bird.
+const ALARM_COOLDOWN: f32 = 6.0;
+/// Perched birds freeze above this fear level, birds in the open head for cover.
+const FREEZE_THRESHOLD: f32 = 0.2;
+/// Perched birds leave for cover above this fear level.
+const FLEE_THRESHOLD: f32 = 0.6;
+/// Hiding birds come out again once their fear drops below this.
+const CALM_THRESHOLD: f32 = 0.1;
+
+/// Fleeing beats freezing when a bird has reason to do both.
+const FLEE_URGE: f32 = URGENT + 0.05;
+/// How much a frightened bird of a mobbing species wants to go after the predator. Not an
+/// emergency, so the bird calls from its perch for a while before it works up the nerve.
+const MOB_URGE: f32 = 0.7;
+const MOB_DURATION: Range<f32> = 6.0..12.0;
+/// Mobbing birds harass the predator from about this distance.
+const MOB_DISTANCE: f32 = 1.5;
+
+/// How frightened a bird is. Raised by seeing a predator or hearing an alarm call, and read
+/// by every behavior that could give the bird away.
+#[derive(Component, Debug, Default)]
+pub(super) struct Fear {
+    /// 0 when calm, 1 with a predator right next to the bird
+    pub level: f32,
+    /// The predator the bird is afraid of, once it knows which one
+    pub predator: Option<Entity>,
+    /// Seconds until the bird may give another alarm call
+    alarm_cooldown: f32,
+}
+
+/// Triggered when a bird gives an alarm call.
+#[derive(Event, Clone, Copy, Debug)]
+pub(super) struct AlarmCall {
+    pub bird: Entity,
+    pub position: Vec3,
+    pub predator: Option<Entity>,
+}
+
+/// Raises each bird's [`Fear`] from the nearest predator in sight, and lets it fade otherwise.
+pub(super) fn sense_predators(
+    time: Res<Time>,
+    catalog: Res<SpeciesCatalog>,
+    mut birds: Query<(Entity, &Bird, &PhysicalTranslation, &mut Fear)>,
+) {
+    let predators: Vec<(Entity, Vec3)> = birds
+        .iter()
+        .filter(|(_, bird, _, _)| catalog[bird.species].predator)
+        .map(|(entity, _, position, _)| (entity, position.0))
+        .collect();
+    let dt = time.delta_secs();
+
+    for (_, bird, position, mut fear) in birds.iter_mut() {
+        if catalog[bird.species].predator {
+            continue;
+        }
+
+        fear.alarm_cooldown = (fear.alarm_cooldown - dt).max(0.0);
+        fear.level = (fear.level - FEAR_DECAY * dt).max(0.0);
+
+        let nearest = predators
+            .iter()
+            .map(|(predator, at)| (*predator, threat(position.0.distance(*at))))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((predator, threat)) = nearest
+            && threat > 0.0
+            && threat >= fear.level
+        {
+            fear.level = threat;
+            fear.predator = Some(predator);
+        }
+        if fear.level == 0.0 {
+            fear.predator = None;
+        }
+    }
+}
+
+/// Fear caused by a predator at the given distance.
+fn threat(distance: f32) -> f32 {
+    (1.0 - distance / THREAT_RADIUS).clamp(0.0, 1.0)
+}
+
+/// Birds within [`ALARM_RADIUS`] of an alarm call become wary of the predator too.
+pub(super) fn hear_alarm(
+    alarm: On<AlarmCall>,
+    catalog: Res<SpeciesCatalog>,
+    mut birds: Query<(Entity, &Bird, &PhysicalTranslation, &mut Fear)>,
+) {
+    for (entity, bird, position, mut fear) in birds.iter_mut() {
+        if entity == alarm.bird
+            || catalog[bird.species].predator
+            || position.0.distance(alarm.position) > ALARM_RADIUS
+        {
+            continue;
+        }
+        if fear.level < ALARM_FEAR {
+            fear.level = ALARM_FEAR;
+        }
+        fear.predator = fear.predator.or(alarm.predator);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Reaction {
+    Stay,
+    Freeze,
+    Flee,
+    Mob,
+}
+
+/// What a bird does about its fear. Only birds going about their business react; hiding,
+/// fleeing, mobbing and departing birds already are.
+fn reaction(state: &BirdState, fear: f32, mobs: bool, predator_known: bool) -> Reaction {
+    let perched = matches!(
+        state,
+        BirdState::Perching { .. }
+            | BirdState::Vocalizing { .. }
+            | BirdState::Foraging { .. }
+            | BirdState::Climbing { .. }
+            | BirdState::Drinking { .. }
+    );
+    let flying = matches!(
+        state,
+        BirdState::Approaching { .. } | BirdState::FlyingToNext { .. }
+    );
+    if !(perched || flying) || fear < FREEZE_THRESHOLD {
+        return Reaction::Stay;
+    }
+
+    if mobs {
+        // Mobbers call from where they are until they work up the nerve, see `MOB_URGE`
+        return if predator_known {
+            Reaction::Mob
+        } else {
+            Reaction::Stay
+        };
+    }
+
+    if flying || (fear > FLEE_THRESHOLD && predator_known) {
+        Reaction::Flee
+    } else {
+        Reaction::Freeze
+    }
+}
+
+/// Scores [`FLEE`], [`HIDE`] and [`MOB`] from each bird's [`Fear`]. Fleeing and freezing are
+/// emergencies; mobbing has to wait out the mind change cooldown.
+pub(super) fn score_threats(
+    catalog: Res<SpeciesCatalog>,
+    mut birds: Query<(&Bird, &BirdState, &Fear, &mut Mind)>,
+    positions: Query<&PhysicalTranslation>,
+) {
+    for (bird, state, fear, mut mind) in birds.iter_mut() {
+        let species = &catalog[bird.species];
+        if !mind.is_thinking() || species.predator {
+            continue;
+        }
+        let predator_known = fear
+            .predator
+            .is_some_and(|predator| positions.contains(predator));
+
+        match reaction(state, fear.level, species.mobs, predator_known) {
+            Reaction::Stay => {}
+            Reaction::Freeze => mind.score(HIDE, URGENT),
+            Reaction::Flee => mind.score(FLEE, FLEE_URGE),
+            Reaction::Mob => mind.score(MOB, MOB_URGE),
+        }
+        // Birds already reacting keep at it until their action is done
+        for (behavior, urge) in [(FLEE, FLEE_URGE), (HIDE, URGENT), (MOB, MOB_URGE)] {
+            if mind.is_doing(behavior) {
+                mind.score(behavior, urge);
+            }
+        }
+    }
+}
+
+/// Counts down a hiding bird's timer, but only while it feels safe. True once it's done hiding.
+fn done_hiding(state: &mut BirdState, fear: &Fear, delta: Duration) -> bool {
+    let BirdState::Hiding { timer } = state else {
+        return false;
+    };
+    fear.level < CALM_THRESHOLD && timer.tick(delta).is_finished()
+}
+
+/// Frightened birds freeze where they are, silent, until they've felt safe for a while.
+pub(super) fn hide(time: Res<Time>, mut birds: Query<(&mut BirdState, &mut Mind, &Fear)>) {
+    let mut rng = rand::rng();
+
+    for (mut state, mut mind, fear) in birds.iter_mut() {
+        if mind.started(HIDE) {
+            *state = BirdState::Hiding {
+                timer: Timer::from_seconds(rng.random_range(2.0..5.0), TimerMode::Once),
+            };
+        } else if mind.is_doing(HIDE) && done_hiding(&mut state, fear, time.delta()) {
+            mind.finish();
+        }
+    }
+}
+
+/// Birds flee to the tree farthest from the predator and hide there once they arrive.
+/// With no cover left they leave the clearing.
+pub(super) fn flee(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut birds: Query<(
+        Entity,
+        &mut BirdState,
+        &mut Mind,
+        &Fear,
+        &PhysicalTranslation,
+        Option<&Perch>,
+    )>,
+    positions: Query<&PhysicalTranslation>,
+    mut spots: Spots,
+    tree_transforms: Query<&Transform, With<Tree>>,
+) {
+    let mut rng = rand::rng();
+
+    for (entity, mut state, mut mind, fear, position, perch) in birds.iter_mut() {
+        if mind.started(FLEE) {
+            let threat_at = fear
+                .predator
+                .and_then(|predator| positions.get(predator).ok())
+                .map_or(position.0, |p| p.0);
+            let cover = farthest_tree(&spots, &tree_transforms, threat_at, perch)
+                .and_then(|tree| reserve_perch_on(&mut rng, &mut spots, entity, tree))
+                .or_else(|| {
+                    reserve_random_perch(&mut rng, &mut spots, entity, perch.map(|p| p.spot))
+                });
+            *state = match cover {
+                Some((cover, target)) => {
+                    commands.entity(entity).insert(cover);
+                    BirdState::Fleeing { target }
+                }
+                None => {
+                    commands.entity(entity).remove::<Perch>();
+                    BirdState::Departing {
+                        target: departure_target(&mut rng),
+                    }
+                }
+            };
+        } else if mind.is_doing(FLEE) && done_hiding(&mut state, fear, time.delta()) {
+            mind.finish();
+        }
+    }
+}
+
+/// Gives an alarm call whenever a bird with one is frightened enough, warning its neighbors.
+pub(super) fn give_alarm_calls(
+    mut commands: Commands,
+    mut voices: Voices,
+    catalog: Res<SpeciesCatalog>,
+    mut birds: Query<(
+        Entity,
+        &Bird,
+        &mut Fear,
+        &PhysicalTranslation,
+        &mut SpatialAudioEmitter,
+    )>,
+) {
+    let mut rng = rand::rng();
+
+    for (entity, bird, mut fear, position, mut emitter) in birds.iter_mut() {
+        if fear.level > ALARM_THRESHOLD
+            && fear.alarm_cooldown == 0.0
+            && let Some(call) = catalog[bird.species]
+                .calls(CallKind::Alarm)
+                .choose(&mut rng)
+        {
+            fear.alarm_cooldown = ALARM_COOLDOWN;
+            if let Some(handle) = voices.play(entity, bird.species, position.0, call, &mut emitter)
+            {
+                commands.entity(entity).insert(ActiveCall(handle));
+            }
+            commands.trigger(AlarmCall {
+                bird: entity,
+                position: position.0,
+                predator: fear.predator,
+            });
+        }
+    }
+}
+
+/// The tree with room for one more bird that is farthest from the threat.
+fn farthest_tree(
+    spots: &Spots,
+    tree_transforms: &Query<&Transform, With<Tree>>,
+    threat_at: Vec3,
+    perch: Option<&Perch>,
+) -> Option<Entity> {
+    spots
+        .iter()
+        .filter(|(tree, perches, destination)| {
+            **destination == Destination::Canopy
+                && Some(*tree) != perch.map(|p| p.spot)
+                && perches.has_free_slot()
+        })
+        .filter_map(|(tree, _, _)| {
+            let at = tree_transforms.get(tree).ok()?.translation;
+            Some((tree, at.distance(threat_at)))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(tree, _)| tree)
+}
+
+/// A point near the predator to swoop at.
+fn mobbing_point(rng: &mut impl Rng, predator: Vec3) -> Vec3 {
+    let angle: f32 = rng.random_range(0.0..std::f32::consts::TAU);
+    predator
+        + Vec3::new(
+            angle.cos() * MOB_DISTANCE,
+            rng.random_range(0.3..1.0),
+            angle.sin() * MOB_DISTANCE,
+        )
+}
+
+/// Mobbing birds keep swooping around the predator until they've made their point or the
+/// predator leaves, then go back to visiting trees.
+pub(super) fn mob(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut birds: Query<(
+        Entity,
+        &mut BirdState,
+        &mut Mind,
+        &Fear,
+        &PhysicalTranslation,
+    )>,
+    positions: Query<&PhysicalTranslation>,
+    mut spots: Spots,
+) {
+    let mut rng = rand::rng();
+
+    for (entity, mut state, mut mind, fear, position) in birds.iter_mut() {
+        if mind.started(MOB) {
+            let predator = fear
+                .predator
+                .and_then(|predator| Some((predator, positions.get(predator).ok()?.0)));
+            match predator {
+                Some((predator, at)) => {
+                    commands.entity(entity).remove::<Perch>();
+                    *state = BirdState::Mobbing {
+                        predator,
+                        target: mobbing_point(&mut rng, at),
+                        timer: Timer::from_seconds(rng.random_range(MOB_DURATION), TimerMode::Once),
+                    };
+                }
+                None => mind.finish(),
+            }
+            continue;
+        }
+
+        let BirdState::Mobbing {
+            predator,
+            target,
+            timer,
+        } = state.as_mut()
+        else {
+            continue;
+        };
+        if !mind.is_doing(MOB) {
+            continue;
+        }
+
+        timer.tick(time.delta());
+        let predator_position = positions.get(*predator).ok().map(|p| p.0);
+        match predator_position {
+            Some(at) if !timer.is_finished() => {
+                if position.0.distance(*target) < 0.5 || target.distance(at) > MOB_DISTANCE * 2.0 {
+                    *target = mobbing_point(&mut rng, at);
+                }
+            }
+            _ => {
+                *state = match reserve_random_perch(&mut rng, &mut spots, entity, None) {
+                    Some((perch, target)) => {
+                        commands.entity(entity).insert(perch);
+                        BirdState::FlyingToNext { target }
+                    }
+                    None => BirdState::Departing {
+                        target: departure_target(&mut rng),
+                    },
+                };
+                mind.finish();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn perching() -> BirdState {
+        BirdState::Perching {
+            timer: Timer::from_seconds(1.0, TimerMode::Once),
+        }
+    }
+
+    #[test]
+    fn test_threat_falls_off_with_distance() {
+        assert_eq!(threat(0.0), 1.0);
+        assert!(threat(THREAT_RADIUS * 0.5) > threat(THREAT_RADIUS * 0.8));
+        assert_eq!(threat(THREAT_RADIUS + 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_perched_birds_freeze_then_flee() {
+        let state = perching();
+        assert_eq!(reaction(&state, 0.1, false, true), Reaction::Stay);
+        assert_eq!(reaction(&state, ALARM_FEAR, false, true), Reaction::Freeze);
+        assert_eq!(reaction(&state, 0.9, false, true), Reaction::Flee);
+        assert_eq!(
+            reaction(&state, 0.9, false, false),
+            Reaction::Freeze,
+            "Birds that only heard an alarm don't know where to flee from"
+        );
+    }
+
+    #[test]
+    fn test_flying_birds_head_for_cover() {
+        let state = BirdState::FlyingToNext { target: Vec3::ONE };
+        assert_eq!(reaction(&state, ALARM_FEAR, false, false), Reaction::Flee);
+    }
+
+    #[test]
+    fn test_mobbers_never_hide() {
+        let state = perching();
+        assert_eq!(reaction(&state, 0.9, true, true), Reaction::Mob);
+        assert_eq!(
+            reaction(&state, 0.9, true, false),
+            Reaction::Stay,
+            "Mobbers need to know where the predator is"
+        );
+    }
+
+    #[test]
+    fn test_hiding_birds_wait_until_calm() {
+        let mut state = BirdState::Hiding {
+            timer: Timer::from_seconds(1.0, TimerMode::Once),
+        };
+        let scared = Fear {
+            level: 0.5,
+            ..default()
+        };
+        assert!(!done_hiding(&mut state, &scared, Duration::from_secs(2)));
+        assert!(done_hiding(
+            &mut state,
+            &Fear::default(),
+            Duration::from_secs(2)
+        ));
+    }
+}
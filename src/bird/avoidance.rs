@@ -0,0 +1,152 @@
+use bevy::prelude::*;
+
+use super::{Bird, BirdState, PhysicalTranslation, Velocity};
+use crate::scene::Obstacle;
+use crate::species::SpeciesCatalog;
+use crate::terrain::Terrain;
+
+/// How far ahead (in seconds of flight) birds look for obstacles.
+const LOOKAHEAD_TIME: f32 = 0.8;
+/// Birds start steering away once they get this close to a surface.
+const AVOID_MARGIN: f32 = 0.6;
+const AVOID_WEIGHT: f32 = 2.5;
+
+/// Obstacle avoidance is the last steering layer, so it may push a bird a bit past cruise speed.
+const MAX_SPEED_FACTOR: f32 = 1.4;
+
+/// Bends flight paths around trunks, canopies and the ground.
+/// Runs after flocking, on top of the velocity chosen this frame.
+pub(super) fn avoid_obstacles(
+    catalog: Res<SpeciesCatalog>,
+    terrain: Res<Terrain>,
+    obstacles: Query<(&Obstacle, &Transform)>,
+    mut birds: Query<(&Bird, &BirdState, &PhysicalTranslation, &mut Velocity)>,
+) {
+    let obstacles: Vec<(Obstacle, Vec3)> = obstacles
+        .iter()
+        .map(|(obstacle, transform)| (*obstacle, transform.translation))
+        .collect();
+
+    for (bird, state, position, mut velocity) in birds.iter_mut() {
+        let Some(target) = state.target() else {
+            continue;
+        };
+
+        let speed = catalog[bird.species].speed;
+        let ground = |point: Vec3| terrain.height(point.x, point.z);
+        let steer = avoidance(position.0, velocity.0, target, &obstacles, ground) * speed;
+        if steer != Vec3::ZERO {
+            velocity.0 = (velocity.0 + steer).clamp_length_max(speed * MAX_SPEED_FACTOR);
+        }
+    }
+}
+
+/// Steering away from every surface within [`AVOID_MARGIN`] of the bird or of its look-ahead
+/// point. Surfaces close to `target` get a smaller margin, so a bird can still land on a perch
+/// right next to them. `ground` gives the height of the ground under a point.
+fn avoidance(
+    position: Vec3,
+    velocity: Vec3,
+    target: Vec3,
+    obstacles: &[(Obstacle, Vec3)],
+    ground: impl Fn(Vec3) -> f32,
+) -> Vec3 {
+    let heading = velocity.normalize_or_zero();
+    let ahead = position + velocity * LOOKAHEAD_TIME;
+    let mut steer = Vec3::ZERO;
+
+    for (obstacle, center) in obstacles {
+        let margin = margin_near(obstacle.signed_distance(*center, target));
+        for probe in [position, ahead] {
+            let distance = obstacle.signed_distance(*center, probe);
+            if distance < margin {
+                let push = (margin - distance) / margin;
+                let away = deflection(obstacle.normal(*center, probe), heading);
+                steer += away * push * AVOID_WEIGHT;
+            }
+        }
+    }
+
+    let ground_margin = margin_near(target.y - ground(target));
+    for probe in [position, ahead] {
+        let distance = probe.y - ground(probe);
+        if distance < ground_margin {
+            steer += Vec3::Y * (ground_margin - distance) / ground_margin * AVOID_WEIGHT;
+        }
+    }
+
+    steer
+}
+
+fn margin_near(target_distance: f32) -> f32 {
+    AVOID_MARGIN.min(target_distance * 0.5).max(0.01)
+}
+
+/// Turns an outward surface normal into a sideways push, so a bird heading straight at an
+/// obstacle slides around it instead of stalling in front of it.
+fn deflection(normal: Vec3, heading: Vec3) -> Vec3 {
+    let sideways = normal - heading * normal.dot(heading);
+    if sideways.length_squared() > 0.01 {
+        sideways.normalize() + normal * 0.5
+    } else {
+        // Dead center: pick a side
+        heading.cross(Vec3::Y).normalize_or(Vec3::X) + normal * 0.5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::GROUND_LEVEL;
+
+    fn flat(_: Vec3) -> f32 {
+        GROUND_LEVEL
+    }
+
+    #[test]
+    fn test_head_on_canopy_steers_sideways() {
+        let canopy = [(Obstacle::Sphere { radius: 1.2 }, Vec3::new(0.0, 3.0, 0.0))];
+        let position = Vec3::new(-2.2, 3.0, 0.0);
+        let target = Vec3::new(10.0, 3.0, 0.0);
+
+        let steer = avoidance(position, Vec3::X, target, &canopy, flat);
+        assert!(
+            steer.length() > 0.0,
+            "Bird should react to the canopy ahead"
+        );
+        assert!(
+            steer.z.abs() > 0.1,
+            "Steering should include a sideways component"
+        );
+    }
+
+    #[test]
+    fn test_perch_next_to_canopy_is_reachable() {
+        let canopy = [(Obstacle::Sphere { radius: 1.2 }, Vec3::new(0.0, 3.0, 0.0))];
+        // Perch sits just above the canopy surface
+        let perch = Vec3::new(0.0, 3.0 + 1.35, 0.0);
+        let position = perch + Vec3::new(0.0, 0.05, 0.0);
+
+        let steer = avoidance(position, Vec3::ZERO, perch, &canopy, flat);
+        assert_eq!(steer, Vec3::ZERO, "Landing on a perch shouldn't be blocked");
+    }
+
+    #[test]
+    fn test_ground_pushes_up() {
+        let position = Vec3::new(0.0, 0.2, 0.0);
+        let target = Vec3::new(5.0, 3.0, 0.0);
+        let steer = avoidance(position, Vec3::new(1.0, -0.5, 0.0), target, &[], flat);
+        assert!(steer.y > 0.0, "Birds should pull up near the ground");
+    }
+
+    #[test]
+    fn test_hills_push_up() {
+        // Level flight well clear of the clearing floor, heading into rising ground
+        let position = Vec3::new(0.0, 1.5, 0.0);
+        let target = Vec3::new(5.0, 1.5, 0.0);
+        let hill = |point: Vec3| point.x * 0.4;
+        assert_eq!(avoidance(position, Vec3::X, target, &[], flat), Vec3::ZERO);
+        let steer = avoidance(position, Vec3::X * 4.0, Vec3::new(5.0, 3.5, 0.0), &[], hill);
+        assert!(steer.y > 0.0, "Birds should climb over a hill");
+    }
+}
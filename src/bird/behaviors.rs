@@ -0,0 +1,336 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::flocking::FollowsLeader;
+use super::mind::{Behavior, Mind};
+use super::needs::Needs;
+use super::perching::{Perch, Spots, reserve_destination};
+use super::{Bird, BirdState, departure_target};
+use crate::scene::{DayClock, Destination, FoodSource, Moon};
+use crate::species::SpeciesCatalog;
+use crate::weather::{HEAVY_RAIN, Weather};
+
+/// Fly to another tree and sit there for a while.
+pub(super) const PERCH: Behavior = Behavior("perch");
+/// Sit still wherever the bird is.
+pub(super) const REST: Behavior = Behavior("rest");
+/// Fly to a food source and feed.
+pub(super) const FORAGE: Behavior = Behavior("forage");
+/// Fly to the bird bath and drink.
+pub(super) const DRINK: Behavior = Behavior("drink");
+/// Leave the clearing for good.
+pub(super) const DEPART: Behavior = Behavior("depart");
+/// Wait out heavy rain in the treetops.
+pub(super) const SHELTER: Behavior = Behavior("shelter");
+
+/// How much a bird wants to try another tree when nothing else is pressing.
+const WANDER: f32 = 0.3;
+/// Flock members mostly sit tight and let their leader decide where the flock goes.
+const FOLLOWER_REST: f32 = 0.4;
+/// How much a bird that has seen enough of the clearing wants to leave.
+const DONE_VISITING: f32 = 0.6;
+/// Sheltering birds come back out once the rain eases below this.
+const RAIN_EASING: f32 = 0.3;
+
+/// Reserves a spot of the given kind, other than the one the bird is at, and sets off for it.
+pub(super) fn visit(
+    commands: &mut Commands,
+    rng: &mut impl Rng,
+    spots: &mut Spots,
+    entity: Entity,
+    bird: &mut Bird,
+    destination: Destination,
+    perch: Option<&Perch>,
+) -> Option<BirdState> {
+    let (next, target) =
+        reserve_destination(rng, spots, entity, destination, perch.map(|p| p.spot))?;
+    commands.entity(entity).insert(next);
+    bird.visits += 1;
+    Some(BirdState::FlyingToNext { target })
+}
+
+// -- Perch --
+
+pub(super) fn score_perch(mut birds: Query<(&Bird, &mut Mind), Without<FollowsLeader>>) {
+    for (bird, mut mind) in birds.iter_mut() {
+        if mind.is_thinking() && bird.visits < bird.max_visits {
+            mind.score(PERCH, WANDER);
+        }
+    }
+}
+
+/// Perching ends once the bird has sat on its new perch for a moment.
+pub(super) fn perch(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut spots: Spots,
+    mut birds: Query<(Entity, &mut Bird, &mut BirdState, &mut Mind, Option<&Perch>)>,
+) {
+    let mut rng = rand::rng();
+
+    for (entity, mut bird, mut state, mut mind, perch) in birds.iter_mut() {
+        if mind.started(PERCH) {
+            let canopy = Destination::Canopy;
+            match visit(
+                &mut commands,
+                &mut rng,
+                &mut spots,
+                entity,
+                &mut bird,
+                canopy,
+                perch,
+            ) {
+                Some(next) => *state = next,
+                None => mind.finish(),
+            }
+        } else if mind.is_doing(PERCH)
+            && let BirdState::Perching { timer } = state.as_mut()
+            && timer.tick(time.delta()).is_finished()
+        {
+            mind.finish();
+        }
+    }
+}
+
+// -- Rest --
+
+pub(super) fn score_rest(mut birds: Query<(&Needs, &mut Mind, Has<FollowsLeader>)>) {
+    for (needs, mut mind, is_follower) in birds.iter_mut() {
+        if !mind.is_thinking() {
+            continue;
+        }
+        let score = if is_follower {
+            needs.fatigue.max(FOLLOWER_REST)
+        } else {
+            needs.fatigue
+        };
+        mind.score(REST, score);
+    }
+}
+
+/// Resting birds sit still where they are until they've caught their breath. Flock members
+/// keep resting until something else comes up.
+pub(super) fn rest(mut birds: Query<(&mut BirdState, &mut Mind, &Needs, Has<FollowsLeader>)>) {
+    for (mut state, mut mind, needs, is_follower) in birds.iter_mut() {
+        if mind.started(REST) && !state.is_flying() {
+            *state = BirdState::Perching {
+                timer: Timer::from_seconds(1.0, TimerMode::Once),
+            };
+        } else if mind.is_doing(REST) && !is_follower && needs.fatigue == 0.0 {
+            mind.finish();
+        }
+    }
+}
+
+// -- Forage --
+
+pub(super) fn score_forage(
+    catalog: Res<SpeciesCatalog>,
+    mut birds: Query<(&Bird, &Needs, &mut Mind), Without<FollowsLeader>>,
+) {
+    for (bird, needs, mut mind) in birds.iter_mut() {
+        if mind.is_thinking() && !catalog[bird.species].forages.is_empty() {
+            mind.score(FORAGE, needs.hunger);
+        }
+    }
+}
+
+/// Foraging birds head for their favorite food source with room for them, and feed until
+/// they're full or it's time to try somewhere else.
+pub(super) fn forage(
+    mut commands: Commands,
+    time: Res<Time>,
+    catalog: Res<SpeciesCatalog>,
+    mut spots: Spots,
+    mut birds: Query<(
+        Entity,
+        &mut Bird,
+        &mut BirdState,
+        &mut Mind,
+        &Needs,
+        Option<&Perch>,
+    )>,
+) {
+    let mut rng = rand::rng();
+
+    for (entity, mut bird, mut state, mut mind, needs, perch) in birds.iter_mut() {
+        if mind.started(FORAGE) {
+            let species = bird.species;
+            // Insects are caught on the wing instead, see `hawking`
+            let sources = catalog[species].forages.iter();
+            let mut sources = sources.filter(|source| **source != FoodSource::Insects);
+            let next = sources.find_map(|source| {
+                let food = Destination::Food(*source);
+                visit(
+                    &mut commands,
+                    &mut rng,
+                    &mut spots,
+                    entity,
+                    &mut bird,
+                    food,
+                    perch,
+                )
+            });
+            match next {
+                Some(next) => *state = next,
+                None => mind.finish(),
+            }
+        } else if mind.is_doing(FORAGE)
+            && let BirdState::Foraging { timer } | BirdState::Climbing { timer, .. } =
+                state.as_mut()
+            && (timer.tick(time.delta()).is_finished() || needs.hunger == 0.0)
+        {
+            mind.finish();
+        }
+    }
+}
+
+// -- Drink --
+
+pub(super) fn score_drink(mut birds: Query<(&Needs, &mut Mind), Without<FollowsLeader>>) {
+    for (needs, mut mind) in birds.iter_mut() {
+        if mind.is_thinking() {
+            mind.score(DRINK, needs.thirst);
+        }
+    }
+}
+
+pub(super) fn drink(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut spots: Spots,
+    mut birds: Query<(
+        Entity,
+        &mut Bird,
+        &mut BirdState,
+        &mut Mind,
+        &Needs,
+        Option<&Perch>,
+    )>,
+) {
+    let mut rng = rand::rng();
+
+    for (entity, mut bird, mut state, mut mind, needs, perch) in birds.iter_mut() {
+        if mind.started(DRINK) {
+            let water = Destination::Water;
+            match visit(
+                &mut commands,
+                &mut rng,
+                &mut spots,
+                entity,
+                &mut bird,
+                water,
+                perch,
+            ) {
+                Some(next) => *state = next,
+                None => mind.finish(),
+            }
+        } else if mind.is_doing(DRINK)
+            && let BirdState::Drinking { timer } = state.as_mut()
+            && (timer.tick(time.delta()).is_finished() || needs.thirst == 0.0)
+        {
+            mind.finish();
+        }
+    }
+}
+
+// -- Shelter --
+
+/// Heavy rain is urgent enough to interrupt whatever a bird is doing.
+pub(super) fn score_shelter(
+    weather: Res<Weather>,
+    mut birds: Query<&mut Mind, Without<FollowsLeader>>,
+) {
+    let rain = weather.conditions().rain;
+    for mut mind in birds.iter_mut() {
+        let sheltering = mind.is_doing(SHELTER) && rain > RAIN_EASING;
+        if mind.is_thinking() && (rain >= HEAVY_RAIN || sheltering) {
+            mind.score(SHELTER, rain);
+        }
+    }
+}
+
+/// Sheltering birds fly into a canopy, unless they're already in one, and sit tight until the
+/// rain eases.
+pub(super) fn shelter(
+    mut commands: Commands,
+    weather: Res<Weather>,
+    mut spots: Spots,
+    mut birds: Query<(Entity, &mut Bird, &mut BirdState, &mut Mind, Option<&Perch>)>,
+) {
+    let mut rng = rand::rng();
+    let rain = weather.conditions().rain;
+
+    for (entity, mut bird, mut state, mut mind, perch) in birds.iter_mut() {
+        if mind.started(SHELTER) {
+            let in_canopy = !state.is_flying()
+                && perch.is_some_and(|perch| {
+                    spots
+                        .get(perch.spot)
+                        .is_ok_and(|(_, _, kind)| *kind == Destination::Canopy)
+                });
+            if in_canopy {
+                *state = BirdState::Perching {
+                    timer: Timer::from_seconds(1.0, TimerMode::Once),
+                };
+            } else {
+                // With every canopy spot taken there's no cover to be had
+                match visit(
+                    &mut commands,
+                    &mut rng,
+                    &mut spots,
+                    entity,
+                    &mut bird,
+                    Destination::Canopy,
+                    perch,
+                ) {
+                    Some(next) => *state = next,
+                    None => mind.finish(),
+                }
+            }
+        } else if mind.is_doing(SHELTER) && rain <= RAIN_EASING {
+            mind.finish();
+        }
+    }
+}
+
+// -- Depart --
+
+/// Birds leave once their species' active hours are over, or once they've visited enough
+/// spots. Flock members leave with their leader, see `sync_flock_members`.
+pub(super) fn score_depart(
+    day_clock: Res<DayClock>,
+    moon: Res<Moon>,
+    catalog: Res<SpeciesCatalog>,
+    mut birds: Query<(&Bird, &mut Mind, Has<FollowsLeader>)>,
+) {
+    let sun_elevation = day_clock.sun_elevation();
+    let moonlight = moon.brightness();
+
+    for (bird, mut mind, is_follower) in birds.iter_mut() {
+        if !mind.is_thinking() {
+            continue;
+        }
+        let inactive = !catalog[bird.species].is_active(sun_elevation, moonlight);
+        let done = !is_follower && bird.visits >= bird.max_visits;
+        if inactive || mind.is_doing(DEPART) {
+            mind.score(DEPART, 1.0);
+        } else if done {
+            mind.score(DEPART, DONE_VISITING);
+        }
+    }
+}
+
+/// Departing never finishes: the bird is despawned once it's out of sight.
+pub(super) fn depart(mut commands: Commands, mut birds: Query<(Entity, &mut BirdState, &Mind)>) {
+    let mut rng = rand::rng();
+
+    for (entity, mut state, mind) in birds.iter_mut() {
+        if mind.started(DEPART) {
+            commands.entity(entity).remove::<Perch>();
+            *state = BirdState::Departing {
+                target: departure_target(&mut rng),
+            };
+        }
+    }
+}
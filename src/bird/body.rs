@@ -0,0 +1,622 @@
+use std::f32::consts::FRAC_PI_2;
+use std::time::Duration;
+
+use bevy::animation::{AnimatedBy, AnimationTargetId, animated_field};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::climbing::Drumming;
+use super::{Bird, BirdState, Velocity};
+use crate::species::{FlightStyle, SizeClass, SpeciesCatalog};
+
+/// How quickly birds turn to face their direction of travel.
+const TURN_RATE: f32 = 8.0;
+/// Birds tilt their body at most this much (radians) when climbing or diving.
+const MAX_PITCH: f32 = 0.6;
+/// Slower than this, a bird keeps its current heading.
+const MIN_FACING_SPEED: f32 = 0.05;
+
+/// Crossfade between animations.
+const BLEND_TIME: Duration = Duration::from_millis(150);
+/// Seconds between hops and head turns while perched.
+const FIDGET_INTERVAL: std::ops::Range<f32> = 1.5..5.0;
+/// Share of fidgets that are hops; the rest are head turns.
+const HOP_CHANCE: f64 = 0.3;
+
+/// One wingbeat of a medium-sized bird, in seconds.
+const WINGBEAT: f32 = 0.25;
+/// Wing angle above (and below) horizontal at the top (and bottom) of a wingbeat.
+const WING_UP: f32 = 0.9;
+const WING_DOWN: f32 = -0.7;
+const HEAD_TURN: f32 = 0.8;
+/// One strike of a drumming woodpecker's bill, in seconds, and how far its head swings.
+const PECK: f32 = 0.08;
+const PECK_ANGLE: f32 = 0.5;
+
+// -- Body parts --
+
+/// Names of the animated body parts. Animation targets are derived from these, so every bird
+/// can share the same clips.
+const BODY: &str = "body";
+const HEAD: &str = "head";
+const LEFT_WING: &str = "left_wing";
+const RIGHT_WING: &str = "right_wing";
+
+fn target_id(path: &[&'static str]) -> AnimationTargetId {
+    let names: Vec<Name> = path.iter().map(|part| Name::new(*part)).collect();
+    AnimationTargetId::from_names(names.iter())
+}
+
+/// Meshes for one [`SizeClass`]. Wings are built with their pivot at the shoulder.
+#[derive(Clone)]
+struct BodyMeshes {
+    body: Handle<Mesh>,
+    head: Handle<Mesh>,
+    beak: Handle<Mesh>,
+    tail: Handle<Mesh>,
+    left_wing: Handle<Mesh>,
+    right_wing: Handle<Mesh>,
+}
+
+impl BodyMeshes {
+    /// Birds face `-Z`, like everything else in Bevy.
+    fn new(radius: f32, meshes: &mut Assets<Mesh>) -> Self {
+        let wing = Cuboid::new(radius * 1.6, radius * 0.08, radius * 0.9);
+        Self {
+            body: meshes.add(
+                Sphere::new(radius)
+                    .mesh()
+                    .uv(12, 8)
+                    .scaled_by(Vec3::new(0.85, 0.8, 1.3)),
+            ),
+            head: meshes.add(Sphere::new(radius * 0.55).mesh().uv(10, 6)),
+            beak: meshes.add(
+                Cone::new(radius * 0.15, radius * 0.4)
+                    .mesh()
+                    .build()
+                    .rotated_by(Quat::from_rotation_x(-FRAC_PI_2)),
+            ),
+            tail: meshes.add(
+                Mesh::from(Cuboid::new(radius * 0.5, radius * 0.06, radius * 0.8))
+                    .translated_by(Vec3::Z * radius * 0.4),
+            ),
+            left_wing: meshes.add(Mesh::from(wing).translated_by(Vec3::NEG_X * radius * 0.8)),
+            right_wing: meshes.add(Mesh::from(wing).translated_by(Vec3::X * radius * 0.8)),
+        }
+    }
+}
+
+// -- Animations --
+
+/// What a bird's body is doing, each backed by one clip in the shared animation graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Pose {
+    Flap,
+    /// Wings held out
+    Glide,
+    /// Wings folded against the body: perched, or between bursts of bounding flight
+    Folded,
+    Hop,
+    HeadTurn,
+    /// Rapid strikes of the bill against bark while drumming
+    Peck,
+}
+
+impl Pose {
+    fn repeats(&self) -> bool {
+        matches!(self, Self::Flap | Self::Glide | Self::Folded | Self::Peck)
+    }
+}
+
+/// Shared meshes and animation graph for all bird bodies.
+#[derive(Resource)]
+pub(super) struct BirdBodies {
+    meshes: HashMap<SizeClass, BodyMeshes>,
+    beak_material: Handle<StandardMaterial>,
+    graph: Handle<AnimationGraph>,
+    nodes: HashMap<Pose, AnimationNodeIndex>,
+}
+
+pub(super) fn setup_bird_bodies(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut clips: ResMut<Assets<AnimationClip>>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+) {
+    let mut graph = AnimationGraph::new();
+    let nodes = [
+        (Pose::Flap, flap_clip()),
+        (Pose::Glide, glide_clip()),
+        (Pose::Folded, folded_clip()),
+        (Pose::Hop, hop_clip()),
+        (Pose::HeadTurn, head_turn_clip()),
+        (Pose::Peck, peck_clip()),
+    ]
+    .into_iter()
+    .map(|(pose, clip)| (pose, graph.add_clip(clips.add(clip), 1.0, graph.root)))
+    .collect();
+
+    commands.insert_resource(BirdBodies {
+        meshes: SizeClass::ALL
+            .into_iter()
+            .map(|size| (size, BodyMeshes::new(size.radius(), &mut meshes)))
+            .collect(),
+        beak_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.15, 0.12, 0.1),
+            perceptual_roughness: 0.6,
+            ..default()
+        }),
+        graph: graphs.add(graph),
+        nodes,
+    });
+}
+
+/// Wing rotation for a wing raised `angle` radians above horizontal. Wings mirror each other.
+fn wing_rotations(angle: f32) -> (Quat, Quat) {
+    (Quat::from_rotation_z(-angle), Quat::from_rotation_z(angle))
+}
+
+/// Wings swept back along the body.
+fn folded_wing_rotations() -> (Quat, Quat) {
+    (
+        Quat::from_rotation_y(1.4) * Quat::from_rotation_z(0.2),
+        Quat::from_rotation_y(-1.4) * Quat::from_rotation_z(-0.2),
+    )
+}
+
+fn add_wing_curves(clip: &mut AnimationClip, keyframes: &[(f32, (Quat, Quat))]) {
+    let left = keyframes.iter().map(|(t, (left, _))| (*t, *left));
+    let right = keyframes.iter().map(|(t, (_, right))| (*t, *right));
+    for (wing, samples) in [
+        (LEFT_WING, left.collect::<Vec<_>>()),
+        (RIGHT_WING, right.collect()),
+    ] {
+        clip.add_curve_to_target(
+            target_id(&[BODY, wing]),
+            AnimatableCurve::new(
+                animated_field!(Transform::rotation),
+                UnevenSampleAutoCurve::new(samples).expect("wing keyframes are sorted"),
+            ),
+        );
+    }
+}
+
+/// Every clip animates every body part, so switching clips never leaves a part mid-motion.
+fn add_head_at_rest(clip: &mut AnimationClip, duration: f32) {
+    clip.add_curve_to_target(
+        target_id(&[BODY, HEAD]),
+        AnimatableCurve::new(
+            animated_field!(Transform::rotation),
+            UnevenSampleAutoCurve::new([(0.0, Quat::IDENTITY), (duration, Quat::IDENTITY)])
+                .expect("head keyframes are sorted"),
+        ),
+    );
+}
+
+fn add_body_at_rest(clip: &mut AnimationClip, duration: f32) {
+    clip.add_curve_to_target(
+        target_id(&[BODY]),
+        AnimatableCurve::new(
+            animated_field!(Transform::translation),
+            UnevenSampleAutoCurve::new([(0.0, Vec3::ZERO), (duration, Vec3::ZERO)])
+                .expect("body keyframes are sorted"),
+        ),
+    );
+}
+
+fn flap_clip() -> AnimationClip {
+    let mut clip = AnimationClip::default();
+    add_wing_curves(
+        &mut clip,
+        &[
+            (0.0, wing_rotations(WING_UP)),
+            (WINGBEAT * 0.5, wing_rotations(WING_DOWN)),
+            (WINGBEAT, wing_rotations(WING_UP)),
+        ],
+    );
+    add_head_at_rest(&mut clip, WINGBEAT);
+    add_body_at_rest(&mut clip, WINGBEAT);
+    clip
+}
+
+fn glide_clip() -> AnimationClip {
+    let mut clip = AnimationClip::default();
+    // Small adjustments keep a gliding bird from looking frozen
+    add_wing_curves(
+        &mut clip,
+        &[
+            (0.0, wing_rotations(0.1)),
+            (1.0, wing_rotations(0.0)),
+            (2.0, wing_rotations(0.1)),
+        ],
+    );
+    add_head_at_rest(&mut clip, 2.0);
+    add_body_at_rest(&mut clip, 2.0);
+    clip
+}
+
+fn folded_clip() -> AnimationClip {
+    let mut clip = AnimationClip::default();
+    add_wing_curves(
+        &mut clip,
+        &[
+            (0.0, folded_wing_rotations()),
+            (1.0, folded_wing_rotations()),
+        ],
+    );
+    add_head_at_rest(&mut clip, 1.0);
+    add_body_at_rest(&mut clip, 1.0);
+    clip
+}
+
+fn hop_clip() -> AnimationClip {
+    const DURATION: f32 = 0.3;
+    let mut clip = AnimationClip::default();
+    // Wings flick open for balance at the top of the hop
+    add_wing_curves(
+        &mut clip,
+        &[
+            (0.0, folded_wing_rotations()),
+            (DURATION * 0.5, wing_rotations(0.3)),
+            (DURATION, folded_wing_rotations()),
+        ],
+    );
+    clip.add_curve_to_target(
+        target_id(&[BODY]),
+        AnimatableCurve::new(
+            animated_field!(Transform::translation),
+            UnevenSampleAutoCurve::new([
+                (0.0, Vec3::ZERO),
+                (DURATION * 0.5, Vec3::Y * 0.12),
+                (DURATION, Vec3::ZERO),
+            ])
+            .expect("hop keyframes are sorted"),
+        ),
+    );
+    add_head_at_rest(&mut clip, DURATION);
+    clip
+}
+
+fn head_turn_clip() -> AnimationClip {
+    let mut clip = AnimationClip::default();
+    add_wing_curves(
+        &mut clip,
+        &[
+            (0.0, folded_wing_rotations()),
+            (2.0, folded_wing_rotations()),
+        ],
+    );
+    clip.add_curve_to_target(
+        target_id(&[BODY, HEAD]),
+        AnimatableCurve::new(
+            animated_field!(Transform::rotation),
+            UnevenSampleAutoCurve::new([
+                (0.0, Quat::IDENTITY),
+                (0.2, Quat::from_rotation_y(HEAD_TURN)),
+                (0.9, Quat::from_rotation_y(HEAD_TURN)),
+                (1.1, Quat::from_rotation_y(-HEAD_TURN)),
+                (1.7, Quat::from_rotation_y(-HEAD_TURN)),
+                (2.0, Quat::IDENTITY),
+            ])
+            .expect("head keyframes are sorted"),
+        ),
+    );
+    add_body_at_rest(&mut clip, 2.0);
+    clip
+}
+
+fn peck_clip() -> AnimationClip {
+    let mut clip = AnimationClip::default();
+    add_wing_curves(
+        &mut clip,
+        &[
+            (0.0, folded_wing_rotations()),
+            (PECK, folded_wing_rotations()),
+        ],
+    );
+    // Head snaps forward onto the bark and springs back
+    clip.add_curve_to_target(
+        target_id(&[BODY, HEAD]),
+        AnimatableCurve::new(
+            animated_field!(Transform::rotation),
+            UnevenSampleAutoCurve::new([
+                (0.0, Quat::IDENTITY),
+                (PECK * 0.4, Quat::from_rotation_x(-PECK_ANGLE)),
+                (PECK, Quat::IDENTITY),
+            ])
+            .expect("head keyframes are sorted"),
+        ),
+    );
+    add_body_at_rest(&mut clip, PECK);
+    clip
+}
+
+// -- Spawning --
+
+/// Animation state of one bird. See [`animate_birds`].
+#[derive(Component)]
+pub(super) struct BirdPose {
+    current: Option<Pose>,
+    /// Time until the next hop or head turn while perched
+    fidget: Timer,
+}
+
+/// Builds a bird's body under `bird`: a body with head, beak, tail and two wings, animated by
+/// an [`AnimationPlayer`] on the bird itself.
+pub(super) fn attach_body(
+    bird: &mut EntityCommands,
+    bodies: &BirdBodies,
+    size: SizeClass,
+    material: Handle<StandardMaterial>,
+) {
+    let player = bird.id();
+    let meshes = &bodies.meshes[&size];
+    let radius = size.radius();
+    let part = |path: &[&'static str]| {
+        (
+            Name::new(path[path.len() - 1]),
+            target_id(path),
+            AnimatedBy(player),
+        )
+    };
+
+    bird.insert((
+        Visibility::default(),
+        AnimationPlayer::default(),
+        AnimationGraphHandle(bodies.graph.clone()),
+        AnimationTransitions::new(),
+        BirdPose {
+            current: None,
+            fidget: fidget_timer(&mut rand::rng()),
+        },
+        children![(
+            part(&[BODY]),
+            Mesh3d(meshes.body.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::default(),
+            children![
+                (
+                    part(&[BODY, HEAD]),
+                    Mesh3d(meshes.head.clone()),
+                    MeshMaterial3d(material.clone()),
+                    Transform::from_xyz(0.0, radius * 0.55, -radius * 0.9),
+                    children![(
+                        Mesh3d(meshes.beak.clone()),
+                        MeshMaterial3d(bodies.beak_material.clone()),
+                        Transform::from_xyz(0.0, 0.0, -radius * 0.6),
+                    )],
+                ),
+                (
+                    Mesh3d(meshes.tail.clone()),
+                    MeshMaterial3d(material.clone()),
+                    Transform::from_xyz(0.0, radius * 0.1, radius * 1.0)
+                        .with_rotation(Quat::from_rotation_x(0.3)),
+                ),
+                (
+                    part(&[BODY, LEFT_WING]),
+                    Mesh3d(meshes.left_wing.clone()),
+                    MeshMaterial3d(material.clone()),
+                    Transform::from_xyz(-radius * 0.6, radius * 0.25, 0.0),
+                ),
+                (
+                    part(&[BODY, RIGHT_WING]),
+                    Mesh3d(meshes.right_wing.clone()),
+                    MeshMaterial3d(material),
+                    Transform::from_xyz(radius * 0.6, radius * 0.25, 0.0),
+                ),
+            ],
+        )],
+    ));
+}
+
+fn fidget_timer(rng: &mut impl Rng) -> Timer {
+    Timer::from_seconds(rng.random_range(FIDGET_INTERVAL), TimerMode::Once)
+}
+
+// -- Systems --
+
+/// Picks each bird's animation from its [`BirdState`] and flight style, and plays the
+/// occasional hop or head turn while perched.
+pub(super) fn animate_birds(
+    time: Res<Time>,
+    catalog: Res<SpeciesCatalog>,
+    bodies: Res<BirdBodies>,
+    mut birds: Query<(
+        &Bird,
+        &BirdState,
+        &Velocity,
+        &mut BirdPose,
+        &mut AnimationPlayer,
+        &mut AnimationTransitions,
+        Has<Drumming>,
+    )>,
+) {
+    let mut rng = rand::rng();
+
+    for (bird, state, velocity, mut pose, mut player, mut transitions, drumming) in birds.iter_mut()
+    {
+        let species = &catalog[bird.species];
+        let fidgeting = pose.current.filter(|current| {
+            matches!(current, Pose::Hop | Pose::HeadTurn)
+                && player
+                    .animation(bodies.nodes[current])
+                    .is_some_and(|animation| !animation.is_finished())
+        });
+
+        let next = if state.is_flying() {
+            flight_pose(species.flight, velocity.y / species.speed)
+        } else if matches!(state, BirdState::Hiding { .. }) {
+            // Frozen in place
+            Pose::Folded
+        } else if drumming {
+            Pose::Peck
+        } else if let Some(current) = fidgeting {
+            // Let hops and head turns play out
+            current
+        } else if pose
+            .fidget
+            .tick(time.delta().mul_f32(fidget_speed(state)))
+            .is_finished()
+        {
+            pose.fidget = fidget_timer(&mut rng);
+            if rng.random_bool(HOP_CHANCE) {
+                Pose::Hop
+            } else {
+                Pose::HeadTurn
+            }
+        } else {
+            Pose::Folded
+        };
+
+        if pose.current == Some(next) {
+            continue;
+        }
+        pose.current = Some(next);
+
+        let animation = transitions.play(&mut player, bodies.nodes[&next], BLEND_TIME);
+        if next.repeats() {
+            animation.repeat();
+        }
+        if next == Pose::Flap {
+            animation.set_speed(wingbeat_speed(species.size_class()));
+        }
+    }
+}
+
+/// Feeding birds peck and hop about far more often than resting ones.
+fn fidget_speed(state: &BirdState) -> f32 {
+    match state {
+        BirdState::Foraging { .. } | BirdState::Drinking { .. } => 3.0,
+        _ => 1.0,
+    }
+}
+
+/// Bounding fliers fold their wings on the way down; gliders only flap to climb.
+fn flight_pose(style: FlightStyle, climb_rate: f32) -> Pose {
+    match style {
+        FlightStyle::Direct if climb_rate < -0.5 => Pose::Glide,
+        FlightStyle::Direct => Pose::Flap,
+        FlightStyle::Bounding if climb_rate < 0.0 => Pose::Folded,
+        FlightStyle::Bounding => Pose::Flap,
+        FlightStyle::Glide if climb_rate > 0.2 => Pose::Flap,
+        FlightStyle::Glide => Pose::Glide,
+    }
+}
+
+/// Small birds beat their wings faster than large ones.
+fn wingbeat_speed(size: SizeClass) -> f32 {
+    match size {
+        SizeClass::Small => 1.5,
+        SizeClass::Medium => 1.0,
+        SizeClass::Large => 0.6,
+    }
+}
+
+/// Turns flying birds to face their direction of travel, pitching up when climbing and down
+/// when diving. Perched birds keep the heading they landed with.
+pub(super) fn face_travel_direction(
+    time: Res<Time>,
+    mut birds: Query<(&BirdState, &Velocity, &mut Transform), With<Bird>>,
+) {
+    let blend = 1.0 - (-TURN_RATE * time.delta_secs()).exp();
+    for (state, velocity, mut transform) in birds.iter_mut() {
+        // Settled birds only move with their swaying branch, and keep their heading
+        if !state.is_flying() {
+            continue;
+        }
+        if let Some(facing) = facing(velocity.0) {
+            transform.rotation = transform.rotation.slerp(facing, blend);
+        }
+    }
+}
+
+/// Rotation that points a bird's `-Z` forward axis along `velocity`, without rolling it.
+pub(super) fn facing(velocity: Vec3) -> Option<Quat> {
+    let horizontal = velocity.xz().length();
+    if velocity.length() < MIN_FACING_SPEED || horizontal < f32::EPSILON {
+        return None;
+    }
+    let yaw = (-velocity.x).atan2(-velocity.z);
+    let pitch = velocity.y.atan2(horizontal).clamp(-MAX_PITCH, MAX_PITCH);
+    Some(Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::species::BirdSpecies;
+
+    #[test]
+    fn test_bird_faces_velocity() {
+        for direction in [Vec3::X, Vec3::NEG_X, Vec3::Z, Vec3::new(1.0, 0.0, -1.0)] {
+            let rotation = facing(direction).expect("moving birds have a facing");
+            let forward = rotation * Vec3::NEG_Z;
+            assert!(
+                forward.distance(direction.normalize()) < 1e-4,
+                "Bird should face {direction}, faces {forward}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_bird_pitch_is_limited() {
+        let rotation = facing(Vec3::new(0.1, -5.0, 0.0)).expect("moving birds have a facing");
+        let forward = rotation * Vec3::NEG_Z;
+        assert!(forward.y < 0.0, "Diving bird should pitch down");
+        assert!(
+            forward.y > -MAX_PITCH.sin() - 1e-4,
+            "Pitch should be clamped"
+        );
+        assert_eq!(
+            facing(Vec3::ZERO),
+            None,
+            "Hovering birds keep their heading"
+        );
+    }
+
+    #[test]
+    fn test_perched_birds_keep_their_heading_on_a_swaying_branch() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        // Both carried sideways, one by its branch and one by its wings
+        let mut spawn = |state: BirdState| {
+            world
+                .spawn((
+                    Bird {
+                        species: BirdSpecies::from_index(0),
+                        visits: 0,
+                        max_visits: 1,
+                    },
+                    state,
+                    Velocity(Vec3::new(0.3, 0.0, 0.1)),
+                    Transform::default(),
+                ))
+                .id()
+        };
+        let perched = spawn(BirdState::Perching {
+            timer: Timer::from_seconds(5.0, TimerMode::Once),
+        });
+        let flying = spawn(BirdState::FlyingToNext { target: Vec3::X });
+
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(100));
+        world.run_system_once(face_travel_direction).unwrap();
+
+        let heading = |bird: Entity| world.get::<Transform>(bird).unwrap().rotation;
+        assert_eq!(heading(perched), Quat::IDENTITY);
+        assert!(heading(flying).angle_between(Quat::IDENTITY) > 0.1);
+    }
+
+    #[test]
+    fn test_flight_poses() {
+        assert_eq!(flight_pose(FlightStyle::Direct, 0.0), Pose::Flap);
+        assert_eq!(flight_pose(FlightStyle::Bounding, -0.3), Pose::Folded);
+        assert_eq!(flight_pose(FlightStyle::Bounding, 0.3), Pose::Flap);
+        assert_eq!(flight_pose(FlightStyle::Glide, 0.0), Pose::Glide);
+    }
+}
@@ -0,0 +1,110 @@
+use std::ops::Range;
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use rand::Rng;
+use rand::seq::IteratorRandom;
+
+use super::body::facing;
+use super::perching::Perch;
+use super::song::BirdVocalized;
+use super::voices::Voices;
+use super::{ActiveCall, Bird, BirdState, PhysicalTranslation};
+use crate::scene::Obstacle;
+use crate::species::{CallKind, SpeciesCatalog};
+
+/// Climbers hop this far up the trunk at a time.
+const HOP_HEIGHT: f32 = 0.12;
+/// Seconds between hops.
+pub(super) const HOP_INTERVAL: Range<f32> = 0.6..1.6;
+/// Climbers go no higher than this fraction of the way from a trunk's center to its top,
+/// which keeps them below the canopy.
+const CLIMB_LIMIT: f32 = 0.5;
+/// Seconds between drumming bouts.
+pub(super) const DRUM_INTERVAL: Range<f32> = 2.0..6.0;
+/// How long one drumming bout lasts.
+const DRUM_DURATION: f32 = 1.5;
+
+/// A climbing bird hammering on the bark. Its body plays the pecking animation meanwhile.
+#[derive(Component, Debug)]
+pub(super) struct Drumming(Timer);
+
+/// Climbing birds cling to the bark facing the trunk, hop up it bit by bit and stop now and
+/// then to drum.
+pub(super) fn climb_trunks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut voices: Voices,
+    catalog: Res<SpeciesCatalog>,
+    mut birds: Query<(
+        Entity,
+        &Bird,
+        &mut BirdState,
+        &mut PhysicalTranslation,
+        &mut Transform,
+        &mut SpatialAudioEmitter,
+        Option<&Perch>,
+        Option<&mut Drumming>,
+    )>,
+    trunks: Query<(&Transform, &Obstacle), Without<Bird>>,
+) {
+    let mut rng = rand::rng();
+
+    for (entity, bird, mut state, mut position, mut transform, mut emitter, perch, drumming) in
+        birds.iter_mut()
+    {
+        let BirdState::Climbing { hop, drum, .. } = state.as_mut() else {
+            // Birds stop drumming the moment they do anything else
+            if drumming.is_some() {
+                commands.entity(entity).remove::<Drumming>();
+            }
+            continue;
+        };
+        let Some((trunk, &Obstacle::Cylinder { half_height, .. })) =
+            perch.and_then(|perch| trunks.get(perch.spot).ok())
+        else {
+            continue;
+        };
+
+        if let Some(rotation) = facing((trunk.translation - position.0).with_y(0.0)) {
+            transform.rotation = rotation;
+        }
+
+        if let Some(mut drumming) = drumming {
+            if drumming.0.tick(time.delta()).is_finished() {
+                commands.entity(entity).remove::<Drumming>();
+            }
+            continue;
+        }
+
+        if hop.tick(time.delta()).is_finished() {
+            *hop = Timer::from_seconds(rng.random_range(HOP_INTERVAL), TimerMode::Once);
+            let top = trunk.translation.y + half_height * CLIMB_LIMIT;
+            if position.y + HOP_HEIGHT <= top {
+                position.y += HOP_HEIGHT;
+            }
+        }
+
+        if drum.tick(time.delta()).is_finished() {
+            *drum = Timer::from_seconds(rng.random_range(DRUM_INTERVAL), TimerMode::Once);
+            let species = &catalog[bird.species];
+            if let Some(clip) = species.calls(CallKind::Drum).choose(&mut rng) {
+                commands.entity(entity).insert(Drumming(Timer::from_seconds(
+                    DRUM_DURATION,
+                    TimerMode::Once,
+                )));
+                if let Some(handle) =
+                    voices.play(entity, bird.species, position.0, clip, &mut emitter)
+                {
+                    commands.entity(entity).insert(ActiveCall(handle));
+                }
+                commands.trigger(BirdVocalized {
+                    bird: entity,
+                    species: bird.species,
+                    position: position.0,
+                    duration: DRUM_DURATION,
+                });
+            }
+        }
+    }
+}
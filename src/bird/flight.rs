@@ -0,0 +1,319 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{Bird, BirdState, PhysicalTranslation, Velocity};
+use crate::species::{FlightStyle, SpeciesCatalog};
+
+/// Points sampled along each Bézier path.
+const PATH_SAMPLES: usize = 24;
+/// Longer flights arc higher, up to this much above the straight line.
+const MAX_LIFT: f32 = 2.0;
+/// Birds reach cruise speed after flying this far.
+const TAKEOFF_DISTANCE: f32 = 1.5;
+/// Birds start braking this far from their perch.
+const LANDING_DISTANCE: f32 = 1.5;
+/// Fraction of cruise speed at takeoff and right before touching down.
+const TAKEOFF_SPEED: f32 = 0.5;
+const LANDING_SPEED: f32 = 0.15;
+/// Birds aim this far ahead along the path, which smooths out the corners between samples.
+const LOOKAHEAD: f32 = 0.4;
+/// The point a bird follows waits for it when it falls further behind than this,
+/// e.g. while flocking or obstacle avoidance push it off the path.
+const MAX_LEAD: f32 = 1.0;
+/// Departing birds are in a hurry.
+const DEPARTURE_SPEED_FACTOR: f32 = 1.2;
+/// Birds escaping a predator fly flat out.
+const FLEEING_SPEED_FACTOR: f32 = 1.5;
+/// So do birds darting after an insect or swooping at a predator.
+const PURSUIT_SPEED_FACTOR: f32 = 1.5;
+
+/// A curved route from takeoff to the current target, with takeoff, cruise and landing phases.
+/// Planned by [`plan_flight_paths`] whenever a bird's target changes.
+#[derive(Component, Debug)]
+pub(super) struct FlightPath {
+    points: Vec<Vec3>,
+    /// Path length from the start to each point
+    distances: Vec<f32>,
+    /// How far along the path the bird has flown
+    progress: f32,
+    /// Departing birds keep their speed instead of braking for a perch
+    lands: bool,
+    style: FlightStyle,
+    /// Offsets the wingbeat cycle, so birds don't bob in unison
+    phase: f32,
+}
+
+impl FlightPath {
+    /// A cubic Bézier from `start` to `end` that climbs after takeoff, bends a little to one
+    /// side and, when landing, drops onto `end` from above. Gliders dip instead.
+    fn plan(start: Vec3, end: Vec3, lands: bool, style: FlightStyle, rng: &mut impl Rng) -> Self {
+        let offset = end - start;
+        let length = offset.length();
+        let lift = match style {
+            // Owls glide down off the perch instead of climbing, and swoop up onto the next
+            FlightStyle::Glide => -(length * 0.1).min(MAX_LIFT),
+            FlightStyle::Direct | FlightStyle::Bounding => (length * 0.25).min(MAX_LIFT),
+        };
+        let bend = Vec3::Y.cross(offset).normalize_or_zero() * length * rng.random_range(-0.2..0.2);
+        let approach = if lands { lift * 0.5 } else { lift };
+
+        let control = [
+            start,
+            start + offset / 3.0 + Vec3::Y * lift + bend,
+            end - offset / 3.0 + Vec3::Y * approach + bend,
+            end,
+        ];
+        let points: Vec<Vec3> = (0..=PATH_SAMPLES)
+            .map(|i| cubic_bezier(control, i as f32 / PATH_SAMPLES as f32))
+            .collect();
+
+        let mut distances = Vec::with_capacity(points.len());
+        let mut total = 0.0;
+        distances.push(total);
+        for pair in points.windows(2) {
+            total += pair[0].distance(pair[1]);
+            distances.push(total);
+        }
+
+        Self {
+            points,
+            distances,
+            progress: 0.0,
+            lands,
+            style,
+            phase: rng.random_range(0.0..TAU),
+        }
+    }
+
+    fn end(&self) -> Vec3 {
+        *self.points.last().expect("paths have at least one point")
+    }
+
+    fn length(&self) -> f32 {
+        *self
+            .distances
+            .last()
+            .expect("paths have at least one point")
+    }
+
+    /// The point `distance` along the path, clamped to its ends.
+    fn point_at(&self, distance: f32) -> Vec3 {
+        let distance = distance.clamp(0.0, self.length());
+        let next = self
+            .distances
+            .partition_point(|&d| d < distance)
+            .clamp(1, self.points.len() - 1);
+        let (from, to) = (self.distances[next - 1], self.distances[next]);
+        let t = if to > from {
+            (distance - from) / (to - from)
+        } else {
+            1.0
+        };
+        self.points[next - 1].lerp(self.points[next], t)
+    }
+
+    /// Fraction of cruise speed for the current phase of the flight.
+    fn speed_factor(&self) -> f32 {
+        let takeoff = (self.progress / TAKEOFF_DISTANCE).clamp(0.0, 1.0);
+        let mut factor = TAKEOFF_SPEED.lerp(1.0, takeoff);
+        if self.lands {
+            let remaining = self.length() - self.progress;
+            let landing = (remaining / LANDING_DISTANCE).clamp(0.0, 1.0);
+            factor = factor.min(LANDING_SPEED.lerp(1.0, landing));
+        }
+        factor
+    }
+
+    /// Vertical speed (as a fraction of cruise speed) from the species' wingbeat pattern.
+    fn bob(&self, elapsed: f32) -> f32 {
+        match self.style {
+            // Steady flapping barely moves the body
+            FlightStyle::Direct => (elapsed * 8.0 + self.phase).sin() * 0.1,
+            // Wings closed between bursts: the bird rises and dips about once a second
+            FlightStyle::Bounding => (elapsed * TAU * 1.2 + self.phase).sin() * 0.5,
+            FlightStyle::Glide => 0.0,
+        }
+    }
+}
+
+fn cubic_bezier([p0, p1, p2, p3]: [Vec3; 4], t: f32) -> Vec3 {
+    let u = 1.0 - t;
+    p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t)
+}
+
+/// Plans a new [`FlightPath`] for every bird whose target changed, and drops the path
+/// once a bird has landed. Birds chasing a moving target fly without one, see
+/// [`chase_targets`].
+pub(super) fn plan_flight_paths(
+    mut commands: Commands,
+    catalog: Res<SpeciesCatalog>,
+    birds: Query<(
+        Entity,
+        &Bird,
+        &BirdState,
+        &PhysicalTranslation,
+        Option<&FlightPath>,
+    )>,
+) {
+    let mut rng = rand::rng();
+
+    for (entity, bird, state, position, path) in birds.iter() {
+        let chasing = matches!(state, BirdState::Mobbing { .. } | BirdState::Hawking { .. });
+        match state.target() {
+            Some(_) if chasing => {
+                if path.is_some() {
+                    commands.entity(entity).remove::<FlightPath>();
+                }
+            }
+            Some(target) if path.is_none_or(|path| path.end() != target) => {
+                let lands = !matches!(state, BirdState::Departing { .. });
+                let style = catalog[bird.species].flight;
+                commands
+                    .entity(entity)
+                    .insert(FlightPath::plan(position.0, target, lands, style, &mut rng));
+            }
+            None if path.is_some() => {
+                commands.entity(entity).remove::<FlightPath>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Moves each flying bird along its [`FlightPath`]. Flocking and obstacle avoidance
+/// adjust the resulting velocity afterwards.
+pub(super) fn fly_along_paths(
+    time: Res<Time>,
+    catalog: Res<SpeciesCatalog>,
+    mut birds: Query<(
+        &Bird,
+        &BirdState,
+        &PhysicalTranslation,
+        &mut Velocity,
+        &mut FlightPath,
+    )>,
+) {
+    for (bird, state, position, mut velocity, mut path) in birds.iter_mut() {
+        if !state.is_flying() {
+            continue;
+        }
+
+        let mut speed = catalog[bird.species].speed;
+        if matches!(state, BirdState::Fleeing { .. }) {
+            speed *= FLEEING_SPEED_FACTOR;
+        }
+        if !path.lands {
+            speed *= DEPARTURE_SPEED_FACTOR;
+            if path.progress >= path.length() {
+                velocity.0 = Vec3::ZERO;
+                continue;
+            }
+        }
+
+        let factor = path.speed_factor();
+        if path.point_at(path.progress).distance(position.0) < MAX_LEAD {
+            path.progress = (path.progress + speed * factor * time.delta_secs()).min(path.length());
+        }
+
+        let aim = path.point_at(path.progress + LOOKAHEAD);
+        let direction = (aim - position.0).normalize_or_zero();
+        let bob = path.bob(time.elapsed_secs());
+        velocity.0 = (direction + Vec3::Y * bob) * speed * factor;
+    }
+}
+
+/// Velocity straight at `target` for a bird with this cruise speed that is chasing it.
+pub(super) fn pursuit(from: Vec3, target: Vec3, speed: f32) -> Vec3 {
+    (target - from).normalize_or_zero() * speed * PURSUIT_SPEED_FACTOR
+}
+
+/// Birds chasing an insect or mobbing a predator fly straight at it, flat out. A planned
+/// path would go stale every time the target moved, and start again at takeoff speed.
+pub(super) fn chase_targets(
+    catalog: Res<SpeciesCatalog>,
+    mut birds: Query<(&Bird, &BirdState, &PhysicalTranslation, &mut Velocity)>,
+) {
+    for (bird, state, position, mut velocity) in birds.iter_mut() {
+        if let BirdState::Hawking { target, .. } | BirdState::Mobbing { target, .. } = state {
+            velocity.0 = pursuit(position.0, *target, catalog[bird.species].speed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn path(lands: bool, style: FlightStyle) -> FlightPath {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        FlightPath::plan(
+            Vec3::new(0.0, 3.0, 0.0),
+            Vec3::new(10.0, 3.0, 0.0),
+            lands,
+            style,
+            &mut rng,
+        )
+    }
+
+    #[test]
+    fn test_path_connects_start_and_end() {
+        let path = path(true, FlightStyle::Direct);
+        assert_eq!(path.point_at(0.0), Vec3::new(0.0, 3.0, 0.0));
+        assert_eq!(path.point_at(path.length()), Vec3::new(10.0, 3.0, 0.0));
+        assert!(
+            path.length() > 10.0,
+            "Curved path should be longer than a straight line"
+        );
+    }
+
+    #[test]
+    fn test_takeoff_climbs() {
+        let path = path(true, FlightStyle::Direct);
+        assert!(
+            path.point_at(1.0).y > 3.0,
+            "Birds should climb right after takeoff"
+        );
+    }
+
+    #[test]
+    fn test_gliders_swoop_down() {
+        let path = path(true, FlightStyle::Glide);
+        assert!(
+            path.point_at(1.0).y < 3.0,
+            "Owls should drop off their perch"
+        );
+        assert!(path.points.iter().all(|point| point.y <= 3.0));
+    }
+
+    #[test]
+    fn test_speed_ramps_up_and_brakes_for_landing() {
+        let mut path = path(true, FlightStyle::Bounding);
+        let takeoff = path.speed_factor();
+        path.progress = path.length() / 2.0;
+        let cruise = path.speed_factor();
+        path.progress = path.length() - 0.1;
+        let landing = path.speed_factor();
+
+        assert_eq!(cruise, 1.0);
+        assert!(takeoff < cruise, "Birds should accelerate after takeoff");
+        assert!(landing < takeoff, "Birds should brake before landing");
+    }
+
+    #[test]
+    fn test_departing_birds_keep_their_speed() {
+        let mut path = path(false, FlightStyle::Direct);
+        path.progress = path.length() - 0.1;
+        assert_eq!(path.speed_factor(), 1.0);
+    }
+
+    #[test]
+    fn test_only_gliders_fly_level() {
+        assert_eq!(path(true, FlightStyle::Glide).bob(0.3), 0.0);
+        assert!(path(true, FlightStyle::Bounding).bob(0.3) != 0.0);
+    }
+}
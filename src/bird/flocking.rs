@@ -0,0 +1,279 @@
+use std::ops::RangeInclusive;
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+use super::perching::{Perch, Spots, reserve_perch_on, reserve_random_perch};
+use super::voices::Voices;
+use super::{
+    ActiveCall, Bird, BirdState, PhysicalTranslation, Velocity, departure_target, settling_factor,
+};
+use crate::species::{BirdSpecies, SpeciesCatalog};
+
+/// How many birds of a social species arrive together.
+pub(super) const FLOCK_SIZE: RangeInclusive<usize> = 3..=6;
+
+/// Personal space between birds of the same species.
+const SAME_SPECIES_SPACING: f32 = 0.8;
+/// Birds of other species are kept at a much larger distance.
+const OTHER_SPECIES_SPACING: f32 = 2.5;
+/// Flockmates further apart than this don't influence each other.
+const FLOCK_RADIUS: f32 = 4.0;
+
+const SEPARATION_WEIGHT: f32 = 2.0;
+const ALIGNMENT_WEIGHT: f32 = 0.5;
+const COHESION_WEIGHT: f32 = 0.3;
+const LEADER_WEIGHT: f32 = 0.4;
+
+/// Followers may fly a little faster than their species' cruise speed to catch up.
+const MAX_SPEED_FACTOR: f32 = 1.3;
+
+/// A flock member follows a randomly chosen leader of its own species.
+#[derive(Component, Debug)]
+#[relationship(relationship_target = Flock)]
+pub(super) struct FollowsLeader(pub Entity);
+
+/// All birds following this leader.
+#[derive(Component, Debug)]
+#[relationship_target(relationship = FollowsLeader)]
+pub(super) struct Flock(Vec<Entity>);
+
+/// Followers take their destination from the leader, so the flock moves and departs together.
+/// Each follower reserves its own perch, preferably at the leader's spot.
+pub(super) fn sync_flock_members(
+    mut commands: Commands,
+    leaders: Query<(&BirdState, &Flock, Option<&Perch>), Without<FollowsLeader>>,
+    mut followers: Query<
+        (
+            Entity,
+            &mut BirdState,
+            &mut SpatialAudioEmitter,
+            Option<&Perch>,
+        ),
+        With<FollowsLeader>,
+    >,
+    mut spots: Spots,
+    mut voices: Voices,
+) {
+    let mut rng = rand::rng();
+
+    for (leader_state, flock, leader_perch) in leaders.iter() {
+        for follower in flock.iter() {
+            let Ok((entity, mut state, mut emitter, perch)) = followers.get_mut(follower) else {
+                continue;
+            };
+
+            let next_state = match leader_state {
+                // Perched followers take off after the leader; ones still in the air land
+                // first and catch up from there. Followers already at the leader's new spot
+                // just wait for it.
+                BirdState::FlyingToNext { .. }
+                    if !state.is_flying()
+                        && perch.map(|p| p.spot) != leader_perch.map(|p| p.spot) =>
+                {
+                    let current_spot = perch.map(|p| p.spot);
+                    let reservation = leader_perch
+                        .and_then(|p| reserve_perch_on(&mut rng, &mut spots, entity, p.spot))
+                        .or_else(|| {
+                            reserve_random_perch(&mut rng, &mut spots, entity, current_spot)
+                        });
+                    match reservation {
+                        Some((next_perch, target)) => {
+                            commands.entity(entity).insert(next_perch);
+                            BirdState::FlyingToNext { target }
+                        }
+                        None => {
+                            commands.entity(entity).remove::<Perch>();
+                            BirdState::Departing {
+                                target: departure_target(&mut rng),
+                            }
+                        }
+                    }
+                }
+                BirdState::Departing { target }
+                    if !matches!(*state, BirdState::Departing { .. }) =>
+                {
+                    commands.entity(entity).remove::<Perch>();
+                    BirdState::Departing { target: *target }
+                }
+                _ => continue,
+            };
+
+            voices.hush(&mut emitter);
+            commands.entity(entity).remove::<ActiveCall>();
+            *state = next_state;
+        }
+    }
+}
+
+struct Boid {
+    entity: Entity,
+    species: BirdSpecies,
+    /// The leader of this bird's flock, or the bird itself when it flies alone
+    flock: Entity,
+    position: Vec3,
+    velocity: Vec3,
+    flying: bool,
+}
+
+/// Adds separation, alignment, cohesion and leader following on top of the velocity the bird's
+/// behavior chose this frame. `advance_bird_physics` integrates the result in `FixedUpdate`.
+pub(super) fn apply_flocking(
+    catalog: Res<SpeciesCatalog>,
+    mut birds: Query<(
+        Entity,
+        &Bird,
+        &BirdState,
+        &PhysicalTranslation,
+        &mut Velocity,
+        Option<&FollowsLeader>,
+    )>,
+) {
+    let boids: Vec<Boid> = birds
+        .iter()
+        .map(|(entity, bird, state, position, velocity, follows)| Boid {
+            entity,
+            species: bird.species,
+            flock: follows.map_or(entity, |f| f.0),
+            position: position.0,
+            velocity: velocity.0,
+            flying: state.is_flying(),
+        })
+        .collect();
+
+    for (entity, bird, state, position, mut velocity, follows) in birds.iter_mut() {
+        let Some(target) = state.target() else {
+            continue;
+        };
+
+        let me = Boid {
+            entity,
+            species: bird.species,
+            flock: follows.map_or(entity, |f| f.0),
+            position: position.0,
+            velocity: velocity.0,
+            flying: true,
+        };
+        // Once the leader has landed, followers head for their own perches
+        let leader_position = follows
+            .and_then(|f| boids.iter().find(|b| b.entity == f.0))
+            .filter(|leader| leader.flying)
+            .map(|leader| leader.position);
+
+        // Flocking fades out as a bird settles onto its perch, so neighbors can't push it off
+        let settling = settling_factor((target - position.0).length());
+        let steer = steering(&me, &boids, leader_position) * settling;
+        let max_speed = catalog[bird.species].speed * MAX_SPEED_FACTOR;
+        velocity.0 = (velocity.0 + steer).clamp_length_max(max_speed);
+    }
+}
+
+fn steering(me: &Boid, boids: &[Boid], leader_position: Option<Vec3>) -> Vec3 {
+    let mut separation = Vec3::ZERO;
+    let mut heading = Vec3::ZERO;
+    let mut center = Vec3::ZERO;
+    let mut flockmates = 0;
+
+    for other in boids {
+        if other.entity == me.entity {
+            continue;
+        }
+
+        let offset = me.position - other.position;
+        let distance = offset.length();
+
+        // Same species keep a small personal space; other species give each other a wide berth
+        let spacing = if other.species == me.species {
+            SAME_SPECIES_SPACING
+        } else {
+            OTHER_SPECIES_SPACING
+        };
+        if distance < spacing && distance > f32::EPSILON {
+            separation += offset / distance * (1.0 - distance / spacing);
+        }
+
+        if other.flying && other.flock == me.flock && distance < FLOCK_RADIUS {
+            heading += other.velocity;
+            center += other.position;
+            flockmates += 1;
+        }
+    }
+
+    let mut steer = separation * SEPARATION_WEIGHT;
+
+    // Only followers steer with the flock; leaders pick the route
+    if let Some(leader_position) = leader_position {
+        if flockmates > 0 {
+            let count = flockmates as f32;
+            steer += (heading / count - me.velocity) * ALIGNMENT_WEIGHT;
+            steer += (center / count - me.position) * COHESION_WEIGHT;
+        }
+        steer += (leader_position - me.position) * LEADER_WEIGHT;
+    }
+
+    steer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boid(index: u32, species: BirdSpecies, flock: u32, position: Vec3) -> Boid {
+        Boid {
+            entity: Entity::from_raw_u32(index).unwrap(),
+            species,
+            flock: Entity::from_raw_u32(flock).unwrap(),
+            position,
+            velocity: Vec3::X,
+            flying: true,
+        }
+    }
+
+    #[test]
+    fn test_same_species_separate_at_close_range() {
+        let species = BirdSpecies::from_index(0);
+        let me = boid(1, species, 1, Vec3::ZERO);
+        let boids = [boid(2, species, 2, Vec3::new(0.4, 0.0, 0.0))];
+
+        let steer = steering(&me, &boids, None);
+        assert!(steer.x < 0.0, "Bird should move away from a close neighbor");
+    }
+
+    #[test]
+    fn test_other_species_avoided_at_longer_range() {
+        let me = boid(1, BirdSpecies::from_index(0), 1, Vec3::ZERO);
+        let same = [boid(
+            2,
+            BirdSpecies::from_index(0),
+            2,
+            Vec3::new(1.5, 0.0, 0.0),
+        )];
+        let other = [boid(
+            2,
+            BirdSpecies::from_index(1),
+            2,
+            Vec3::new(1.5, 0.0, 0.0),
+        )];
+
+        assert_eq!(
+            steering(&me, &same, None),
+            Vec3::ZERO,
+            "Same species beyond spacing should be ignored"
+        );
+        assert!(
+            steering(&me, &other, None).x < 0.0,
+            "Other species should be avoided at the same distance"
+        );
+    }
+
+    #[test]
+    fn test_follower_steers_toward_leader() {
+        let species = BirdSpecies::from_index(0);
+        let leader_position = Vec3::new(0.0, 0.0, 3.0);
+        let me = boid(2, species, 1, Vec3::ZERO);
+        let boids = [boid(1, species, 1, leader_position)];
+
+        let steer = steering(&me, &boids, Some(leader_position));
+        assert!(steer.z > 0.0, "Follower should steer toward its leader");
+    }
+}
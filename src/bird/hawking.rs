@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+
+use super::flocking::FollowsLeader;
+use super::mind::{Behavior, Mind};
+use super::needs::Needs;
+use super::perching::{Perch, Spots, reserve_random_perch};
+use super::{Bird, BirdState, PhysicalTranslation, departure_target};
+use crate::insect::{Insect, InsectCaught};
+use crate::scene::FoodSource;
+use crate::species::SpeciesCatalog;
+
+/// Dart out after an insect and catch it on the wing, then find a perch again. Birders call
+/// this hawking.
+pub(super) const HAWK: Behavior = Behavior("hawk");
+
+/// Birds notice insects within this distance.
+const SIGHTING_DISTANCE: f32 = 6.0;
+/// Birds this full let insects go by.
+const PECKISH: f32 = 0.2;
+/// An insect right in front of a bird beats a trip to the feeder.
+const HAWK_BONUS: f32 = 0.15;
+/// Never urgent enough to beat fear.
+const HAWK_MAX: f32 = 0.85;
+/// A bird this close to an insect has caught it. The chase follows the insect once it moves
+/// this far from where the bird is heading.
+const CATCH_DISTANCE: f32 = 0.3;
+/// Seconds a bird chases an insect before it gives up.
+const CHASE_TIME: f32 = 5.0;
+/// How much hunger one insect satisfies.
+const INSECT_MEAL: f32 = 0.3;
+
+/// The closest insect a bird at `from` can see, and where it is.
+fn nearest_insect(
+    insects: &Query<(Entity, &Insect, &Transform)>,
+    from: Vec3,
+) -> Option<(Entity, Vec3)> {
+    insects
+        .iter()
+        .filter(|(_, insect, _)| insect.is_prey())
+        .map(|(entity, _, transform)| (entity, transform.translation))
+        .filter(|(_, at)| at.distance(from) < SIGHTING_DISTANCE)
+        .min_by(|(_, a), (_, b)| a.distance(from).total_cmp(&b.distance(from)))
+}
+
+/// Aims the chase at where the insect is now, once it has moved off or the bird has reached
+/// where it was.
+fn follow(target: &mut Vec3, bird_at: Vec3, insect_at: Vec3) {
+    let closing_in = bird_at.distance(*target) < CATCH_DISTANCE;
+    if target.distance(insect_at) > CATCH_DISTANCE || closing_in {
+        *target = insect_at;
+    }
+}
+
+/// How much a bird with this much hunger wants to go after an insect it can see, if at all.
+fn appetite(hunger: f32) -> Option<f32> {
+    (hunger > PECKISH).then(|| (hunger + HAWK_BONUS).min(HAWK_MAX))
+}
+
+pub(super) fn score_hawk(
+    catalog: Res<SpeciesCatalog>,
+    insects: Query<(Entity, &Insect, &Transform)>,
+    mut birds: Query<(&Bird, &Needs, &PhysicalTranslation, &mut Mind), Without<FollowsLeader>>,
+) {
+    for (bird, needs, position, mut mind) in birds.iter_mut() {
+        let eats_insects = catalog[bird.species].forages.contains(&FoodSource::Insects);
+        if !mind.is_thinking() || !eats_insects {
+            continue;
+        }
+        if let Some(score) = appetite(needs.hunger)
+            && nearest_insect(&insects, position.0).is_some()
+        {
+            mind.score(HAWK, score);
+        }
+    }
+}
+
+/// Hawking birds chase the nearest insect until they catch it or lose it, then fly to a
+/// perch.
+pub(super) fn hawk(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut spots: Spots,
+    insects: Query<(Entity, &Insect, &Transform)>,
+    mut birds: Query<(
+        Entity,
+        &mut BirdState,
+        &mut Mind,
+        &mut Needs,
+        &PhysicalTranslation,
+    )>,
+) {
+    let mut rng = rand::rng();
+
+    for (entity, mut state, mut mind, mut needs, position) in birds.iter_mut() {
+        if mind.started(HAWK) {
+            match nearest_insect(&insects, position.0) {
+                Some((insect, at)) => {
+                    commands.entity(entity).remove::<Perch>();
+                    *state = BirdState::Hawking {
+                        insect,
+                        target: at,
+                        timer: Timer::from_seconds(CHASE_TIME, TimerMode::Once),
+                    };
+                }
+                None => mind.finish(),
+            }
+            continue;
+        }
+
+        let BirdState::Hawking {
+            insect,
+            target,
+            timer,
+        } = state.as_mut()
+        else {
+            continue;
+        };
+        if !mind.is_doing(HAWK) {
+            continue;
+        }
+
+        timer.tick(time.delta());
+        let prey = insects
+            .get(*insect)
+            .ok()
+            .filter(|(_, insect, _)| insect.is_prey())
+            .map(|(_, _, transform)| transform.translation);
+        match prey {
+            Some(at) if position.0.distance(at) < CATCH_DISTANCE => {
+                commands.trigger(InsectCaught(*insect));
+                needs.hunger = (needs.hunger - INSECT_MEAL).max(0.0);
+            }
+            Some(at) if !timer.is_finished() => {
+                follow(target, position.0, at);
+                continue;
+            }
+            // Got away
+            _ => {}
+        }
+
+        *state = match reserve_random_perch(&mut rng, &mut spots, entity, None) {
+            Some((perch, target)) => {
+                commands.entity(entity).insert(perch);
+                BirdState::FlyingToNext { target }
+            }
+            None => BirdState::Departing {
+                target: departure_target(&mut rng),
+            },
+        };
+        mind.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::flight::pursuit;
+    use super::super::mind::URGENT;
+    use super::*;
+
+    #[test]
+    fn test_insects_tempt_hungry_birds() {
+        assert_eq!(appetite(0.1), None, "Full birds let insects go by");
+        // An insect close by beats going to the feeder just as hungry
+        assert!(appetite(0.5).unwrap() > 0.5);
+        // but never a predator
+        assert!(appetite(1.0).unwrap() < URGENT);
+    }
+
+    #[test]
+    fn test_hawking_birds_catch_flying_insects() {
+        // A chickadee after a bee circling a flower at full speed
+        let chickadee_speed = 1.2;
+        let (bee_speed, circle) = (1.5, 1.0);
+        let flower = Vec3::new(0.0, 1.0, 0.0);
+        let bee_at = |t: f32| {
+            let angle = t * bee_speed / circle;
+            flower + Vec3::new(angle.cos(), 0.0, angle.sin()) * circle
+        };
+
+        let dt = 1.0 / 64.0;
+        let mut position = Vec3::new(5.0, 3.0, 0.0);
+        let mut target = bee_at(0.0);
+        let caught = (0..(CHASE_TIME / dt) as usize).any(|step| {
+            let insect = bee_at(step as f32 * dt);
+            if position.distance(insect) < CATCH_DISTANCE {
+                return true;
+            }
+            follow(&mut target, position, insect);
+            position += pursuit(position, target, chickadee_speed) * dt;
+            false
+        });
+        assert!(
+            caught,
+            "Ended {} from the bee",
+            position.distance(bee_at(CHASE_TIME))
+        );
+    }
+}
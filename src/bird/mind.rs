@@ -0,0 +1,301 @@
+use std::time::Duration;
+
+use bevy::ecs::system::ScheduleSystem;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::GameState;
+
+/// Scores at or above this are emergencies, which change a bird's mind even during the
+/// cooldown.
+pub(super) const URGENT: f32 = 0.9;
+/// Outside emergencies, a bird only switches to a behavior that beats its current one by this
+/// much, so near-ties don't make it dither.
+const SWITCH_MARGIN: f32 = 0.1;
+
+/// Utility AI for birds: every decision tick, each behavior's scorer rates how much a bird
+/// wants to do it, [`decide`] picks the winner and the winning behavior's action system
+/// carries it out.
+///
+/// Behaviors are plain systems registered with [`AddBehavior::add_behavior`]; nothing
+/// central needs to know about them.
+pub(super) struct MindPlugin;
+
+impl Plugin for MindPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MindSettings>()
+            .configure_sets(
+                Update,
+                (
+                    MindSystems::Think,
+                    MindSystems::Score,
+                    MindSystems::Decide,
+                    MindSystems::Act,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(Update, start_thinking.in_set(MindSystems::Think))
+            .add_systems(Update, decide.in_set(MindSystems::Decide));
+    }
+}
+
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(super) enum MindSystems {
+    /// Decides which birds think this frame
+    Think,
+    /// Behaviors score themselves for thinking birds
+    Score,
+    /// Thinking birds pick the best-scoring behavior
+    Decide,
+    /// Behaviors act for the birds doing them
+    Act,
+}
+
+/// How often birds reconsider what they're doing.
+#[derive(Resource, Clone, Debug)]
+pub(super) struct MindSettings {
+    /// Time between decision ticks
+    pub decision_interval: Duration,
+    /// After changing its mind, a bird sticks with its choice this long unless there's an
+    /// emergency or the behavior runs its course
+    pub mind_change_cooldown: Duration,
+}
+
+impl Default for MindSettings {
+    fn default() -> Self {
+        Self {
+            decision_interval: Duration::from_millis(500),
+            mind_change_cooldown: Duration::from_secs(4),
+        }
+    }
+}
+
+/// Identifies a behavior. Each behavior declares its own as a constant next to its systems.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(super) struct Behavior(pub &'static str);
+
+pub(super) trait AddBehavior {
+    /// Adds a behavior: `scorer` runs while birds are deciding what to do (see
+    /// [`Mind::is_thinking`]), `action` runs every frame for the birds that chose it.
+    fn add_behavior<S, A>(
+        &mut self,
+        scorer: impl IntoScheduleConfigs<ScheduleSystem, S>,
+        action: impl IntoScheduleConfigs<ScheduleSystem, A>,
+    ) -> &mut Self;
+}
+
+impl AddBehavior for App {
+    fn add_behavior<S, A>(
+        &mut self,
+        scorer: impl IntoScheduleConfigs<ScheduleSystem, S>,
+        action: impl IntoScheduleConfigs<ScheduleSystem, A>,
+    ) -> &mut Self {
+        self.add_systems(
+            Update,
+            (
+                scorer.in_set(MindSystems::Score),
+                action.in_set(MindSystems::Act),
+            ),
+        )
+    }
+}
+
+/// What a bird is doing and how much it wanted to do everything else, as of its last
+/// decision. Its `Debug` output is the quickest way to see why a bird does what it does.
+#[derive(Component, Debug)]
+pub(super) struct Mind {
+    current: Option<Behavior>,
+    /// Set on the frame a behavior is chosen, so its action can start it
+    started: bool,
+    /// Set by the current behavior's action once it has run its course
+    finished: bool,
+    thinking: bool,
+    scores: Vec<(Behavior, f32)>,
+    decision: Timer,
+    cooldown: Timer,
+    /// Seconds this mind has existed, and when each behavior last ended
+    clock: f32,
+    ended: HashMap<Behavior, f32>,
+}
+
+impl Mind {
+    /// A bird already busy with `behavior`, e.g. flying in to perch. Decision ticks are
+    /// staggered so birds don't all think on the same frame.
+    pub fn doing(behavior: Behavior, settings: &MindSettings, rng: &mut impl Rng) -> Self {
+        let mut decision = Timer::new(settings.decision_interval, TimerMode::Repeating);
+        decision.set_elapsed(settings.decision_interval.mul_f32(rng.random()));
+        Self {
+            current: Some(behavior),
+            started: false,
+            finished: false,
+            thinking: false,
+            scores: Vec::new(),
+            decision,
+            cooldown: Timer::new(settings.mind_change_cooldown, TimerMode::Once),
+            clock: 0.0,
+            ended: HashMap::default(),
+        }
+    }
+
+    /// True while the bird is doing `behavior` and it hasn't finished yet.
+    pub fn is_doing(&self, behavior: Behavior) -> bool {
+        self.current == Some(behavior) && !self.finished
+    }
+
+    /// True on the frame `behavior` was chosen.
+    pub fn started(&self, behavior: Behavior) -> bool {
+        self.started && self.is_doing(behavior)
+    }
+
+    /// True on the frame any behavior was chosen.
+    pub fn changed(&self) -> bool {
+        self.started
+    }
+
+    /// Scorers only need to run for birds that are deciding what to do this frame.
+    pub fn is_thinking(&self) -> bool {
+        self.thinking
+    }
+
+    /// Rates how much the bird wants to do `behavior`, from 0 (not at all) to 1.
+    /// Behaviors that aren't scored are out of the question.
+    pub fn score(&mut self, behavior: Behavior, score: f32) {
+        self.scores.push((behavior, score));
+    }
+
+    /// Ends the current behavior; the bird decides what to do next right away.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Seconds since the bird last stopped doing `behavior`, if it ever did.
+    pub fn since(&self, behavior: Behavior) -> Option<f32> {
+        self.ended.get(&behavior).map(|ended| self.clock - ended)
+    }
+}
+
+fn start_thinking(time: Res<Time>, mut minds: Query<&mut Mind>) {
+    for mut mind in minds.iter_mut() {
+        mind.clock += time.delta_secs();
+        mind.started = false;
+        mind.cooldown.tick(time.delta());
+        let tick = mind.decision.tick(time.delta()).just_finished();
+        mind.thinking = tick || mind.finished || mind.current.is_none();
+        if mind.thinking {
+            mind.scores.clear();
+        }
+    }
+}
+
+/// Switches each thinking bird to its best-scoring behavior, see [`choose`].
+pub(super) fn decide(settings: Res<MindSettings>, mut minds: Query<(&mut Mind, Option<&Name>)>) {
+    for (mut mind, name) in minds.iter_mut() {
+        if !mind.thinking {
+            continue;
+        }
+        mind.thinking = false;
+
+        let Some(next) = choose(
+            mind.current,
+            mind.finished,
+            &mind.scores,
+            mind.cooldown.is_finished(),
+        ) else {
+            continue;
+        };
+
+        debug!(
+            "{} changes its mind: {:?} -> {:?}, scores {:?}",
+            name.map_or("Bird", Name::as_str),
+            mind.current,
+            next,
+            mind.scores
+        );
+        if let Some(previous) = mind.current {
+            let clock = mind.clock;
+            mind.ended.insert(previous, clock);
+        }
+        mind.current = Some(next);
+        mind.started = true;
+        mind.finished = false;
+        mind.cooldown = Timer::new(settings.mind_change_cooldown, TimerMode::Once);
+    }
+}
+
+/// The behavior a bird should switch to, or `None` to carry on. A finished behavior can be
+/// chosen again, which starts it afresh.
+fn choose(
+    current: Option<Behavior>,
+    finished: bool,
+    scores: &[(Behavior, f32)],
+    cooldown_over: bool,
+) -> Option<Behavior> {
+    let (best, best_score) = scores
+        .iter()
+        .copied()
+        .filter(|(_, score)| *score > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    let Some(current) = current.filter(|_| !finished) else {
+        return Some(best);
+    };
+    if best == current {
+        return None;
+    }
+
+    let current_score = scores
+        .iter()
+        .find(|(behavior, _)| *behavior == current)
+        .map_or(0.0, |(_, score)| *score);
+    let urgent = best_score >= URGENT && best_score > current_score;
+    let better = cooldown_over && best_score > current_score + SWITCH_MARGIN;
+    (urgent || better).then_some(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REST: Behavior = Behavior("rest");
+    const SING: Behavior = Behavior("sing");
+    const FLEE: Behavior = Behavior("flee");
+
+    #[test]
+    fn test_birds_pick_the_best_score() {
+        assert_eq!(
+            choose(None, false, &[(REST, 0.3), (SING, 0.6)], true),
+            Some(SING)
+        );
+        assert_eq!(
+            choose(None, false, &[(REST, 0.0)], true),
+            None,
+            "Behaviors nobody wants aren't chosen"
+        );
+    }
+
+    #[test]
+    fn test_cooldown_keeps_birds_from_changing_their_mind() {
+        let scores = [(REST, 0.3), (SING, 0.6)];
+        assert_eq!(choose(Some(REST), false, &scores, false), None);
+        assert_eq!(choose(Some(REST), false, &scores, true), Some(SING));
+        assert_eq!(
+            choose(Some(REST), false, &[(REST, 0.55), (SING, 0.6)], true),
+            None,
+            "Near-ties shouldn't make birds dither"
+        );
+    }
+
+    #[test]
+    fn test_emergencies_ignore_the_cooldown() {
+        let scores = [(REST, 0.3), (FLEE, 0.95)];
+        assert_eq!(choose(Some(REST), false, &scores, false), Some(FLEE));
+    }
+
+    #[test]
+    fn test_finished_behaviors_make_way() {
+        let scores = [(REST, 0.3), (SING, 0.6)];
+        assert_eq!(choose(Some(SING), true, &scores, false), Some(SING));
+        assert_eq!(choose(Some(SING), true, &[(REST, 0.3)], false), Some(REST));
+    }
+}
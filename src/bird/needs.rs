@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::BirdState;
+
+/// Birds get this much hungrier and thirstier every second.
+const HUNGER_RATE: f32 = 0.02;
+const THIRST_RATE: f32 = 0.015;
+/// Flying tires birds out; sitting still lets them recover.
+const FLIGHT_FATIGUE: f32 = 0.04;
+const REST_RATE: f32 = 0.05;
+/// How quickly feeding and drinking satisfy a bird, per second.
+const FEEDING_RATE: f32 = 0.12;
+const DRINKING_RATE: f32 = 0.25;
+
+/// How hungry, thirsty and tired a bird is, each from 0 (not at all) to 1.
+/// Rises and falls with what the bird is doing, see [`update_needs`], and drives how much it
+/// wants to forage, drink or rest.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub(super) struct Needs {
+    pub hunger: f32,
+    pub thirst: f32,
+    pub fatigue: f32,
+}
+
+impl Needs {
+    /// Birds arrive with varying appetites, a little tired from the journey.
+    pub fn arriving(rng: &mut impl Rng) -> Self {
+        Self {
+            hunger: rng.random_range(0.2..0.8),
+            thirst: rng.random_range(0.1..0.6),
+            fatigue: rng.random_range(0.3..0.6),
+        }
+    }
+
+    /// Advances the needs by `dt` seconds of `state`.
+    pub fn tick(&mut self, state: &BirdState, dt: f32) {
+        self.hunger += HUNGER_RATE * dt;
+        self.thirst += THIRST_RATE * dt;
+        match state {
+            _ if state.is_flying() => self.fatigue += FLIGHT_FATIGUE * dt,
+            BirdState::Foraging { .. } | BirdState::Climbing { .. } => {
+                self.hunger -= FEEDING_RATE * dt
+            }
+            BirdState::Drinking { .. } => self.thirst -= DRINKING_RATE * dt,
+            _ => self.fatigue -= REST_RATE * dt,
+        }
+        self.hunger = self.hunger.clamp(0.0, 1.0);
+        self.thirst = self.thirst.clamp(0.0, 1.0);
+        self.fatigue = self.fatigue.clamp(0.0, 1.0);
+    }
+}
+
+/// Birds get hungrier, thirstier and more tired over time, and feeding, drinking and resting
+/// make up for it.
+pub(super) fn update_needs(time: Res<Time>, mut birds: Query<(&BirdState, &mut Needs)>) {
+    for (state, mut needs) in birds.iter_mut() {
+        needs.tick(state, time.delta_secs());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feeding_satisfies_hunger() {
+        let foraging = BirdState::Foraging {
+            timer: Timer::from_seconds(10.0, TimerMode::Once),
+        };
+        let mut needs = Needs {
+            hunger: 0.5,
+            thirst: 0.0,
+            fatigue: 0.0,
+        };
+        for _ in 0..100 {
+            needs.tick(&foraging, 0.1);
+        }
+        assert_eq!(needs.hunger, 0.0);
+        assert!(needs.thirst > 0.0, "Birds get thirsty while they eat");
+
+        let flying = BirdState::FlyingToNext { target: Vec3::ONE };
+        needs.tick(&flying, 1.0);
+        assert!(needs.fatigue > 0.0, "Flying is tiring");
+    }
+}
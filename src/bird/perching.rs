@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use rand::Rng;
+use rand::seq::IndexedRandom;
+
+use crate::scene::{Destination, PerchPoints};
+
+/// Every spot birds can fly to: tree canopies and trunks, seed patches, the feeder and the
+/// bird bath.
+pub(super) type Spots<'w, 's> =
+    Query<'w, 's, (Entity, &'static mut PerchPoints, &'static Destination)>;
+
+/// The perch slot a bird has reserved. Replacing or removing it (or despawning the bird)
+/// frees the slot again, see [`release_perch`].
+#[derive(Component, Clone, Copy, Debug)]
+pub(super) struct Perch {
+    pub spot: Entity,
+    pub slot: usize,
+}
+
+/// Reserves a free slot on a random tree canopy that still has room, skipping `exclude`.
+pub(super) fn reserve_random_perch(
+    rng: &mut impl Rng,
+    spots: &mut Spots,
+    bird: Entity,
+    exclude: Option<Entity>,
+) -> Option<(Perch, Vec3)> {
+    reserve_destination(rng, spots, bird, Destination::Canopy, exclude)
+}
+
+/// Reserves a free slot on a random spot of the given kind, skipping `exclude`.
+pub(super) fn reserve_destination(
+    rng: &mut impl Rng,
+    spots: &mut Spots,
+    bird: Entity,
+    destination: Destination,
+    exclude: Option<Entity>,
+) -> Option<(Perch, Vec3)> {
+    let open_spots: Vec<Entity> = spots
+        .iter()
+        .filter(|(spot, perches, kind)| {
+            **kind == destination && Some(*spot) != exclude && perches.has_free_slot()
+        })
+        .map(|(spot, _, _)| spot)
+        .collect();
+    let spot = *open_spots.choose(rng)?;
+    reserve_perch_on(rng, spots, bird, spot)
+}
+
+/// Reserves a random free slot on `spot`. Returns the reservation and where to fly to.
+pub(super) fn reserve_perch_on(
+    rng: &mut impl Rng,
+    spots: &mut Spots,
+    bird: Entity,
+    spot: Entity,
+) -> Option<(Perch, Vec3)> {
+    let (_, mut perches, _) = spots.get_mut(spot).ok()?;
+    let slot = *perches.free_slots().choose(rng)?;
+    perches
+        .reserve(slot, bird)
+        .then(|| (Perch { spot, slot }, perches.position(slot)))
+}
+
+pub(super) fn release_perch(
+    replace: On<Replace, Perch>,
+    perches: Query<&Perch>,
+    mut spots: Query<&mut PerchPoints>,
+) {
+    let Ok(perch) = perches.get(replace.entity) else {
+        return;
+    };
+    if let Ok(mut points) = spots.get_mut(perch.spot) {
+        points.release(perch.slot, replace.entity);
+    }
+}
@@ -0,0 +1,169 @@
+use std::ops::Range;
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use rand::Rng;
+use rand::seq::IteratorRandom;
+
+use super::flocking::FollowsLeader;
+use super::mind::{Behavior, Mind};
+use super::perching::{Spots, reserve_perch_on};
+use super::voices::Voices;
+use super::{ActiveCall, Bird, BirdState, PhysicalTranslation};
+use crate::scene::{Destination, Tree};
+use crate::species::{CallKind, SpeciesCatalog};
+use crate::wildlife::{Animal, AnimalKind};
+
+/// Fly to the tree nearest an animal the bird objects to and call at it from there.
+pub(super) const SCOLD: Behavior = Behavior("scold");
+
+/// Birds notice animals within this distance.
+const NOTICE_DISTANCE: f32 = 10.0;
+/// Seconds after scolding before a bird scolds again.
+const SCOLD_RECOVERY: f32 = 30.0;
+/// Keener than singing or feeding, but not an emergency.
+const SCOLD_URGE: f32 = 0.7;
+const SCOLD_DURATION: Range<f32> = 4.0..8.0;
+
+/// The closest animal of a kind the species scolds, and where it is.
+fn nearest_scolded(
+    animals: &Query<(&Animal, &Transform)>,
+    scolds: &[AnimalKind],
+    from: Vec3,
+) -> Option<Vec3> {
+    animals
+        .iter()
+        .filter(|(animal, _)| scolds.contains(&animal.kind))
+        .map(|(_, transform)| transform.translation)
+        .filter(|at| at.distance(from) < NOTICE_DISTANCE)
+        .min_by(|a, b| a.distance(from).total_cmp(&b.distance(from)))
+}
+
+/// Whether a bird that last scolded `since` seconds ago is ready to do it again.
+fn ready_to_scold(since: Option<f32>) -> bool {
+    since.is_none_or(|since| since > SCOLD_RECOVERY)
+}
+
+pub(super) fn score_scold(
+    catalog: Res<SpeciesCatalog>,
+    animals: Query<(&Animal, &Transform)>,
+    mut birds: Query<(&Bird, &PhysicalTranslation, &mut Mind), Without<FollowsLeader>>,
+) {
+    for (bird, position, mut mind) in birds.iter_mut() {
+        let scolds = &catalog[bird.species].scolds;
+        if !mind.is_thinking() || scolds.is_empty() || !ready_to_scold(mind.since(SCOLD)) {
+            continue;
+        }
+        if nearest_scolded(&animals, scolds, position.0).is_some() {
+            mind.score(SCOLD, SCOLD_URGE);
+        }
+    }
+}
+
+/// The tree with room for one more bird that is closest to the animal.
+fn nearest_tree(
+    spots: &Spots,
+    tree_transforms: &Query<&Transform, With<Tree>>,
+    animal_at: Vec3,
+) -> Option<Entity> {
+    spots
+        .iter()
+        .filter(|(_, perches, destination)| {
+            **destination == Destination::Canopy && perches.has_free_slot()
+        })
+        .filter_map(|(tree, _, _)| {
+            let at = tree_transforms.get(tree).ok()?.translation;
+            Some((tree, at.distance(animal_at)))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(tree, _)| tree)
+}
+
+/// Scolding birds fly to the tree nearest the animal, and once they've landed give their
+/// harshest call at it for a while.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn scold(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut voices: Voices,
+    catalog: Res<SpeciesCatalog>,
+    mut spots: Spots,
+    tree_transforms: Query<&Transform, With<Tree>>,
+    animals: Query<(&Animal, &Transform)>,
+    mut birds: Query<(
+        Entity,
+        &Bird,
+        &mut BirdState,
+        &mut Mind,
+        &PhysicalTranslation,
+        &mut SpatialAudioEmitter,
+    )>,
+) {
+    let mut rng = rand::rng();
+
+    for (entity, bird, mut state, mut mind, position, mut emitter) in birds.iter_mut() {
+        if !mind.is_doing(SCOLD) {
+            continue;
+        }
+        let species = &catalog[bird.species];
+
+        if mind.started(SCOLD) {
+            let perch = nearest_scolded(&animals, &species.scolds, position.0)
+                .and_then(|at| nearest_tree(&spots, &tree_transforms, at))
+                .and_then(|tree| reserve_perch_on(&mut rng, &mut spots, entity, tree));
+            match perch {
+                Some((perch, target)) => {
+                    commands.entity(entity).insert(perch);
+                    *state = BirdState::FlyingToNext { target };
+                }
+                None => mind.finish(),
+            }
+            continue;
+        }
+
+        let flying = state.is_flying();
+        match state.as_mut() {
+            BirdState::Perching { timer } => {
+                if !timer.tick(time.delta()).is_finished() {
+                    continue;
+                }
+                let call = species.calls(CallKind::Alarm).choose(&mut rng);
+                let Some(call) = call.or_else(|| species.calls(CallKind::Vocal).choose(&mut rng))
+                else {
+                    mind.finish();
+                    continue;
+                };
+                if let Some(handle) =
+                    voices.play(entity, bird.species, position.0, call, &mut emitter)
+                {
+                    commands.entity(entity).insert(ActiveCall(handle));
+                }
+                *state = BirdState::Vocalizing {
+                    timer: Timer::from_seconds(rng.random_range(SCOLD_DURATION), TimerMode::Once),
+                };
+            }
+            BirdState::Vocalizing { timer } => {
+                if timer.tick(time.delta()).is_finished() {
+                    voices.hush(&mut emitter);
+                    commands.entity(entity).remove::<ActiveCall>();
+                    mind.finish();
+                }
+            }
+            // On the way
+            _ if flying => {}
+            _ => mind.finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_birds_rest_between_scoldings() {
+        assert!(ready_to_scold(None), "Birds that never scolded are ready");
+        assert!(!ready_to_scold(Some(5.0)));
+        assert!(ready_to_scold(Some(SCOLD_RECOVERY + 1.0)));
+    }
+}
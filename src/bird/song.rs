@@ -0,0 +1,314 @@
+use std::ops::Range;
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use rand::Rng;
+use rand::seq::IteratorRandom;
+
+use super::behaviors::visit;
+use super::flocking::FollowsLeader;
+use super::mind::{Behavior, Mind};
+use super::perching::{Perch, Spots};
+use super::voices::Voices;
+use super::{ActiveCall, Bird, BirdState, PhysicalTranslation};
+use crate::scene::{DayClock, Destination, SeasonClock};
+use crate::species::{BirdSpecies, CallKind, SpeciesCatalog};
+use crate::weather::Weather;
+
+/// Sing or call from a perch in the treetops.
+pub(super) const SING: Behavior = Behavior("sing");
+
+/// Birds hear each other within this distance.
+const HEARING_RADIUS: f32 = 15.0;
+/// Chance that a bird answers a song it can respond to.
+const ANSWER_CHANCE: f64 = 0.6;
+/// Quiet gap after a song before a neighbor starts its own.
+const SONG_GAP: Range<f32> = 0.5..2.0;
+/// After singing, a bird's urge to sing again builds back up over this many seconds.
+const SONG_RECOVERY: f32 = 20.0;
+/// How much a bird answering a neighbor wants to sing.
+const ANSWER_URGE: f32 = 0.8;
+/// How much more a bird wants to sing just after a heavy rain stops.
+const AFTER_RAIN_URGE: f32 = 0.5;
+
+/// Triggered whenever a bird starts singing or calling.
+#[derive(Event, Clone, Copy, Debug)]
+pub(super) struct BirdVocalized {
+    pub bird: Entity,
+    pub species: BirdSpecies,
+    pub position: Vec3,
+    /// How long the song lasts, in seconds
+    pub duration: f32,
+}
+
+/// Marks a bird that wants to answer a song it heard. Perched birds answer once their perch
+/// timer runs out.
+#[derive(Component, Debug)]
+pub(super) struct Answering;
+
+#[derive(Debug, PartialEq)]
+enum Reaction {
+    Ignore,
+    /// Sing after this many seconds
+    Answer(f32),
+    /// Hold off singing for this many seconds
+    Wait(f32),
+}
+
+/// Perched birds within [`HEARING_RADIUS`] of a song either answer it once it ends, or hold
+/// their own song until it's over so neighbors don't sing over each other.
+pub(super) fn hear_song(
+    song: On<BirdVocalized>,
+    mut commands: Commands,
+    catalog: Res<SpeciesCatalog>,
+    mut birds: Query<(Entity, &Bird, &mut BirdState, &PhysicalTranslation)>,
+) {
+    let mut rng = rand::rng();
+
+    for (entity, bird, mut state, position) in birds.iter_mut() {
+        if entity == song.bird {
+            continue;
+        }
+        let BirdState::Perching { timer } = state.as_mut() else {
+            continue;
+        };
+
+        let answers = catalog[bird.species].answers.contains(&song.species);
+        let reaction = reaction(
+            position.0.distance(song.position),
+            answers,
+            timer.remaining_secs(),
+            song.duration,
+            &mut rng,
+        );
+        match reaction {
+            Reaction::Ignore => {}
+            Reaction::Answer(delay) => {
+                *timer = Timer::from_seconds(delay, TimerMode::Once);
+                commands.entity(entity).insert(Answering);
+            }
+            Reaction::Wait(delay) => {
+                *timer = Timer::from_seconds(delay, TimerMode::Once);
+            }
+        }
+    }
+}
+
+fn reaction(
+    distance: f32,
+    answers: bool,
+    remaining: f32,
+    song_duration: f32,
+    rng: &mut impl Rng,
+) -> Reaction {
+    if distance > HEARING_RADIUS {
+        return Reaction::Ignore;
+    }
+    let after_song = song_duration + rng.random_range(SONG_GAP);
+    if answers && rng.random_bool(ANSWER_CHANCE) {
+        Reaction::Answer(after_song)
+    } else if remaining < after_song {
+        Reaction::Wait(after_song)
+    } else {
+        Reaction::Ignore
+    }
+}
+
+/// How much a bird wants to sing: its species' singing rate at this time of day and year, held
+/// back for a while after its last song.
+fn sing_urge(rate: f32, since_last_song: Option<f32>) -> f32 {
+    rate * since_last_song.map_or(1.0, |since| (since / SONG_RECOVERY).min(1.0))
+}
+
+/// Rain quiets birds down; once a heavy rain stops they make up for it. Birds that wouldn't
+/// be singing at all still don't.
+fn weather_urge(urge: f32, rain: f32, after_rain: f32) -> f32 {
+    (urge * (1.0 - rain + AFTER_RAIN_URGE * after_rain)).min(1.0)
+}
+
+pub(super) fn score_sing(
+    day_clock: Res<DayClock>,
+    season: Res<SeasonClock>,
+    weather: Res<Weather>,
+    catalog: Res<SpeciesCatalog>,
+    mut birds: Query<(&Bird, &mut Mind, Has<Answering>)>,
+) {
+    let progress = day_clock.progress();
+    let song_season = season.song_season();
+    let rain = weather.conditions().rain;
+    let after_rain = weather.after_rain();
+
+    for (bird, mut mind, answering) in birds.iter_mut() {
+        let species = &catalog[bird.species];
+        // Silent species have nothing to sing
+        if !mind.is_thinking() || species.calls(CallKind::Vocal).next().is_none() {
+            continue;
+        }
+        let urge = sing_urge(
+            species.singing_rate(progress, song_season),
+            mind.since(SING),
+        );
+        let urge = weather_urge(urge, rain, after_rain);
+        if answering {
+            mind.score(SING, urge.max(ANSWER_URGE));
+        } else {
+            mind.score(SING, urge);
+        }
+    }
+}
+
+/// Singers settle on a perch in a canopy, wait out their perch timer (which [`hear_song`] may
+/// stretch so they don't sing over a neighbor) and sing one song. Flock members sing wherever
+/// the flock happens to be.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn sing(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut voices: Voices,
+    catalog: Res<SpeciesCatalog>,
+    mut spots: Spots,
+    mut birds: Query<(
+        Entity,
+        &mut Bird,
+        &mut BirdState,
+        &mut Mind,
+        &PhysicalTranslation,
+        &mut SpatialAudioEmitter,
+        Option<&Perch>,
+        Has<FollowsLeader>,
+    )>,
+) {
+    let mut rng = rand::rng();
+
+    for (entity, mut bird, mut state, mut mind, position, mut emitter, perch, is_follower) in
+        birds.iter_mut()
+    {
+        if !mind.is_doing(SING) {
+            continue;
+        }
+
+        let in_canopy = perch
+            .and_then(|perch| spots.get(perch.spot).ok())
+            .is_some_and(|(_, _, destination)| *destination == Destination::Canopy);
+        let flying = state.is_flying();
+        match state.as_mut() {
+            // Wait until landed
+            _ if flying && (in_canopy || is_follower) => {}
+            BirdState::Perching { timer } if !mind.started(SING) => {
+                if !timer.tick(time.delta()).is_finished() {
+                    continue;
+                }
+                let species = &catalog[bird.species];
+                let Some(call) = species.calls(CallKind::Vocal).choose(&mut rng) else {
+                    mind.finish();
+                    continue;
+                };
+                let handle = voices.play(entity, bird.species, position.0, call, &mut emitter);
+                if let Some(handle) = handle {
+                    commands.entity(entity).insert(ActiveCall(handle));
+                }
+                commands.entity(entity).remove::<Answering>();
+
+                let duration = rng.random_range(4.0..12.0);
+                commands.trigger(BirdVocalized {
+                    bird: entity,
+                    species: bird.species,
+                    position: position.0,
+                    duration,
+                });
+                *state = BirdState::Vocalizing {
+                    timer: Timer::from_seconds(duration, TimerMode::Once),
+                };
+            }
+            BirdState::Vocalizing { timer } => {
+                if timer.tick(time.delta()).is_finished() {
+                    voices.hush(&mut emitter);
+                    commands.entity(entity).remove::<ActiveCall>();
+                    mind.finish();
+                }
+            }
+            _ if !flying && (in_canopy || is_follower) => {
+                *state = BirdState::Perching {
+                    timer: Timer::from_seconds(rng.random_range(0.5..2.0), TimerMode::Once),
+                };
+            }
+            // Songbirds sing from the treetops
+            _ => {
+                let canopy = Destination::Canopy;
+                match visit(
+                    &mut commands,
+                    &mut rng,
+                    &mut spots,
+                    entity,
+                    &mut bird,
+                    canopy,
+                    perch,
+                ) {
+                    Some(next) => *state = next,
+                    None => mind.finish(),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    #[test]
+    fn test_distant_songs_are_ignored() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        assert_eq!(
+            reaction(HEARING_RADIUS + 1.0, true, 0.5, 5.0, &mut rng),
+            Reaction::Ignore
+        );
+    }
+
+    #[test]
+    fn test_answers_come_after_the_song() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut answered = 0;
+        for _ in 0..50 {
+            if let Reaction::Answer(delay) = reaction(3.0, true, 10.0, 5.0, &mut rng) {
+                assert!(delay > 5.0, "Answers shouldn't overlap the song");
+                answered += 1;
+            }
+        }
+        assert!(answered > 10, "Birds should often answer their own species");
+    }
+
+    #[test]
+    fn test_birds_take_a_break_between_songs() {
+        assert_eq!(sing_urge(0.8, None), 0.8);
+        assert_eq!(sing_urge(0.8, Some(0.0)), 0.0);
+        assert!(sing_urge(0.8, Some(SONG_RECOVERY / 2.0)) < 0.8);
+        assert_eq!(sing_urge(0.8, Some(SONG_RECOVERY * 2.0)), 0.8);
+    }
+
+    #[test]
+    fn test_neighbors_wait_for_song_to_end() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        match reaction(3.0, false, 1.0, 5.0, &mut rng) {
+            Reaction::Wait(delay) => assert!(delay > 5.0),
+            other => panic!("Expected the neighbor to wait, got {other:?}"),
+        }
+        assert_eq!(
+            reaction(3.0, false, 20.0, 5.0, &mut rng),
+            Reaction::Ignore,
+            "Birds that sing after the song ends don't need to wait"
+        );
+    }
+
+    #[test]
+    fn test_rain_quiets_birds_until_it_stops() {
+        assert_eq!(weather_urge(0.6, 0.0, 0.0), 0.6);
+        assert_eq!(weather_urge(0.6, 1.0, 0.0), 0.0, "Silent in a downpour");
+        assert!(weather_urge(0.6, 0.35, 0.0) < 0.6);
+        assert!(weather_urge(0.6, 0.0, 1.0) > 0.6, "Chorus after the rain");
+        assert_eq!(weather_urge(0.0, 0.0, 1.0), 0.0);
+    }
+}
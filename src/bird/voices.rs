@@ -0,0 +1,311 @@
+use std::time::Duration;
+
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy_kira_audio::SpatialRadius;
+use bevy_kira_audio::prelude::*;
+
+use super::{ActiveCall, Bird, PhysicalTranslation};
+use crate::species::BirdSpecies;
+
+/// Most bird calls heard at once. More than this and the chorus turns to mush, however many
+/// birds are in the clearing.
+const MAX_VOICES: usize = 6;
+/// The voices that get the full mix. The rest are ducked under them.
+const LEAD_VOICES: usize = 3;
+
+/// How far a bird's calls carry at full volume, and once ducked. The spatial mix sets each
+/// call's volume from its distance and this radius every frame, so shrinking the radius is
+/// what quiets a call, and it quiets distant birds far more than near ones.
+pub(super) const CALL_RADIUS: f32 = 60.0;
+const DUCKED_RADIUS: f32 = 35.0;
+/// How quickly a bird's calls duck and come back up, per second.
+const DUCK_RATE: f32 = 2.0;
+
+/// Calls fade in and out over these times instead of starting and stopping with a click.
+const FADE_IN: Duration = Duration::from_millis(300);
+const FADE_OUT: Duration = Duration::from_millis(800);
+/// A call can take this long to start playing, e.g. while its clip loads. Until then it
+/// holds its voice without an instance to show for it.
+const STARTING: f32 = 1.0;
+
+/// How much being the only one of its kind in the clearing counts for, against being right
+/// next to the listener (1).
+const RARITY_WEIGHT: f32 = 0.3;
+
+/// A bird call taking up one of the voices.
+struct Voice {
+    bird: Entity,
+    species: BirdSpecies,
+    instance: Handle<AudioInstance>,
+    priority: f32,
+    /// When it was played, in seconds since startup
+    started: f32,
+}
+
+/// The bird calls playing now, most important first, and what's needed to rank new ones.
+#[derive(Resource, Default)]
+pub(super) struct VoiceManager {
+    voices: Vec<Voice>,
+    listener: Vec3,
+    /// How many birds of each species are in the clearing
+    present: HashMap<BirdSpecies, usize>,
+}
+
+impl VoiceManager {
+    fn priority(&self, species: BirdSpecies, position: Vec3) -> f32 {
+        let same_species = self.present.get(&species).copied().unwrap_or(1);
+        priority(position.distance(self.listener), same_species)
+    }
+}
+
+/// How much a call deserves one of the voices: nearer birds first, then rarer ones.
+fn priority(distance: f32, same_species: usize) -> f32 {
+    let nearness = (1.0 - distance / CALL_RADIUS).clamp(0.0, 1.0);
+    nearness + RARITY_WEIGHT / same_species.max(1) as f32
+}
+
+/// Plays and stops bird calls, keeping within [`MAX_VOICES`].
+#[derive(SystemParam)]
+pub(super) struct Voices<'w> {
+    time: Res<'w, Time>,
+    audio: Res<'w, Audio>,
+    manager: ResMut<'w, VoiceManager>,
+    instances: ResMut<'w, Assets<AudioInstance>>,
+}
+
+impl Voices<'_> {
+    /// Starts a call from the bird, if it's important enough to be heard over the others.
+    /// When the voices are all in use, [`manage_voices`] takes one back from whichever call
+    /// ranks lowest by the end of the frame. A call that isn't played is still given, just
+    /// out of earshot; birds answer it all the same.
+    pub(super) fn play(
+        &mut self,
+        bird: Entity,
+        species: BirdSpecies,
+        position: Vec3,
+        clip: &Handle<AudioSource>,
+        emitter: &mut SpatialAudioEmitter,
+    ) -> Option<Handle<AudioInstance>> {
+        let priority = self.manager.priority(species, position);
+        let voices = &self.manager.voices;
+        if voices.len() >= MAX_VOICES && voices.iter().all(|voice| voice.priority >= priority) {
+            return None;
+        }
+
+        let instance = self
+            .audio
+            .play(clip.clone())
+            .fade_in(AudioTween::linear(FADE_IN))
+            .handle();
+        emitter.instances.push(instance.clone());
+        self.manager.voices.push(Voice {
+            bird,
+            species,
+            instance: instance.clone(),
+            priority,
+            started: self.time.elapsed_secs(),
+        });
+        Some(instance)
+    }
+
+    /// Fades out everything the bird is calling, freeing its voices.
+    pub(super) fn hush(&mut self, emitter: &mut SpatialAudioEmitter) {
+        for handle in emitter.instances.drain(..) {
+            fade_out(&mut self.instances, &handle);
+            self.manager.voices.retain(|voice| voice.instance != handle);
+        }
+    }
+}
+
+fn fade_out(instances: &mut Assets<AudioInstance>, handle: &Handle<AudioInstance>) {
+    if let Some(instance) = instances.get_mut(handle) {
+        instance.stop(AudioTween::linear(FADE_OUT));
+    }
+}
+
+/// Takes a call that lost its voice off the bird giving it.
+fn silence(commands: &mut Commands, voice: &Voice) {
+    let instance = voice.instance.clone();
+    commands
+        .entity(voice.bird)
+        .queue_silenced(move |mut bird: EntityWorldMut| {
+            if let Some(mut emitter) = bird.get_mut::<SpatialAudioEmitter>() {
+                emitter.instances.retain(|handle| *handle != instance);
+            }
+            if bird
+                .get::<ActiveCall>()
+                .is_some_and(|call| call.0 == instance)
+            {
+                bird.remove::<ActiveCall>();
+            }
+        });
+}
+
+/// Re-ranks the calls as birds come and go, fading out any pushed past [`MAX_VOICES`] and
+/// ducking those outside the [`LEAD_VOICES`].
+pub(super) fn manage_voices(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut manager: ResMut<VoiceManager>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    listener: Query<&GlobalTransform, With<SpatialAudioReceiver>>,
+    mut birds: Query<(Entity, &Bird, &PhysicalTranslation, &mut SpatialRadius)>,
+) {
+    let manager = manager.as_mut();
+    if let Ok(listener) = listener.single() {
+        manager.listener = listener.translation();
+    }
+    manager.present.clear();
+    for (_, bird, ..) in birds.iter() {
+        *manager.present.entry(bird.species).or_default() += 1;
+    }
+
+    // Finished calls and departed birds give their voices back
+    let now = time.elapsed_secs();
+    manager.voices.retain(|voice| {
+        birds.contains(voice.bird)
+            && match instances.get(&voice.instance) {
+                Some(instance) => !matches!(instance.state(), PlaybackState::Stopped),
+                None => now - voice.started < STARTING,
+            }
+    });
+    for index in 0..manager.voices.len() {
+        let Voice { bird, species, .. } = manager.voices[index];
+        if let Ok((_, _, position, ..)) = birds.get(bird) {
+            manager.voices[index].priority = manager.priority(species, position.0);
+        }
+    }
+    manager
+        .voices
+        .sort_by(|a, b| b.priority.total_cmp(&a.priority));
+
+    // Calls pushed down by newer ones, or by their birds flying off, lose their voices
+    for voice in manager.voices.drain(MAX_VOICES.min(manager.voices.len())..) {
+        fade_out(&mut instances, &voice.instance);
+        silence(&mut commands, &voice);
+    }
+
+    let step = (DUCK_RATE * time.delta_secs()).min(1.0);
+    for (entity, _, _, mut radius) in birds.iter_mut() {
+        let leads = manager
+            .voices
+            .iter()
+            .take(LEAD_VOICES)
+            .any(|voice| voice.bird == entity);
+        let ducked = !leads && manager.voices.iter().any(|voice| voice.bird == entity);
+        let target = if ducked { DUCKED_RADIUS } else { CALL_RADIUS };
+        radius.radius += (target - radius.radius) * step;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    /// Plays a call from each bird, as the behaviors do.
+    fn call(world: &mut World, birds: Vec<Entity>) {
+        world
+            .run_system_once(
+                move |mut commands: Commands,
+                      mut voices: Voices,
+                      mut callers: Query<(
+                    &Bird,
+                    &PhysicalTranslation,
+                    &mut SpatialAudioEmitter,
+                )>| {
+                    for &entity in &birds {
+                        let (bird, position, mut emitter) = callers.get_mut(entity).unwrap();
+                        let clip = Handle::default();
+                        if let Some(handle) =
+                            voices.play(entity, bird.species, position.0, &clip, &mut emitter)
+                        {
+                            commands.entity(entity).insert(ActiveCall(handle));
+                        }
+                    }
+                },
+            )
+            .unwrap();
+    }
+
+    fn calling(world: &World, bird: Entity) -> bool {
+        let emitter = world.get::<SpatialAudioEmitter>(bird).unwrap();
+        let active = world.get::<ActiveCall>(bird).is_some();
+        assert_eq!(emitter.instances.len(), usize::from(active));
+        active
+    }
+
+    fn ducked(world: &World, bird: Entity) -> bool {
+        let radius = world.get::<SpatialRadius>(bird).unwrap().radius;
+        (radius - DUCKED_RADIUS).abs() < 0.1
+    }
+
+    #[test]
+    fn test_near_and_rare_birds_keep_their_voices() {
+        let mut world = World::new();
+        world.init_resource::<Audio>();
+        world.init_resource::<Assets<AudioInstance>>();
+        world.init_resource::<VoiceManager>();
+        world.init_resource::<Time>();
+        world.spawn((GlobalTransform::IDENTITY, SpatialAudioReceiver));
+        let mut spawn = |species: usize, distance: f32| {
+            world
+                .spawn((
+                    Bird {
+                        species: BirdSpecies::from_index(species),
+                        visits: 0,
+                        max_visits: 1,
+                    },
+                    PhysicalTranslation(Vec3::X * distance),
+                    SpatialRadius {
+                        radius: CALL_RADIUS,
+                    },
+                    SpatialAudioEmitter::default(),
+                ))
+                .id()
+        };
+        // A crowd of one species at 2, 4, .. 16 and a lone bird of another among them
+        let common: Vec<Entity> = (1..=8).map(|i| spawn(0, i as f32 * 2.0)).collect();
+        let rare = spawn(1, 14.0);
+        world.run_system_once(manage_voices).unwrap();
+
+        // The nearest six fill the voices, and the furthest can't get one
+        call(&mut world, common[..6].to_vec());
+        call(&mut world, vec![common[7]]);
+        assert!(common[..6].iter().all(|&bird| calling(&world, bird)));
+        assert!(!calling(&world, common[7]));
+
+        // The nearest flies off, and the rare bird calls over the crowd
+        world.get_mut::<PhysicalTranslation>(common[0]).unwrap().0 = Vec3::X * 50.0;
+        call(&mut world, vec![rare, common[6]]);
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(500));
+        world.run_system_once(manage_voices).unwrap();
+
+        assert!(calling(&world, rare));
+        assert!(common[1..6].iter().all(|&bird| calling(&world, bird)));
+        assert!(
+            !calling(&world, common[0]),
+            "The bird that flew off ranks lowest now, and loses its voice"
+        );
+        assert!(!calling(&world, common[6]) && !calling(&world, common[7]));
+
+        // The rare bird and the two nearest lead, the rest are ducked under them
+        assert!(!ducked(&world, rare) && !ducked(&world, common[1]) && !ducked(&world, common[2]));
+        assert!(common[3..6].iter().all(|&bird| ducked(&world, bird)));
+        assert!(!ducked(&world, common[0]) && !ducked(&world, common[7]));
+    }
+
+    #[test]
+    fn test_near_and_rare_birds_come_first() {
+        assert!(priority(5.0, 3) > priority(20.0, 3), "Nearer first");
+        assert!(priority(10.0, 1) > priority(10.0, 4), "Rarer first");
+        // A lone bird across the clearing still beats one of a crowd a little nearer
+        assert!(priority(15.0, 1) > priority(10.0, 5));
+        assert_eq!(priority(CALL_RADIUS * 2.0, 1), RARITY_WEIGHT);
+    }
+}
@@ -0,0 +1,377 @@
+//! Lays out a forest from a seed: the terrain, trees spaced by Poisson-disk sampling, their
+//! species and sizes, open clearings and undergrowth between the trees.
+//!
+//! Generation only uses the seeded RNG and integer-hashed noise, so the same seed always gives
+//! the same forest on the same platform. Share a [`ForestSeed`] to share a forest. Across
+//! platforms the standard library's floating-point functions (`sin`, `powf` and the like) may
+//! round differently, which can move a tree or tip a choice between two species.
+
+use std::f32::consts::{SQRT_2, TAU};
+
+use bevy::prelude::*;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::terrain::{HILL_HEIGHT, HILLS_START, MAIN_CLEARING, Terrain};
+
+/// Which forest to grow. Insert it as a resource before the game starts to pick another one.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ForestSeed(pub u64);
+
+impl Default for ForestSeed {
+    fn default() -> Self {
+        Self(DEFAULT_SEED)
+    }
+}
+
+impl std::fmt::Display for ForestSeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+impl std::str::FromStr for ForestSeed {
+    type Err = String;
+
+    /// A seed as shown, in hex after `0x`, or in decimal.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => s.parse(),
+        }
+        .map(Self)
+        .map_err(|error| format!("{s:?} isn't a seed: {error}"))
+    }
+}
+
+/// The forest everyone gets unless they pick another.
+const DEFAULT_SEED: u64 = 0xf0_2e57;
+
+/// Trees grow in a square this far out from the middle of the clearing in each direction,
+/// inside where birds arrive from and leave to.
+pub const FOREST_HALF_SIZE: f32 = 16.0;
+/// Closest two trunks can be.
+const TREE_SPACING: f32 = 3.2;
+/// Candidates tried around each point before giving up on it, see [`poisson_disk`].
+const POISSON_ATTEMPTS: usize = 30;
+
+/// Other clearings scattered through the forest, and how big they are.
+const EXTRA_CLEARINGS: std::ops::RangeInclusive<usize> = 2..=3;
+const CLEARING_RADIUS: std::ops::Range<f32> = 2.5..4.5;
+
+/// Shrubs tried for the undergrowth; wetter ground keeps more of them.
+const SHRUB_CANDIDATES: usize = 220;
+/// Shrubs keep at least this far from trunks.
+const SHRUB_TRUNK_GAP: f32 = 0.9;
+/// Trees and shrubs keep this far back from the water's edge.
+const TREE_SHORE_GAP: f32 = 1.0;
+const SHRUB_SHORE_GAP: f32 = 0.3;
+
+/// Kinds of tree, each suited to different ground.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TreeKind {
+    /// Tall evergreen on high, dry ground
+    Conifer,
+    /// Broad and spreading, happy almost anywhere
+    Oak,
+    /// Slender, in damp hollows
+    Aspen,
+}
+
+impl TreeKind {
+    /// Size relative to the standard tree.
+    fn scale_range(self) -> std::ops::Range<f32> {
+        match self {
+            Self::Conifer => 0.9..1.4,
+            Self::Oak => 1.0..1.5,
+            Self::Aspen => 0.8..1.2,
+        }
+    }
+
+    /// How well this tree grows at a given height above the clearing and moisture.
+    fn suitability(self, height: f32, moisture: f32) -> f32 {
+        let height = (height / HILL_HEIGHT).clamp(-1.0, 1.0);
+        match self {
+            Self::Conifer => (1.0 - moisture) + height.max(0.0),
+            Self::Oak => 0.6,
+            Self::Aspen => moisture * moisture * 2.0 - height.max(0.0),
+        }
+        .max(0.05)
+    }
+}
+
+/// One tree: where its trunk meets the ground, its kind and size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ForestTree {
+    pub position: Vec3,
+    pub kind: TreeKind,
+    pub scale: f32,
+    /// Which of the grown models of its kind this tree uses
+    pub variant: usize,
+    /// Turn about its trunk, so trees sharing a model don't look alike
+    pub yaw: f32,
+}
+
+/// An open patch with no trees.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Clearing {
+    pub center: Vec2,
+    pub radius: f32,
+}
+
+impl Clearing {
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.center.distance(point) < self.radius
+    }
+}
+
+/// A bush in the undergrowth.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Shrub {
+    pub position: Vec3,
+    pub scale: f32,
+}
+
+/// Everything that grows in one forest.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct Forest {
+    pub seed: u64,
+    pub terrain: Terrain,
+    /// The main clearing comes first
+    pub clearings: Vec<Clearing>,
+    pub trees: Vec<ForestTree>,
+    pub undergrowth: Vec<Shrub>,
+}
+
+impl Forest {
+    /// Grows the forest for `seed`.
+    pub fn generate(seed: ForestSeed) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed.0);
+        let terrain = Terrain::generate(&mut rng);
+        let clearings = clearings(&mut rng);
+
+        let trees: Vec<ForestTree> = poisson_disk(&mut rng, FOREST_HALF_SIZE, TREE_SPACING)
+            .into_iter()
+            .filter(|point| {
+                !clearings.iter().any(|clearing| clearing.contains(*point))
+                    && terrain.pond.shore_distance(*point) > TREE_SHORE_GAP
+            })
+            .map(|point| {
+                let height = terrain.height(point.x, point.y);
+                let moisture = terrain.moisture(point.x, point.y);
+                let kind = *[TreeKind::Conifer, TreeKind::Oak, TreeKind::Aspen]
+                    .choose_weighted(&mut rng, |kind| kind.suitability(height, moisture))
+                    .expect("every tree kind has a positive weight");
+                ForestTree {
+                    position: Vec3::new(point.x, height, point.y),
+                    kind,
+                    scale: rng.random_range(kind.scale_range()),
+                    variant: rng.random::<u32>() as usize,
+                    yaw: rng.random_range(0.0..TAU),
+                }
+            })
+            .collect();
+
+        let undergrowth = (0..SHRUB_CANDIDATES)
+            .filter_map(|_| {
+                let point = Vec2::new(
+                    rng.random_range(-FOREST_HALF_SIZE..FOREST_HALF_SIZE),
+                    rng.random_range(-FOREST_HALF_SIZE..FOREST_HALF_SIZE),
+                );
+                let moisture = terrain.moisture(point.x, point.y);
+                let keep = rng.random::<f32>() < moisture;
+                let scale = rng.random_range(0.5..1.1);
+                let open = !clearings[0].contains(point)
+                    && terrain.pond.shore_distance(point) > SHRUB_SHORE_GAP
+                    && trees
+                        .iter()
+                        .all(|tree| tree.position.xz().distance(point) > SHRUB_TRUNK_GAP);
+                (keep && open).then(|| Shrub {
+                    position: Vec3::new(point.x, terrain.height(point.x, point.y), point.y),
+                    scale,
+                })
+            })
+            .collect();
+
+        Self {
+            seed: seed.0,
+            terrain,
+            clearings,
+            trees,
+            undergrowth,
+        }
+    }
+}
+
+/// The main clearing, then a few more out in the forest.
+fn clearings(rng: &mut impl Rng) -> Vec<Clearing> {
+    let main = Clearing {
+        center: Vec2::ZERO,
+        radius: MAIN_CLEARING,
+    };
+    let extra = rng.random_range(EXTRA_CLEARINGS);
+    std::iter::once(main)
+        .chain((0..extra).map(|_| {
+            let radius = rng.random_range(CLEARING_RADIUS);
+            let distance = rng.random_range(HILLS_START..FOREST_HALF_SIZE - radius / 2.0);
+            Clearing {
+                center: Vec2::from_angle(rng.random_range(0.0..TAU)) * distance,
+                radius,
+            }
+        }))
+        .collect()
+}
+
+/// Bridson's Poisson-disk sampling: points spread evenly over a square `half_size` out from
+/// the origin, none closer than `spacing`, without the grid look of regular spacing.
+fn poisson_disk(rng: &mut impl Rng, half_size: f32, spacing: f32) -> Vec<Vec2> {
+    // Each grid cell holds at most one point
+    let cell = spacing / SQRT_2;
+    let cells = (2.0 * half_size / cell).ceil() as usize;
+    let mut grid: Vec<Option<usize>> = vec![None; cells * cells];
+    let cell_of = |point: Vec2| {
+        let x = ((point.x + half_size) / cell) as usize;
+        let y = ((point.y + half_size) / cell) as usize;
+        (x.min(cells - 1), y.min(cells - 1))
+    };
+
+    let first = Vec2::new(
+        rng.random_range(-half_size..half_size),
+        rng.random_range(-half_size..half_size),
+    );
+    let mut points = vec![first];
+    let mut active = vec![0];
+    let (x, y) = cell_of(first);
+    grid[y * cells + x] = Some(0);
+
+    while !active.is_empty() {
+        let index = rng.random_range(0..active.len());
+        let center = points[active[index]];
+        let found = (0..POISSON_ATTEMPTS).find_map(|_| {
+            let candidate = center
+                + Vec2::from_angle(rng.random_range(0.0..TAU))
+                    * rng.random_range(spacing..2.0 * spacing);
+            if candidate.abs().max_element() >= half_size {
+                return None;
+            }
+            let (x, y) = cell_of(candidate);
+            let crowded = (y.saturating_sub(2)..(y + 3).min(cells)).any(|ny| {
+                (x.saturating_sub(2)..(x + 3).min(cells)).any(|nx| {
+                    grid[ny * cells + nx]
+                        .is_some_and(|other| points[other].distance(candidate) < spacing)
+                })
+            });
+            (!crowded).then_some((candidate, x, y))
+        });
+
+        match found {
+            Some((point, x, y)) => {
+                grid[y * cells + x] = Some(points.len());
+                active.push(points.len());
+                points.push(point);
+            }
+            None => {
+                active.swap_remove(index);
+            }
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::GROUND_LEVEL;
+
+    #[test]
+    fn test_seeds_read_back_as_shown() {
+        let seed = ForestSeed::default();
+        assert_eq!(seed.to_string().parse(), Ok(seed));
+        assert_eq!("42".parse(), Ok(ForestSeed(42)));
+        assert!("forest".parse::<ForestSeed>().is_err());
+    }
+
+    #[test]
+    fn test_same_seed_same_forest() {
+        let forest = Forest::generate(ForestSeed(42));
+        assert_eq!(forest, Forest::generate(ForestSeed(42)));
+        assert_ne!(forest.trees, Forest::generate(ForestSeed(43)).trees);
+    }
+
+    #[test]
+    fn test_trees_keep_their_distance() {
+        let forest = Forest::generate(ForestSeed::default());
+        assert!(forest.trees.len() > 20, "{}", forest.trees.len());
+        for (i, tree) in forest.trees.iter().enumerate() {
+            assert!(tree.position.xz().abs().max_element() < FOREST_HALF_SIZE);
+            for other in &forest.trees[i + 1..] {
+                let distance = tree.position.xz().distance(other.position.xz());
+                assert!(distance >= TREE_SPACING, "{tree:?} {other:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_clearings_stay_open() {
+        let forest = Forest::generate(ForestSeed(7));
+        assert!(forest.clearings.len() > *EXTRA_CLEARINGS.start());
+        for clearing in &forest.clearings {
+            assert!(
+                forest
+                    .trees
+                    .iter()
+                    .all(|tree| !clearing.contains(tree.position.xz()))
+            );
+        }
+        assert!(
+            forest
+                .undergrowth
+                .iter()
+                .all(|shrub| !forest.clearings[0].contains(shrub.position.xz()))
+        );
+
+        // Nothing grows in the pond
+        let pond = forest.terrain.pond;
+        assert!(
+            forest
+                .trees
+                .iter()
+                .map(|tree| tree.position)
+                .chain(forest.undergrowth.iter().map(|shrub| shrub.position))
+                .all(|position| pond.shore_distance(position.xz()) > 0.0)
+        );
+    }
+
+    #[test]
+    fn test_trees_stand_on_the_ground() {
+        let forest = Forest::generate(ForestSeed(11));
+        for tree in &forest.trees {
+            let ground = forest.terrain.height(tree.position.x, tree.position.z);
+            assert_eq!(tree.position.y, ground);
+        }
+        // The middle of the clearing is flat, the forest beyond is not
+        assert_eq!(forest.terrain.height(2.0, -3.0), GROUND_LEVEL);
+        assert!(
+            forest
+                .trees
+                .iter()
+                .any(|tree| (tree.position.y - GROUND_LEVEL).abs() > 0.1)
+        );
+    }
+
+    #[test]
+    fn test_trees_suit_the_ground() {
+        // Dry ridges favor conifers, wet hollows aspens
+        let ridge = (HILL_HEIGHT, 0.1);
+        let hollow = (-HILL_HEIGHT, 0.9);
+        assert!(
+            TreeKind::Conifer.suitability(ridge.0, ridge.1)
+                > TreeKind::Aspen.suitability(ridge.0, ridge.1)
+        );
+        assert!(
+            TreeKind::Aspen.suitability(hollow.0, hollow.1)
+                > TreeKind::Conifer.suitability(hollow.0, hollow.1)
+        );
+    }
+}
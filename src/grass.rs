@@ -0,0 +1,511 @@
+//! Grass, ferns and wildflowers scattered over the ground where they grow best: grass in the
+//! open, ferns in damp shade and wildflowers in sunny patches.
+//!
+//! Every plant of a kind shares one mesh and one material, so they are drawn in instanced
+//! batches, and they bend in the same gusts as the trees in a vertex shader, see
+//! `assets/shaders/grass.wgsl`. How many there are, and how quickly they thin out away from
+//! the camera, is up to the [`GrassQuality`].
+
+use std::f32::consts::TAU;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::camera::visibility::VisibilityRange;
+use bevy::mesh::PrimitiveTopology;
+use bevy::pbr::{ExtendedMaterial, MaterialExtension};
+use bevy::prelude::*;
+use bevy::render::render_resource::AsBindGroup;
+use bevy::shader::ShaderRef;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::GameState;
+use crate::forest::{FOREST_HALF_SIZE, Forest};
+use crate::scene::setup_scene;
+use crate::terrain::{GroundCover, ground_cover, value_noise};
+use crate::weather::{GUST_SPEED, GUST_WAVE, WIND_DIRECTION, Weather};
+
+pub struct GrassPlugin;
+
+impl Plugin for GrassPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GrassQuality>()
+            .add_plugins(MaterialPlugin::<GrassMaterial>::default())
+            .add_systems(OnEnter(GameState::Playing), spawn_grass.after(setup_scene))
+            .add_systems(Update, sway_grass.run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// How thick the grass grows. Insert it as a resource before the game starts to pick another.
+/// Browsers and phones get less by default.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GrassQuality {
+    /// Bare ground
+    Off,
+    #[cfg_attr(
+        any(target_arch = "wasm32", target_os = "android", target_os = "ios"),
+        default
+    )]
+    Low,
+    Medium,
+    #[cfg_attr(
+        not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")),
+        default
+    )]
+    High,
+}
+
+impl GrassQuality {
+    const ALL: [Self; 4] = [Self::Off, Self::Low, Self::Medium, Self::High];
+
+    /// The next thicker quality, after [`GrassQuality::High`] back to bare ground.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|quality| *quality == self);
+        Self::ALL[index.map_or(0, |index| (index + 1) % Self::ALL.len())]
+    }
+
+    /// Share of the plants that grow.
+    fn density(self) -> f32 {
+        match self {
+            Self::Off => 0.0,
+            Self::Low => 0.25,
+            Self::Medium => 0.5,
+            Self::High => 1.0,
+        }
+    }
+
+    /// Distance from the camera where plants start to thin out, and where the last is gone.
+    fn thinning(self) -> std::ops::Range<f32> {
+        match self {
+            Self::Off | Self::Low => 26.0..36.0,
+            Self::Medium => 30.0..42.0,
+            Self::High => 34.0..50.0,
+        }
+    }
+}
+
+impl std::str::FromStr for GrassQuality {
+    type Err = String;
+
+    /// `off`, `low`, `medium` or `high`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|quality| format!("{quality:?}").eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("expected off, low, medium or high, not {s:?}"))
+    }
+}
+
+/// Plants grow this far out past the trees, to the edge of what the camera sees.
+const GROWING_HALF_SIZE: f32 = FOREST_HALF_SIZE + 2.0;
+/// Plants keep this far from the water's edge, and this far from a trunk, times its size.
+const SHORE_GAP: f32 = 0.1;
+const TRUNK_GAP: f32 = 0.35;
+/// Size of the patches wildflowers grow in.
+const FLOWER_PATCH_SCALE: f32 = 2.5;
+/// Plants fade out over this distance, instead of popping.
+const FADE: f32 = 2.0;
+
+/// How far grass tips bend in a full gale, per unit of height squared.
+const GRASS_SWAY: f32 = 1.2;
+
+/// The grass shader, see `assets/shaders/grass.wgsl`.
+const GRASS_SHADER: &str = "shaders/grass.wgsl";
+
+/// Mixes the plants come in, so neighbors don't look alike.
+const VARIANTS: usize = 3;
+
+const GRASS_BASE: Color = Color::srgb(0.16, 0.3, 0.1);
+const GRASS_TIP: Color = Color::srgb(0.45, 0.62, 0.28);
+const FERN: Color = Color::srgb(0.2, 0.42, 0.16);
+const STEM: Color = Color::srgb(0.25, 0.45, 0.18);
+const PETALS: [Color; VARIANTS] = [
+    Color::srgb(0.95, 0.93, 0.85),
+    Color::srgb(0.95, 0.8, 0.2),
+    Color::srgb(0.6, 0.4, 0.85),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Plant {
+    Grass,
+    Fern,
+    Wildflower,
+}
+
+impl Plant {
+    const ALL: [Self; 3] = [Self::Grass, Self::Fern, Self::Wildflower];
+
+    /// Most plants per square unit, on the best ground.
+    fn max_density(self) -> f32 {
+        match self {
+            Self::Grass => 6.0,
+            Self::Fern => 3.0,
+            Self::Wildflower => 2.0,
+        }
+    }
+
+    /// Share of [`Plant::max_density`] that grows on this ground. `flowers` marks the sunny
+    /// patches wildflowers grow in, from 0 to 1.
+    fn density(self, cover: &GroundCover, moisture: f32, flowers: f32) -> f32 {
+        match self {
+            Self::Grass => cover.grass,
+            Self::Fern => cover.litter * moisture,
+            Self::Wildflower => cover.grass * flowers,
+        }
+    }
+
+    fn scale(self) -> std::ops::Range<f32> {
+        match self {
+            Self::Grass => 0.7..1.3,
+            Self::Fern => 0.8..1.4,
+            Self::Wildflower => 0.8..1.2,
+        }
+    }
+}
+
+/// Where the wildflowers bloom, for the insects that visit them. Empty when the
+/// [`GrassQuality`] leaves the ground bare.
+#[derive(Resource, Clone, Debug, Default, Deref)]
+pub struct Wildflowers(Vec<Vec3>);
+
+/// Height of a wildflower's head of petals above the ground, roughly.
+pub const BLOSSOM_HEIGHT: f32 = 0.35;
+
+/// One plant, where it grows and how far off it can still be seen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Planting {
+    plant: Plant,
+    variant: usize,
+    position: Vec3,
+    yaw: f32,
+    scale: f32,
+    /// Distance from the camera it thins out at
+    visible_to: f32,
+}
+
+/// Scatters plants over the forest floor by each one's density map.
+fn scatter(forest: &Forest, quality: GrassQuality, rng: &mut impl Rng) -> Vec<Planting> {
+    let terrain = &forest.terrain;
+    let area = (2.0 * GROWING_HALF_SIZE).powi(2);
+    let thinning = quality.thinning();
+    let flower_seed = forest.seed as u32 ^ 0xf10e;
+    let mut plantings = Vec::new();
+    for plant in Plant::ALL {
+        let candidates = (area * plant.max_density() * quality.density()) as usize;
+        for _ in 0..candidates {
+            let point = Vec2::new(
+                rng.random_range(-GROWING_HALF_SIZE..GROWING_HALF_SIZE),
+                rng.random_range(-GROWING_HALF_SIZE..GROWING_HALF_SIZE),
+            );
+            let chance = rng.random::<f32>();
+            let open = terrain.pond.shore_distance(point) > SHORE_GAP
+                && forest
+                    .trees
+                    .iter()
+                    .all(|tree| tree.position.xz().distance(point) > TRUNK_GAP * tree.scale);
+            if !open {
+                continue;
+            }
+            let flowers = value_noise(flower_seed, point / FLOWER_PATCH_SCALE).max(0.0);
+            let cover = ground_cover(forest, point.x, point.y);
+            let moisture = terrain.moisture(point.x, point.y);
+            if chance >= plant.density(&cover, moisture, flowers) {
+                continue;
+            }
+            plantings.push(Planting {
+                plant,
+                variant: rng.random_range(0..VARIANTS),
+                position: terrain.on_ground(point.x, point.y),
+                yaw: rng.random_range(0.0..TAU),
+                scale: rng.random_range(plant.scale()),
+                visible_to: thinning.end.lerp(thinning.start, rng.random()),
+            });
+        }
+    }
+    plantings
+}
+
+pub type GrassMaterial = ExtendedMaterial<StandardMaterial, Swaying>;
+
+/// Bends plants downwind in a vertex shader, in the same gusts as the trees.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+pub struct Swaying {
+    /// Downwind direction in x and y, and the wind's strength from the weather in z
+    #[uniform(100)]
+    wind: Vec4,
+    /// Gust speed and wavelength in x and y, and how far tips bend in a full gale in z
+    #[uniform(100)]
+    gusts: Vec4,
+}
+
+impl MaterialExtension for Swaying {
+    fn vertex_shader() -> ShaderRef {
+        GRASS_SHADER.into()
+    }
+
+    // Too small to cast shadows worth the cost, and the prepass wouldn't bend with the wind
+    fn enable_prepass() -> bool {
+        false
+    }
+
+    fn enable_shadows() -> bool {
+        false
+    }
+}
+
+pub(crate) fn spawn_grass(
+    mut commands: Commands,
+    quality: Res<GrassQuality>,
+    forest: Res<Forest>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<GrassMaterial>>,
+) {
+    let mut rng = ChaCha8Rng::seed_from_u64(forest.seed ^ 0x6a55);
+    let plantings = scatter(&forest, *quality, &mut rng);
+    info!(
+        "Growing {} plants at {:?} quality",
+        plantings.len(),
+        *quality
+    );
+
+    let mut shapes = |plant: Plant| -> [Handle<Mesh>; VARIANTS] {
+        std::array::from_fn(|variant| {
+            let mesh = match plant {
+                Plant::Grass => grass_tuft(&mut rng),
+                Plant::Fern => fern(&mut rng),
+                Plant::Wildflower => wildflower(PETALS[variant], &mut rng),
+            };
+            meshes.add(mesh)
+        })
+    };
+    let grass = shapes(Plant::Grass);
+    let ferns = shapes(Plant::Fern);
+    let wildflowers = shapes(Plant::Wildflower);
+    commands.insert_resource(Wildflowers(
+        plantings
+            .iter()
+            .filter(|planting| planting.plant == Plant::Wildflower)
+            .map(|planting| planting.position + Vec3::Y * BLOSSOM_HEIGHT * planting.scale)
+            .collect(),
+    ));
+    let material = materials.add(GrassMaterial {
+        base: StandardMaterial {
+            perceptual_roughness: 0.8,
+            double_sided: true,
+            cull_mode: None,
+            ..default()
+        },
+        extension: Swaying {
+            wind: Vec4::new(WIND_DIRECTION.x, WIND_DIRECTION.y, 0.0, 0.0),
+            gusts: Vec4::new(GUST_SPEED, GUST_WAVE, GRASS_SWAY, 0.0),
+        },
+    });
+
+    for planting in plantings {
+        let mesh = match planting.plant {
+            Plant::Grass => &grass,
+            Plant::Fern => &ferns,
+            Plant::Wildflower => &wildflowers,
+        }[planting.variant]
+            .clone();
+        commands.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(planting.position)
+                .with_rotation(Quat::from_rotation_y(planting.yaw))
+                .with_scale(Vec3::splat(planting.scale)),
+            VisibilityRange {
+                start_margin: 0.0..0.0,
+                end_margin: planting.visible_to..planting.visible_to + FADE,
+                use_aabb: false,
+            },
+        ));
+    }
+}
+
+/// Passes the wind on to the plants, whenever it changes.
+fn sway_grass(weather: Res<Weather>, mut materials: ResMut<Assets<GrassMaterial>>) {
+    let wind = weather.conditions().wind;
+    // Touching a material sends it to the GPU again
+    if materials
+        .iter()
+        .all(|(_, material)| material.extension.wind.z == wind)
+    {
+        return;
+    }
+    for (_, material) in materials.iter_mut() {
+        material.extension.wind.z = wind;
+    }
+}
+
+/// Flat, colored triangles for a plant, with its base at the origin.
+#[derive(Default)]
+struct Leaves {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    colors: Vec<[f32; 4]>,
+}
+
+impl Leaves {
+    fn triangle(&mut self, corners: [Vec3; 3], colors: [Color; 3]) {
+        let normal = (corners[1] - corners[0])
+            .cross(corners[2] - corners[0])
+            .normalize_or(Vec3::Y);
+        for (corner, color) in corners.into_iter().zip(colors) {
+            self.positions.push(corner.to_array());
+            self.normals.push(normal.to_array());
+            self.colors.push(color.to_linear().to_f32_array());
+        }
+    }
+
+    /// A blade tapering from a base `width` wide to a point.
+    fn blade(&mut self, base: Vec3, tip: Vec3, width: f32, colors: [Color; 2]) {
+        let side = (tip - base).cross(Vec3::Y).normalize_or(Vec3::X) * width / 2.0;
+        self.triangle(
+            [base - side, base + side, tip],
+            [colors[0], colors[0], colors[1]],
+        );
+    }
+
+    /// A leaf `width` wide at the middle, lying across `side`.
+    fn leaf(&mut self, base: Vec3, tip: Vec3, side: Vec3, width: f32, color: Color) {
+        let side = side.normalize_or(Vec3::X) * width / 2.0;
+        let middle = (base + tip) / 2.0;
+        self.triangle([base, middle + side, tip], [color; 3]);
+        self.triangle([base, tip, middle - side], [color; 3]);
+    }
+
+    fn build(self) -> Mesh {
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, self.positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, self.colors)
+    }
+}
+
+/// A handful of blades leaning out from a clump.
+fn grass_tuft(rng: &mut impl Rng) -> Mesh {
+    let mut leaves = Leaves::default();
+    let blades = 7;
+    for i in 0..blades {
+        let out = Vec2::from_angle((i as f32 + rng.random::<f32>()) / blades as f32 * TAU);
+        let base = Vec3::new(out.x, 0.0, out.y) * 0.03;
+        let lean = Vec3::new(out.x, 0.0, out.y) * rng.random_range(0.05..0.15);
+        let tip = base + lean + Vec3::Y * rng.random_range(0.22..0.42);
+        leaves.blade(base, tip, 0.05, [GRASS_BASE, GRASS_TIP]);
+    }
+    leaves.build()
+}
+
+/// Fronds arching out and drooping at the tips.
+fn fern(rng: &mut impl Rng) -> Mesh {
+    let mut leaves = Leaves::default();
+    let fronds = 7;
+    for i in 0..fronds {
+        let out = Vec2::from_angle((i as f32 + rng.random::<f32>() * 0.5) / fronds as f32 * TAU);
+        let out = Vec3::new(out.x, 0.0, out.y);
+        let length = rng.random_range(0.4..0.55);
+        let base = Vec3::Y * 0.02;
+        let arch = out * length * 0.5 + Vec3::Y * length * 0.6;
+        let tip = out * length + Vec3::Y * length * 0.35;
+        let side = out.cross(Vec3::Y);
+        leaves.leaf(base, arch, side, 0.16, FERN);
+        leaves.leaf(arch, tip, side, 0.12, FERN);
+    }
+    leaves.build()
+}
+
+/// A stem or two, each with a flat head of petals.
+fn wildflower(petals: Color, rng: &mut impl Rng) -> Mesh {
+    let mut leaves = Leaves::default();
+    for _ in 0..rng.random_range(1..=2) {
+        let out = Vec2::from_angle(rng.random_range(0.0..TAU)) * rng.random_range(0.0..0.05);
+        let top = Vec3::new(out.x, rng.random_range(0.25..0.4), out.y);
+        leaves.blade(Vec3::ZERO, top, 0.025, [STEM, STEM]);
+        for petal in 0..5 {
+            let out = Vec2::from_angle(petal as f32 / 5.0 * TAU);
+            let out = Vec3::new(out.x, 0.1, out.y);
+            leaves.leaf(top, top + out * 0.06, out.cross(Vec3::Y), 0.04, petals);
+        }
+    }
+    leaves.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forest::ForestSeed;
+
+    #[test]
+    fn test_plants_suit_the_ground() {
+        let cover = |grass, litter| GroundCover {
+            grass,
+            dirt: 0.0,
+            litter,
+            rock: 1.0 - grass - litter,
+        };
+        let open = cover(1.0, 0.0);
+        let damp_shade = cover(0.1, 0.9);
+        let rocky = cover(0.0, 0.0);
+        assert!(
+            Plant::Grass.density(&open, 0.5, 0.0) > Plant::Grass.density(&damp_shade, 0.8, 0.0)
+        );
+        assert!(Plant::Fern.density(&damp_shade, 0.8, 0.0) > Plant::Fern.density(&open, 0.5, 0.0));
+        assert_eq!(
+            Plant::Wildflower.density(&open, 0.5, 0.0),
+            0.0,
+            "Only in patches"
+        );
+        assert!(Plant::Wildflower.density(&open, 0.5, 0.8) > 0.5);
+        for plant in Plant::ALL {
+            assert_eq!(
+                plant.density(&rocky, 0.2, 1.0),
+                0.0,
+                "{plant:?} on bare rock"
+            );
+        }
+    }
+
+    #[test]
+    fn test_quality_sets_how_much_grows() {
+        let forest = Forest::generate(ForestSeed::default());
+        let count = |quality| scatter(&forest, quality, &mut ChaCha8Rng::seed_from_u64(1)).len();
+        assert_eq!(count(GrassQuality::Off), 0);
+        let (low, high) = (count(GrassQuality::Low), count(GrassQuality::High));
+        assert!(low > 200, "{low}");
+        assert!(high > low * 3, "{low} {high}");
+    }
+
+    #[test]
+    fn test_menu_steps_through_qualities() {
+        assert_eq!(GrassQuality::Off.next(), GrassQuality::Low);
+        assert_eq!(GrassQuality::High.next(), GrassQuality::Off);
+        assert_eq!("medium".parse(), Ok(GrassQuality::Medium));
+        assert!("lush".parse::<GrassQuality>().is_err());
+    }
+
+    #[test]
+    fn test_plants_grow_on_dry_land() {
+        let forest = Forest::generate(ForestSeed(5));
+        let plantings = scatter(
+            &forest,
+            GrassQuality::Medium,
+            &mut ChaCha8Rng::seed_from_u64(2),
+        );
+        for plant in Plant::ALL {
+            assert!(
+                plantings.iter().any(|planting| planting.plant == plant),
+                "{plant:?}"
+            );
+        }
+        let thinning = GrassQuality::Medium.thinning();
+        for planting in &plantings {
+            let (x, z) = (planting.position.x, planting.position.z);
+            assert_eq!(planting.position.y, forest.terrain.height(x, z));
+            assert!(forest.terrain.pond.shore_distance(planting.position.xz()) > 0.0);
+            assert!(thinning.contains(&planting.visible_to) || planting.visible_to == thinning.end);
+        }
+    }
+}
@@ -0,0 +1,699 @@
+//! Insects: butterflies and bees going from flower to flower by day, fireflies blinking over
+//! the grass at dusk, and crickets and cicadas singing into the night. Each kind has its
+//! season and its hours, see [`InsectKind::activity`].
+//!
+//! Every kind has a fixed number of insects, hidden until the time is right, so nothing is
+//! spawned while the game runs. Butterflies and bees are food for birds that catch insects on
+//! the wing; a caught insect is hidden for a while, then another takes its place.
+
+use std::f32::consts::TAU;
+use std::ops::Range;
+use std::time::Duration;
+
+use bevy::light::NotShadowCaster;
+use bevy::prelude::*;
+use bevy_kira_audio::SpatialRadius;
+use bevy_kira_audio::prelude::*;
+use rand::Rng;
+use rand::seq::IndexedRandom;
+
+use crate::GameState;
+use crate::forest::Forest;
+use crate::grass::{Wildflowers, spawn_grass};
+use crate::loading::AudioAssets;
+use crate::scene::{DayClock, SeasonClock};
+use crate::terrain::{Terrain, ground_cover, smoothstep};
+use crate::weather::{Weather, WeatherConditions};
+
+pub struct InsectPlugin;
+
+impl Plugin for InsectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(catch_insect)
+            .add_systems(
+                OnEnter(GameState::Playing),
+                spawn_insects.after(spawn_grass),
+            )
+            .add_systems(
+                Update,
+                (
+                    rouse_insects,
+                    fly_insects,
+                    (flap_wings, blink_fireflies, sing_insects),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// Insects stay within this distance of the middle of the clearing.
+const RANGE: f32 = 15.0;
+/// Butterflies and bees pick their next flower within this distance, when there is one.
+const FLOWER_HOP: f32 = 4.0;
+/// How close an insect gets to where it's heading before it counts as there.
+const ARRIVAL_DISTANCE: f32 = 0.05;
+/// Seconds until a caught insect's place is taken by another.
+const REPLACEMENT_TIME: Range<f32> = 20.0..60.0;
+
+/// Fireflies drift this high over the grass.
+const FIREFLY_HEIGHT: Range<f32> = 0.3..1.8;
+/// How far a firefly drifts before turning.
+const FIREFLY_DRIFT: f32 = 2.0;
+/// Seconds between a firefly's flashes, and how long each one lasts.
+const FLASH_INTERVAL: Range<f32> = 2.5..6.0;
+const FLASH_TIME: f32 = 0.5;
+/// Brightness of a firefly's glow at the height of a flash.
+const GLOW: LinearRgba = LinearRgba::rgb(6.0, 8.0, 1.5);
+/// Steps from dark to the height of a flash, see [`FireflyGlows`].
+const GLOW_LEVELS: usize = 12;
+
+/// Crickets call from ground with at least this much grass cover.
+const CRICKET_GRASS: f32 = 0.5;
+/// Spots tried for crickets; fewer call where the clearing is mostly bare.
+const CRICKET_TRIES: usize = 200;
+/// How far away a cricket or cicada can be heard.
+const CHORUS_RADIUS: f32 = 25.0;
+/// Length of the cricket and cicada loops, in seconds.
+const CHORUS_LOOP: f64 = 4.0;
+/// Seconds a singer takes to join in or fall silent.
+const CHORUS_FADE: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum InsectKind {
+    Butterfly,
+    Bee,
+    Firefly,
+    Cricket,
+    Cicada,
+}
+
+impl InsectKind {
+    const FLIERS: [Self; 3] = [Self::Butterfly, Self::Bee, Self::Firefly];
+
+    /// How many there are at the height of their season.
+    fn count(self) -> usize {
+        match self {
+            Self::Butterfly => 8,
+            Self::Bee => 12,
+            Self::Firefly => 40,
+            Self::Cricket => 8,
+            Self::Cicada => 4,
+        }
+    }
+
+    /// When they're around: (day of the year, share out) keyframes.
+    fn season(self) -> &'static [(f32, f32)] {
+        match self {
+            Self::Butterfly => &[(95.0, 0.0), (130.0, 1.0), (260.0, 1.0), (290.0, 0.0)],
+            Self::Bee => &[(80.0, 0.0), (110.0, 1.0), (270.0, 1.0), (300.0, 0.0)],
+            Self::Firefly => &[(140.0, 0.0), (165.0, 1.0), (215.0, 1.0), (235.0, 0.0)],
+            Self::Cricket => &[(110.0, 0.0), (160.0, 1.0), (280.0, 1.0), (310.0, 0.0)],
+            Self::Cicada => &[(150.0, 0.0), (175.0, 1.0), (230.0, 1.0), (250.0, 0.0)],
+        }
+    }
+
+    /// Share of them out and about, from 0 to 1. Butterflies and bees come out in sunshine,
+    /// fireflies in the first hours after sunset, cicadas from late afternoon into the evening
+    /// and crickets all night. Rain sends them all to cover, and wind grounds the flimsier
+    /// fliers.
+    ///
+    /// `progress` is the [`DayClock::progress`].
+    fn activity(
+        self,
+        progress: f32,
+        sun_elevation: f32,
+        day_of_year: f32,
+        conditions: &WeatherConditions,
+    ) -> f32 {
+        let calm = 1.0 - smoothstep(0.3, 0.6, conditions.wind);
+        let hours = match self {
+            Self::Butterfly => smoothstep(0.1, 0.35, sun_elevation) * calm,
+            Self::Bee => smoothstep(0.05, 0.25, sun_elevation),
+            Self::Firefly => {
+                smoothstep(0.49, 0.53, progress) * (1.0 - smoothstep(0.6, 0.68, progress)) * calm
+            }
+            Self::Cricket => 1.0 - smoothstep(-0.15, 0.0, sun_elevation),
+            Self::Cicada => {
+                smoothstep(0.4, 0.46, progress) * (1.0 - smoothstep(0.6, 0.7, progress))
+            }
+        };
+        let dry = 1.0 - smoothstep(0.1, 0.4, conditions.rain);
+        seasonal(self.season(), day_of_year) * hours * dry
+    }
+
+    /// How many are out at this activity. The first ones in the pool come out first.
+    fn out(self, activity: f32) -> usize {
+        (activity * self.count() as f32).round() as usize
+    }
+
+    /// Birds leave fireflies alone: they taste foul.
+    fn is_prey(self) -> bool {
+        matches!(self, Self::Butterfly | Self::Bee)
+    }
+
+    /// Flying speed in units/second.
+    fn speed(self) -> f32 {
+        match self {
+            Self::Butterfly => 0.8,
+            Self::Bee => 1.5,
+            _ => 0.3,
+        }
+    }
+
+    /// How far they stray from a straight line, and how quickly they swing from side to side.
+    fn flutter(self) -> (f32, f32) {
+        match self {
+            Self::Butterfly => (0.6, 3.0),
+            Self::Bee => (0.2, 9.0),
+            _ => (0.2, 0.8),
+        }
+    }
+
+    /// Seconds spent at each flower, for the kinds that visit them.
+    fn visit(self) -> Option<Range<f32>> {
+        match self {
+            Self::Butterfly => Some(2.0..6.0),
+            Self::Bee => Some(1.0..3.0),
+            _ => None,
+        }
+    }
+}
+
+/// A share between 0 and 1 for the day of the year, from (day, share) keyframes.
+fn seasonal(keyframes: &[(f32, f32)], day: f32) -> f32 {
+    let next = keyframes.partition_point(|(d, _)| *d <= day);
+    match (keyframes.get(next.wrapping_sub(1)), keyframes.get(next)) {
+        (Some(&(d0, s0)), Some(&(d1, s1))) => s0.lerp(s1, (day - d0) / (d1 - d0)),
+        _ => 0.0,
+    }
+}
+
+/// A butterfly, bee or firefly.
+#[derive(Component, Debug)]
+pub struct Insect {
+    kind: InsectKind,
+    /// Place in its kind's pool, see [`InsectKind::out`]
+    index: usize,
+    out: bool,
+    /// A flower, or for fireflies a spot over the grass
+    target: Vec3,
+    /// Seconds left at the current flower
+    visit: f32,
+    /// Seconds until a caught insect's place is taken by another
+    away: f32,
+    /// From 0 to 1, keeps its flutter and flashes out of step with the others
+    phase: f32,
+}
+
+impl Insect {
+    /// Out and about, and something a bird would eat.
+    pub fn is_prey(&self) -> bool {
+        self.out && self.kind.is_prey()
+    }
+}
+
+/// Trigger when a bird catches an insect.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct InsectCaught(pub Entity);
+
+/// One of a butterfly's wings, flapping about its body.
+#[derive(Component)]
+struct Wing {
+    /// 1 for the right wing, -1 for the left
+    side: f32,
+}
+
+/// One material for each step of a firefly's glow, dark first. Fireflies switch between
+/// them as they flash, which leaves the materials alone; changing one sends it to the GPU
+/// again.
+#[derive(Resource)]
+struct FireflyGlows(Vec<Handle<StandardMaterial>>);
+
+impl FireflyGlows {
+    fn at(&self, glow: f32) -> &Handle<StandardMaterial> {
+        let level = (glow * (GLOW_LEVELS - 1) as f32).round() as usize;
+        &self.0[level.min(GLOW_LEVELS - 1)]
+    }
+}
+
+/// A cricket or cicada, heard but never seen. See [`sing_insects`].
+#[derive(Component)]
+struct Chorister {
+    kind: InsectKind,
+    index: usize,
+    /// Each one calls a little faster or slower than the rest
+    rate: f64,
+}
+
+fn spawn_insects(
+    mut commands: Commands,
+    forest: Res<Forest>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut rng = rand::rng();
+    let flat = |color: Color| StandardMaterial {
+        base_color: color,
+        perceptual_roughness: 0.9,
+        double_sided: true,
+        cull_mode: None,
+        ..default()
+    };
+    // Each wing lies flat, out to the side of the body
+    let wing = meshes.add(
+        Rectangle::new(0.05, 0.045)
+            .mesh()
+            .build()
+            .rotated_by(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2))
+            .translated_by(Vec3::X * 0.025),
+    );
+    let wing_colors = [
+        materials.add(flat(Color::srgb(0.95, 0.55, 0.1))),
+        materials.add(flat(Color::srgb(0.95, 0.9, 0.4))),
+        materials.add(flat(Color::srgb(0.92, 0.92, 0.88))),
+    ];
+    let bee = meshes.add(
+        Sphere::new(0.018)
+            .mesh()
+            .ico(1)
+            .expect("low subdivision counts are always valid"),
+    );
+    let bee_color = materials.add(flat(Color::srgb(0.75, 0.55, 0.1)));
+    let firefly = meshes.add(
+        Sphere::new(0.012)
+            .mesh()
+            .ico(0)
+            .expect("low subdivision counts are always valid"),
+    );
+    let glows = FireflyGlows(
+        (0..GLOW_LEVELS)
+            .map(|level| {
+                materials.add(StandardMaterial {
+                    base_color: Color::srgb(0.15, 0.15, 0.05),
+                    emissive: GLOW * (level as f32 / (GLOW_LEVELS - 1) as f32),
+                    ..default()
+                })
+            })
+            .collect(),
+    );
+
+    for kind in InsectKind::FLIERS {
+        for index in 0..kind.count() {
+            let mut insect = commands.spawn((
+                Name::new(format!("{kind:?}")),
+                Insect {
+                    kind,
+                    index,
+                    out: false,
+                    target: Vec3::ZERO,
+                    visit: 0.0,
+                    away: 0.0,
+                    phase: rng.random(),
+                },
+                Transform::default(),
+                Visibility::Hidden,
+                NotShadowCaster,
+            ));
+            match kind {
+                InsectKind::Butterfly => {
+                    let color = wing_colors.choose(&mut rng).expect("there are wing colors");
+                    insect.with_children(|body| {
+                        for side in [1.0, -1.0] {
+                            body.spawn((
+                                Wing { side },
+                                Mesh3d(wing.clone()),
+                                MeshMaterial3d(color.clone()),
+                                Transform::from_scale(Vec3::new(side, 1.0, 1.0)),
+                                NotShadowCaster,
+                            ));
+                        }
+                    });
+                }
+                InsectKind::Bee => {
+                    insect.insert((Mesh3d(bee.clone()), MeshMaterial3d(bee_color.clone())));
+                }
+                _ => {
+                    insect.insert((
+                        Mesh3d(firefly.clone()),
+                        MeshMaterial3d(glows.at(0.0).clone()),
+                    ));
+                }
+            }
+        }
+    }
+    commands.insert_resource(glows);
+
+    // Crickets call from the grass, cicadas from up in the trees
+    let terrain = &forest.terrain;
+    let crickets: Vec<Vec3> = (0..CRICKET_TRIES)
+        .filter_map(|_| {
+            let x = rng.random_range(-RANGE..RANGE);
+            let z = rng.random_range(-RANGE..RANGE);
+            let open = terrain.pond.shore_distance(Vec2::new(x, z)) > 0.0;
+            (open && ground_cover(&forest, x, z).grass > CRICKET_GRASS)
+                .then(|| terrain.on_ground(x, z))
+        })
+        .take(InsectKind::Cricket.count())
+        .collect();
+    let nearby_trees: Vec<Vec3> = forest
+        .trees
+        .iter()
+        .filter(|tree| tree.position.xz().length() < RANGE)
+        .map(|tree| tree.position + Vec3::Y * rng.random_range(2.5..4.0) * tree.scale)
+        .collect();
+    let cicadas: Vec<Vec3> = nearby_trees
+        .choose_multiple(&mut rng, InsectKind::Cicada.count())
+        .copied()
+        .collect();
+    for (kind, spots) in [
+        (InsectKind::Cricket, crickets),
+        (InsectKind::Cicada, cicadas),
+    ] {
+        for (index, spot) in spots.into_iter().enumerate() {
+            commands.spawn((
+                Name::new(format!("{kind:?}")),
+                Chorister {
+                    kind,
+                    index,
+                    rate: rng.random_range(0.9..1.1),
+                },
+                Transform::from_translation(spot),
+                SpatialAudioEmitter { instances: vec![] },
+                SpatialRadius {
+                    radius: CHORUS_RADIUS,
+                },
+            ));
+        }
+    }
+}
+
+/// Where an insect heads next: another flower nearby, or for fireflies anywhere close over
+/// the grass.
+fn next_stop(
+    kind: InsectKind,
+    rng: &mut impl Rng,
+    from: Vec3,
+    flowers: &[Vec3],
+    terrain: &Terrain,
+) -> Option<Vec3> {
+    match kind {
+        InsectKind::Butterfly | InsectKind::Bee => {
+            let nearby: Vec<Vec3> = flowers
+                .iter()
+                .filter(|flower| flower.distance(from) < FLOWER_HOP && **flower != from)
+                .copied()
+                .collect();
+            nearby.choose(rng).or_else(|| flowers.choose(rng)).copied()
+        }
+        _ => {
+            let drift = Vec2::from_angle(rng.random_range(0.0..TAU)) * FIREFLY_DRIFT;
+            let spot = (from.xz() + drift).clamp_length_max(RANGE);
+            Some(terrain.on_ground(spot.x, spot.y) + Vec3::Y * rng.random_range(FIREFLY_HEIGHT))
+        }
+    }
+}
+
+/// Brings insects out as their hours come and sends them back as they pass. Butterflies and
+/// bees need flowers to visit.
+fn rouse_insects(
+    time: Res<Time>,
+    day_clock: Res<DayClock>,
+    season: Res<SeasonClock>,
+    weather: Res<Weather>,
+    terrain: Res<Terrain>,
+    flowers: Option<Res<Wildflowers>>,
+    mut insects: Query<(&mut Insect, &mut Transform, &mut Visibility)>,
+) {
+    let mut rng = rand::rng();
+    let flowers = flowers
+        .as_deref()
+        .map_or(&[][..], |flowers| flowers.as_slice());
+    let conditions = weather.conditions();
+    let out = InsectKind::FLIERS.map(|kind| {
+        let activity = kind.activity(
+            day_clock.progress(),
+            day_clock.sun_elevation(),
+            season.day,
+            &conditions,
+        );
+        (kind, kind.out(activity))
+    });
+
+    for (mut insect, mut transform, mut visibility) in insects.iter_mut() {
+        insect.away = (insect.away - time.delta_secs()).max(0.0);
+        let shown = out
+            .iter()
+            .find(|(kind, _)| *kind == insect.kind)
+            .is_some_and(|(_, shown)| insect.index < *shown);
+        if shown && !insect.out && insect.away == 0.0 {
+            // Somewhere in the clearing to come out from
+            let from = Vec2::from_angle(rng.random_range(0.0..TAU)) * rng.random_range(0.0..RANGE);
+            let Some(start) = next_stop(
+                insect.kind,
+                &mut rng,
+                terrain.on_ground(from.x, from.y),
+                flowers,
+                &terrain,
+            ) else {
+                continue;
+            };
+            transform.translation = start;
+            insect.target = start;
+            insect.visit = 0.0;
+            insect.out = true;
+            *visibility = Visibility::Inherited;
+        } else if !shown && insect.out {
+            insect.out = false;
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+/// Flies insects from one stop to the next, fluttering along the way, and lets butterflies and
+/// bees feed at each flower for a while.
+fn fly_insects(
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    flowers: Option<Res<Wildflowers>>,
+    mut insects: Query<(&mut Insect, &mut Transform)>,
+) {
+    let mut rng = rand::rng();
+    let flowers = flowers
+        .as_deref()
+        .map_or(&[][..], |flowers| flowers.as_slice());
+    let dt = time.delta_secs();
+    let t = time.elapsed_secs();
+
+    for (mut insect, mut transform) in insects.iter_mut() {
+        if !insect.out {
+            continue;
+        }
+        if insect.visit > 0.0 {
+            insect.visit -= dt;
+            if insect.visit <= 0.0
+                && let Some(next) =
+                    next_stop(insect.kind, &mut rng, insect.target, flowers, &terrain)
+            {
+                insect.target = next;
+            }
+            continue;
+        }
+
+        let to_target = insect.target - transform.translation;
+        let distance = to_target.length();
+        if distance < ARRIVAL_DISTANCE {
+            match insect.kind.visit() {
+                Some(visit) => insect.visit = rng.random_range(visit),
+                None => {
+                    insect.target =
+                        next_stop(insect.kind, &mut rng, insect.target, flowers, &terrain)
+                            .unwrap_or(insect.target)
+                }
+            }
+            continue;
+        }
+        let heading = to_target / distance;
+        // Sway across the line of flight, less as the insect closes in
+        let (sway, frequency) = insect.kind.flutter();
+        let across = heading.cross(Vec3::Y).normalize_or(Vec3::X);
+        let swing = (t * frequency + insect.phase * TAU).sin() * sway * distance.min(1.0);
+        let velocity = (heading + across * swing + Vec3::Y * swing * 0.5) * insect.kind.speed();
+        transform.translation += velocity.clamp_length_max(distance / dt.max(f32::EPSILON)) * dt;
+        let ground = terrain.height(transform.translation.x, transform.translation.z);
+        transform.translation.y = transform.translation.y.max(ground + 0.05);
+        let facing = Vec3::new(velocity.x, 0.0, velocity.z);
+        if facing.length_squared() > 0.0 {
+            transform.look_to(facing, Vec3::Y);
+        }
+    }
+}
+
+/// Butterflies flap quickly in flight and slowly open and close their wings on a flower.
+fn flap_wings(
+    time: Res<Time>,
+    insects: Query<&Insect>,
+    mut wings: Query<(&Wing, &ChildOf, &mut Transform)>,
+) {
+    let t = time.elapsed_secs();
+    for (wing, child_of, mut transform) in wings.iter_mut() {
+        let Ok(insect) = insects.get(child_of.parent()) else {
+            continue;
+        };
+        let (speed, lift) = if insect.visit > 0.0 {
+            (2.0, 0.6)
+        } else {
+            (18.0, 1.0)
+        };
+        let angle = (t * speed + insect.phase * TAU).sin() * lift;
+        transform.rotation = Quat::from_rotation_z(wing.side * angle);
+    }
+}
+
+/// How brightly a firefly glows `t` seconds into its flash cycle, from 0 to 1: a short flash,
+/// then darkness until the next.
+fn flash(t: f32) -> f32 {
+    if t < FLASH_TIME {
+        (t / FLASH_TIME * std::f32::consts::PI).sin().powi(2)
+    } else {
+        0.0
+    }
+}
+
+/// Each firefly flashes on its own, switching to the glow material for how bright it is.
+fn blink_fireflies(
+    time: Res<Time>,
+    glows: Res<FireflyGlows>,
+    mut insects: Query<(&Insect, &mut MeshMaterial3d<StandardMaterial>)>,
+) {
+    let t = time.elapsed_secs();
+    for (insect, mut material) in insects.iter_mut() {
+        if insect.kind != InsectKind::Firefly || !insect.out {
+            continue;
+        }
+        let interval = FLASH_INTERVAL.start.lerp(FLASH_INTERVAL.end, insect.phase);
+        let glow = glows.at(flash((t + insect.phase * interval) % interval));
+        if material.0 != *glow {
+            material.0 = glow.clone();
+        }
+    }
+}
+
+/// Crickets and cicadas join in as their hours come, each from its own spot, and fall silent
+/// as they pass.
+fn sing_insects(
+    audio: Res<Audio>,
+    audio_assets: Res<AudioAssets>,
+    day_clock: Res<DayClock>,
+    season: Res<SeasonClock>,
+    weather: Res<Weather>,
+    mut choristers: Query<(&Chorister, &mut SpatialAudioEmitter)>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    let mut rng = rand::rng();
+    let conditions = weather.conditions();
+    let tween = AudioTween::linear(CHORUS_FADE);
+
+    for (chorister, mut emitter) in choristers.iter_mut() {
+        let activity = chorister.kind.activity(
+            day_clock.progress(),
+            day_clock.sun_elevation(),
+            season.day,
+            &conditions,
+        );
+        let singing = chorister.index < chorister.kind.out(activity);
+        if singing && emitter.instances.is_empty() {
+            let clip = match chorister.kind {
+                InsectKind::Cicada => &audio_assets.cicada,
+                _ => &audio_assets.cricket,
+            };
+            // Start each loop somewhere different so they don't call in unison
+            let handle = audio
+                .play(clip.clone())
+                .looped()
+                .start_from(rng.random_range(0.0..CHORUS_LOOP))
+                .with_playback_rate(chorister.rate)
+                .fade_in(tween.clone())
+                .handle();
+            emitter.instances.push(handle);
+        } else if !singing {
+            for handle in emitter.instances.drain(..) {
+                if let Some(instance) = audio_instances.get_mut(&handle) {
+                    instance.stop(tween.clone());
+                }
+            }
+        }
+    }
+}
+
+/// A caught insect disappears, and another takes its place after a while.
+fn catch_insect(caught: On<InsectCaught>, mut insects: Query<(&mut Insect, &mut Visibility)>) {
+    let Ok((mut insect, mut visibility)) = insects.get_mut(caught.0) else {
+        return;
+    };
+    insect.out = false;
+    insect.away = rand::rng().random_range(REPLACEMENT_TIME);
+    *visibility = Visibility::Hidden;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::WeatherKind;
+
+    const MIDSUMMER: f32 = 190.0;
+    const NOON: (f32, f32) = (0.25, 0.8);
+    const DUSK: (f32, f32) = (0.55, -0.2);
+    const DAWN: (f32, f32) = (0.95, -0.2);
+    const MIDNIGHT: (f32, f32) = (0.75, -0.7);
+
+    fn activity(kind: InsectKind, (progress, sun): (f32, f32), weather: WeatherKind) -> f32 {
+        kind.activity(progress, sun, MIDSUMMER, &weather.conditions())
+    }
+
+    #[test]
+    fn test_insects_keep_their_hours() {
+        let clear = WeatherKind::Clear;
+        for kind in [InsectKind::Butterfly, InsectKind::Bee] {
+            assert!(activity(kind, NOON, clear) > 0.9);
+            assert_eq!(activity(kind, MIDNIGHT, clear), 0.0);
+        }
+        assert!(activity(InsectKind::Firefly, DUSK, clear) > 0.9);
+        assert_eq!(
+            activity(InsectKind::Firefly, DAWN, clear),
+            0.0,
+            "Fireflies come out at dusk"
+        );
+        assert_eq!(activity(InsectKind::Firefly, NOON, clear), 0.0);
+        assert!(activity(InsectKind::Cricket, MIDNIGHT, clear) > 0.9);
+        assert!(activity(InsectKind::Cicada, DUSK, clear) > 0.9);
+        assert_eq!(activity(InsectKind::Cricket, NOON, clear), 0.0);
+    }
+
+    #[test]
+    fn test_rain_and_winter_quiet_them() {
+        assert_eq!(activity(InsectKind::Bee, NOON, WeatherKind::HeavyRain), 0.0);
+        assert_eq!(
+            activity(InsectKind::Cricket, MIDNIGHT, WeatherKind::Thunderstorm),
+            0.0
+        );
+        let winter =
+            InsectKind::Cricket.activity(0.75, -0.7, 20.0, &WeatherKind::Clear.conditions());
+        assert_eq!(winter, 0.0);
+        // Fireflies are only about for a few weeks in early summer
+        assert!(seasonal(InsectKind::Firefly.season(), 120.0) == 0.0);
+        assert!(seasonal(InsectKind::Firefly.season(), 190.0) == 1.0);
+        assert!(seasonal(InsectKind::Bee.season(), 95.0) > 0.0);
+    }
+
+    #[test]
+    fn test_fireflies_flash_briefly() {
+        let samples = 1000;
+        let lit = (0..samples)
+            .map(|i| flash(i as f32 / samples as f32 * FLASH_INTERVAL.start))
+            .filter(|glow| *glow > 0.1)
+            .count();
+        assert!(lit > 0 && lit * 4 < samples, "{lit}");
+        assert!((flash(FLASH_TIME / 2.0) - 1.0).abs() < 1e-4);
+    }
+}
@@ -1,16 +1,43 @@
 #![allow(clippy::type_complexity)]
 
+mod astronomy;
 mod audio;
 mod bird;
+mod forest;
+mod grass;
+mod insect;
 mod loading;
 mod menu;
+mod movement;
+mod night_sky;
 mod scene;
+mod sky;
+mod species;
+mod terrain;
+mod tree;
+mod weather;
+mod wildlife;
 
 use crate::audio::InternalAudioPlugin;
 use crate::bird::BirdPlugin;
+use crate::grass::GrassPlugin;
+use crate::insect::InsectPlugin;
 use crate::loading::LoadingPlugin;
 use crate::menu::MenuPlugin;
+use crate::movement::MovementPlugin;
+use crate::night_sky::NightSkyPlugin;
 use crate::scene::ScenePlugin;
+use crate::sky::SkyPlugin;
+use crate::species::SpeciesPlugin;
+use crate::terrain::TerrainPlugin;
+use crate::tree::TreePlugin;
+use crate::weather::WeatherPlugin;
+use crate::wildlife::WildlifePlugin;
+
+pub use crate::forest::ForestSeed;
+pub use crate::grass::GrassQuality;
+pub use crate::scene::DayMode;
+pub use crate::weather::{Weather, WeatherKind};
 
 use bevy::app::App;
 #[cfg(debug_assertions)]
@@ -30,10 +57,20 @@ pub struct GamePlugin;
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<GameState>().add_plugins((
+            SpeciesPlugin,
             LoadingPlugin,
             MenuPlugin,
             InternalAudioPlugin,
             ScenePlugin,
+            NightSkyPlugin,
+            SkyPlugin,
+            WeatherPlugin,
+            TerrainPlugin,
+            TreePlugin,
+            GrassPlugin,
+            InsectPlugin,
+            MovementPlugin,
+            WildlifePlugin,
             BirdPlugin,
         ));
 
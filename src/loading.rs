@@ -1,4 +1,5 @@
 use crate::GameState;
+use crate::species::SpeciesCatalog;
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 use bevy_kira_audio::AudioSource;
@@ -12,7 +13,9 @@ impl Plugin for LoadingPlugin {
             LoadingState::new(GameState::Loading)
                 .continue_to_state(GameState::Menu)
                 .load_collection::<AudioAssets>()
-                .load_collection::<TextureAssets>(),
+                .load_collection::<SpeciesAssets>()
+                .load_collection::<TextureAssets>()
+                .finally_init_resource::<SpeciesCatalog>(),
         );
     }
 }
@@ -22,111 +25,44 @@ impl Plugin for LoadingPlugin {
 
 #[derive(AssetCollection, Resource)]
 pub struct AudioAssets {
-    #[asset(path = "audio/city-night-evening-ambience.ogg")]
-    pub ambient: Handle<AudioSource>,
+    #[asset(path = "audio/dawn.wav")]
+    pub dawn: Handle<AudioSource>,
+    #[asset(path = "audio/day.wav")]
+    pub day: Handle<AudioSource>,
+    #[asset(path = "audio/dusk.wav")]
+    pub dusk: Handle<AudioSource>,
+    #[asset(path = "audio/night.wav")]
+    pub night: Handle<AudioSource>,
+    #[asset(path = "audio/creek.wav")]
+    pub creek: Handle<AudioSource>,
+    #[asset(
+        path = "audio/city-night-evening-ambience-crickets-cicadas-and-distant-traffic-los-angeles-california-2.mp3"
+    )]
+    pub traffic: Handle<AudioSource>,
     #[allow(dead_code)]
     #[asset(
         path = "audio/Voices of Western Backyard Birds updated 2/01 Western Backyard Birds.ogg"
     )]
     pub western_backyard_birds: Handle<AudioSource>,
-    #[asset(path = "audio/Voices of Western Backyard Birds updated 2/02 Mourning Dove Song.ogg")]
-    pub mourning_dove_song: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/03 Downy Woodpecker Calls.ogg"
-    )]
-    pub downy_woodpecker_calls: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/04 Downy Woodpecker Drum.ogg"
-    )]
-    pub downy_woodpecker_drum: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/05 Northern Flicker Call.ogg"
-    )]
-    pub northern_flicker_call: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/06 Northern Flicker Call 2.ogg"
-    )]
-    pub northern_flicker_call_2: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/07 Northern Flicker Drum.ogg"
-    )]
-    pub northern_flicker_drum: Handle<AudioSource>,
-    #[asset(path = "audio/Voices of Western Backyard Birds updated 2/08 Steller's Jay Call.ogg")]
-    pub stellers_jay_call: Handle<AudioSource>,
-    #[asset(path = "audio/Voices of Western Backyard Birds updated 2/09 Steller's Jay Calls.ogg")]
-    pub stellers_jay_calls: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/10 California Scrub-Jay Calls.ogg"
-    )]
-    pub california_scrub_jay_calls: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/11 Black-capped Chickadee Song.ogg"
-    )]
-    pub black_capped_chickadee_song: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/12 Black-capped Chickadee Call.ogg"
-    )]
-    pub black_capped_chickadee_call: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/13 White-breasted Nuthatch Song.ogg"
-    )]
-    pub white_breasted_nuthatch_song: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/14 White-breasted Nuthatch Call 1.ogg"
-    )]
-    pub white_breasted_nuthatch_call_1: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/15 White-breasted Nuthatch Call 2.ogg"
-    )]
-    pub white_breasted_nuthatch_call_2: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/16 White-crowned Sparrow Song 1.ogg"
-    )]
-    pub white_crowned_sparrow_song_1: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/17 White-crowned Sparrow Song 2.ogg"
-    )]
-    pub white_crowned_sparrow_song_2: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/18 White-crowned Sparrow Call.ogg"
-    )]
-    pub white_crowned_sparrow_call: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/19 Red-winged Blackbird Song.ogg"
-    )]
-    pub red_winged_blackbird_song: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/20 Red-winged Blackbird Calls.ogg"
-    )]
-    pub red_winged_blackbird_calls: Handle<AudioSource>,
-    #[asset(path = "audio/Voices of Western Backyard Birds updated 2/21 Cassin's Finch Song.ogg")]
-    pub cassins_finch_song: Handle<AudioSource>,
-    #[asset(path = "audio/Voices of Western Backyard Birds updated 2/22 Cassin's Finch Call.ogg")]
-    pub cassins_finch_call: Handle<AudioSource>,
-    #[asset(path = "audio/Voices of Western Backyard Birds updated 2/23 House Finch Song.ogg")]
-    pub house_finch_song: Handle<AudioSource>,
-    #[asset(path = "audio/Voices of Western Backyard Birds updated 2/24 House Finch Call.ogg")]
-    pub house_finch_call: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/25 Pine Siskin Song, Calls.ogg"
-    )]
-    pub pine_siskin_song_calls: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/26 American Goldfinch Song, Call.ogg"
-    )]
-    pub american_goldfinch_song_call: Handle<AudioSource>,
-    #[asset(
-        path = "audio/Voices of Western Backyard Birds updated 2/27 Evening Grosbeak Calls.ogg"
-    )]
-    pub evening_grosbeak_calls: Handle<AudioSource>,
+    #[asset(path = "audio/rain.wav")]
+    pub rain: Handle<AudioSource>,
+    #[asset(path = "audio/wind.wav")]
+    pub wind: Handle<AudioSource>,
+    #[asset(path = "audio/cricket.wav")]
+    pub cricket: Handle<AudioSource>,
+    #[asset(path = "audio/cicada.wav")]
+    pub cicada: Handle<AudioSource>,
+    #[asset(path = "audio/squirrel.wav")]
+    pub squirrel: Handle<AudioSource>,
+    #[asset(path = "audio/chipmunk.wav")]
+    pub chipmunk: Handle<AudioSource>,
+}
 
-    // Nocturnal birds
-    #[asset(path = "audio/Great Horned Owl Call.ogg")]
-    pub great_horned_owl_call: Handle<AudioSource>,
-    #[asset(path = "audio/Barn Owl Call.ogg")]
-    pub barn_owl_call: Handle<AudioSource>,
-    #[asset(path = "audio/Western Screech-Owl Call.ogg")]
-    pub western_screech_owl_call: Handle<AudioSource>,
+/// Per-species data and call clips. See `assets/birds.species.ron`.
+#[derive(AssetCollection, Resource)]
+pub struct SpeciesAssets {
+    #[asset(path = "birds.species.ron")]
+    pub catalog: Handle<SpeciesCatalog>,
 }
 
 #[derive(AssetCollection, Resource)]
@@ -7,13 +7,14 @@ use bevy::ecs::system::NonSendMarker;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy::winit::WINIT_WINDOWS;
-use bevy_game::GamePlugin; // ToDo: Replace bevy_game with your new crate name.
+use bevy_game::{DayMode, ForestSeed, GamePlugin, GrassQuality}; // ToDo: Replace bevy_game with your new crate name.
 use std::io::Cursor;
+use std::str::FromStr;
 use winit::window::Icon;
 
 fn main() {
-    App::new()
-        .insert_resource(ClearColor(Color::linear_rgb(0.4, 0.4, 0.4)))
+    let mut app = App::new();
+    app.insert_resource(ClearColor(Color::linear_rgb(0.4, 0.4, 0.4)))
         .add_plugins(
             DefaultPlugins
                 .set(WindowPlugin {
@@ -34,8 +35,37 @@ fn main() {
                 }),
         )
         .add_plugins(GamePlugin)
-        .add_systems(Startup, set_window_icon)
-        .run();
+        .add_systems(Startup, set_window_icon);
+    apply_arguments(&mut app);
+    app.run();
+}
+
+/// Settings picked on the command line, ahead of the menu:
+/// `--day accelerated|fixed:PROGRESS|LATITUDE,LONGITUDE` picks the [`DayMode`],
+/// `--seed SEED` the [`ForestSeed`], as shown on the menu, and `--grass off|low|medium|high`
+/// the [`GrassQuality`].
+fn apply_arguments(app: &mut App) {
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args.next().unwrap_or_default();
+        let applied = match flag.as_str() {
+            "--day" => insert_parsed::<DayMode>(app, &value),
+            "--seed" => insert_parsed::<ForestSeed>(app, &value),
+            "--grass" => insert_parsed::<GrassQuality>(app, &value),
+            _ => Err("unknown option".to_string()),
+        };
+        if let Err(error) = applied {
+            warn!("Ignoring {flag} {value}: {error}");
+        }
+    }
+}
+
+fn insert_parsed<T: Resource + FromStr<Err = String>>(
+    app: &mut App,
+    value: &str,
+) -> Result<(), String> {
+    app.insert_resource(value.parse::<T>()?);
+    Ok(())
 }
 
 // Sets the icon on windows and X11
@@ -1,15 +1,22 @@
 use crate::GameState;
+use crate::forest::ForestSeed;
+use crate::grass::GrassQuality;
 use crate::loading::TextureAssets;
+use crate::scene::DayMode;
 use bevy::prelude::*;
 
 pub struct MenuPlugin;
 
-/// This plugin is responsible for the game menu (containing only one button...)
+/// This plugin is responsible for the game menu (the play button and a few settings)
 /// The menu is only drawn during the State `GameState::Menu` and is removed when that state is exited
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(GameState::Menu), setup_menu)
-            .add_systems(Update, click_play_button.run_if(in_state(GameState::Menu)))
+            .add_systems(
+                Update,
+                (click_play_button, (change_settings, show_settings).chain())
+                    .run_if(in_state(GameState::Menu)),
+            )
             .add_systems(OnExit(GameState::Menu), cleanup_menu);
     }
 }
@@ -71,6 +78,32 @@ fn setup_menu(mut commands: Commands, textures: Res<TextureAssets>) {
                     },
                     TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
                 ));
+            for setting in Setting::ALL {
+                children
+                    .spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(320.0),
+                            height: Val::Px(40.0),
+                            margin: UiRect::top(Val::Px(10.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BackgroundColor(ButtonColors::default().normal),
+                        ButtonColors::default(),
+                        setting,
+                    ))
+                    .with_child((
+                        Text::default(),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
+                        setting,
+                    ));
+            }
         });
     commands
         .spawn((
@@ -168,6 +201,19 @@ struct ChangeState(GameState);
 #[derive(Component)]
 struct OpenLink(&'static str);
 
+/// A menu button that steps through the choices for one setting, and its label.
+#[derive(Component, Clone, Copy)]
+enum Setting {
+    Day,
+    /// Grows a new forest from a random seed
+    Forest,
+    Grass,
+}
+
+impl Setting {
+    const ALL: [Self; 3] = [Self::Day, Self::Forest, Self::Grass];
+}
+
 fn click_play_button(
     mut next_state: ResMut<NextState<GameState>>,
     mut interaction_query: Query<
@@ -202,6 +248,42 @@ fn click_play_button(
     }
 }
 
+fn change_settings(
+    interaction_query: Query<(&Interaction, &Setting), (Changed<Interaction>, With<Button>)>,
+    mut day_mode: ResMut<DayMode>,
+    mut forest_seed: ResMut<ForestSeed>,
+    mut grass_quality: ResMut<GrassQuality>,
+) {
+    for (interaction, setting) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match setting {
+            Setting::Day => *day_mode = day_mode.next(),
+            Setting::Forest => *forest_seed = ForestSeed(rand::random()),
+            Setting::Grass => *grass_quality = grass_quality.next(),
+        }
+    }
+}
+
+fn show_settings(
+    day_mode: Res<DayMode>,
+    forest_seed: Res<ForestSeed>,
+    grass_quality: Res<GrassQuality>,
+    mut labels: Query<(&Setting, &mut Text)>,
+) {
+    for (setting, mut text) in &mut labels {
+        let label = match setting {
+            Setting::Day => format!("Day: {}", *day_mode),
+            Setting::Forest => format!("Forest: {}", *forest_seed),
+            Setting::Grass => format!("Grass: {:?}", *grass_quality),
+        };
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+}
+
 fn cleanup_menu(mut commands: Commands, menu: Query<Entity, With<Menu>>) {
     for entity in menu.iter() {
         commands.entity(entity).despawn();
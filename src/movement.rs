@@ -0,0 +1,85 @@
+//! Movement shared by everything that gets around on its own: birds and the other wildlife.
+//!
+//! Systems steer by setting a [`Velocity`]. Positions advance in [`PhysicalTranslation`] on the
+//! fixed timestep, and every frame the `Transform` is placed between the last two steps, so
+//! motion stays smooth whatever the frame rate.
+
+use bevy::prelude::*;
+
+use crate::GameState;
+
+pub struct MovementPlugin;
+
+impl Plugin for MovementPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DidFixedTimestepRunThisFrame>()
+            .add_systems(FixedPreUpdate, set_fixed_timestep_flag)
+            .add_systems(PreUpdate, clear_fixed_timestep_flag)
+            .add_systems(
+                FixedUpdate,
+                advance_physics.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                RunFixedMainLoop,
+                interpolate_transforms
+                    .in_set(RunFixedMainLoopSystems::AfterFixedMainLoop)
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+// -- Fixed timestep flag --
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct DidFixedTimestepRunThisFrame(bool);
+
+fn clear_fixed_timestep_flag(mut flag: ResMut<DidFixedTimestepRunThisFrame>) {
+    flag.0 = false;
+}
+
+fn set_fixed_timestep_flag(mut flag: ResMut<DidFixedTimestepRunThisFrame>) {
+    flag.0 = true;
+}
+
+// -- Components --
+
+/// Where the animal really is, as of the last fixed step.
+#[derive(Component, Default, Deref, DerefMut)]
+pub struct PhysicalTranslation(pub Vec3);
+
+/// Where the animal was one fixed step earlier.
+#[derive(Component, Default, Deref, DerefMut)]
+pub struct PreviousPhysicalTranslation(pub Vec3);
+
+#[derive(Component, Default, Deref, DerefMut)]
+pub struct Velocity(pub Vec3);
+
+// -- Physics --
+
+fn advance_physics(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(
+        &mut PhysicalTranslation,
+        &mut PreviousPhysicalTranslation,
+        &Velocity,
+    )>,
+) {
+    for (mut current, mut previous, velocity) in query.iter_mut() {
+        previous.0 = current.0;
+        current.0 += velocity.0 * fixed_time.delta_secs();
+    }
+}
+
+fn interpolate_transforms(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(
+        &mut Transform,
+        &PhysicalTranslation,
+        &PreviousPhysicalTranslation,
+    )>,
+) {
+    let alpha = fixed_time.overstep_fraction();
+    for (mut transform, current, previous) in query.iter_mut() {
+        transform.translation = previous.0.lerp(current.0, alpha);
+    }
+}
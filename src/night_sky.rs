@@ -0,0 +1,278 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::asset::RenderAssetUsages;
+use bevy::pbr::{ExtendedMaterial, MaterialExtension};
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, Extent3d, Face, TextureDimension, TextureFormat};
+use bevy::shader::ShaderRef;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::GameState;
+use crate::scene::{DayClock, Moon, SeasonClock, sky_direction};
+
+/// The moon and a dome of stars that fades in at twilight and turns around the pole through
+/// the night.
+pub struct NightSkyPlugin;
+
+impl Plugin for NightSkyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<MoonMaterial>::default())
+            .add_systems(OnEnter(GameState::Playing), setup_night_sky)
+            .add_systems(
+                Update,
+                update_night_sky.run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// Radius of the star dome, centered on the clearing; large enough to hold the camera.
+const DOME_RADIUS: f32 = 90.0;
+/// The moon sits just inside the dome.
+const MOON_DISTANCE: f32 = 80.0;
+const MOON_RADIUS: f32 = 2.5;
+const MOON_COLOR: Color = Color::srgb(0.95, 0.93, 0.85);
+/// How bright the dark side of the moon looks, lit by earthshine, next to the sunlit side.
+const EARTHSHINE: f32 = 0.02;
+/// The moon shader, see `assets/shaders/moon.wgsl`.
+const MOON_SHADER: &str = "shaders/moon.wgsl";
+
+/// Size of the equirectangular star map.
+const STAR_MAP_SIZE: (u32, u32) = (1024, 512);
+const STAR_COUNT: usize = 1500;
+/// The same stars every night.
+const STAR_SEED: u64 = 0x5747;
+/// Stars come out between these sun elevations.
+const DUSK: f32 = 0.0;
+const NIGHT: f32 = -0.12;
+/// Share of the stars that still show under a full moon high in the sky.
+const MOONLIT_STARS: f32 = 0.5;
+/// Smaller changes to the stars' fade or the moon's lighting wouldn't show.
+const VISIBLE_CHANGE: f32 = 0.005;
+
+#[derive(Component)]
+struct StarDome;
+
+#[derive(Component)]
+struct MoonDisc;
+
+type MoonMaterial = ExtendedMaterial<StandardMaterial, MoonPhase>;
+
+/// Lights the moon from the sun's side of the sky, so it shows its phase: a thin crescent
+/// just after new moon, a half disc at the quarters and all of it at full moon.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+struct MoonPhase {
+    /// Unit vector pointing at the sun
+    #[uniform(100)]
+    sun: Vec3,
+    /// Lit fraction of the disc, see [`Moon::illumination`]
+    #[uniform(100)]
+    illumination: f32,
+    /// See [`EARTHSHINE`]
+    #[uniform(100)]
+    earthshine: f32,
+}
+
+impl MoonPhase {
+    fn new(moon: &Moon, sun: Vec3) -> Self {
+        Self {
+            sun,
+            illumination: moon.illumination(),
+            earthshine: EARTHSHINE,
+        }
+    }
+
+    /// Whether the moon would look any different lit like this instead of like `other`.
+    fn differs_from(&self, other: &Self) -> bool {
+        self.sun.angle_between(other.sun) >= VISIBLE_CHANGE
+            || (self.illumination - other.illumination).abs() >= VISIBLE_CHANGE
+    }
+}
+
+impl MaterialExtension for MoonPhase {
+    fn fragment_shader() -> ShaderRef {
+        MOON_SHADER.into()
+    }
+}
+
+fn setup_night_sky(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut moon_materials: ResMut<Assets<MoonMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let mut rng = ChaCha8Rng::seed_from_u64(STAR_SEED);
+    let stars = images.add(star_map(&mut rng));
+
+    // Seen from the inside; the sky has its own light and sits beyond the fog
+    commands.spawn((
+        StarDome,
+        Mesh3d(meshes.add(Sphere::new(DOME_RADIUS).mesh().uv(48, 24))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::WHITE.with_alpha(0.0),
+            base_color_texture: Some(stars),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            fog_enabled: false,
+            cull_mode: Some(Face::Front),
+            ..default()
+        })),
+        Transform::default(),
+        Visibility::Hidden,
+    ));
+
+    commands.spawn((
+        MoonDisc,
+        Mesh3d(meshes.add(Sphere::new(MOON_RADIUS).mesh().uv(24, 12))),
+        MeshMaterial3d(moon_materials.add(MoonMaterial {
+            base: StandardMaterial {
+                base_color: MOON_COLOR,
+                unlit: true,
+                fog_enabled: false,
+                ..default()
+            },
+            extension: MoonPhase::new(&Moon::default(), Vec3::Y),
+        })),
+        Transform::default(),
+        Visibility::Hidden,
+    ));
+}
+
+/// Scatters stars over an equirectangular map, most of them faint. White pixels with the
+/// star's brightness as alpha, so the dome's material can fade them all at once.
+fn star_map(rng: &mut impl Rng) -> Image {
+    let (width, height) = STAR_MAP_SIZE;
+    let mut data = vec![0; (width * height * 4) as usize];
+    for _ in 0..STAR_COUNT {
+        // Even over the sphere rather than bunched at the poles
+        let x = rng.random_range(0..width);
+        let polar = (1.0 - 2.0 * rng.random::<f32>()).acos() / std::f32::consts::PI;
+        let y = ((polar * height as f32) as u32).min(height - 1);
+        let brightness = rng.random::<f32>().powi(3).max(0.15);
+
+        let pixel = ((y * width + x) * 4) as usize;
+        data[pixel..pixel + 3].fill(255);
+        data[pixel + 3] = (brightness * 255.0) as u8;
+    }
+
+    Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+/// How much of the starfield shows, from 0 in daylight to 1 on a dark night. Moonlight
+/// washes out the fainter stars.
+fn star_visibility(sun_elevation: f32, moonlight: f32) -> f32 {
+    let darkness = ((DUSK - sun_elevation) / (DUSK - NIGHT)).clamp(0.0, 1.0);
+    darkness * (1.0 - (1.0 - MOONLIT_STARS) * moonlight)
+}
+
+/// Fades the stars and turns them with the sky, and puts the moon in its place showing its
+/// phase. Neither material is touched while hidden or unless it would visibly change.
+fn update_night_sky(
+    clock: Res<DayClock>,
+    season: Res<SeasonClock>,
+    moon: Res<Moon>,
+    mut dome: Query<
+        (
+            &MeshMaterial3d<StandardMaterial>,
+            &mut Transform,
+            &mut Visibility,
+        ),
+        (With<StarDome>, Without<MoonDisc>),
+    >,
+    mut disc: Query<
+        (
+            &MeshMaterial3d<MoonMaterial>,
+            &mut Transform,
+            &mut Visibility,
+        ),
+        With<MoonDisc>,
+    >,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut moon_materials: ResMut<Assets<MoonMaterial>>,
+) {
+    if let Ok((material, mut transform, mut visibility)) = dome.single_mut() {
+        let alpha = star_visibility(clock.sun_elevation(), moon.brightness());
+        visibility.set_if_neq(if alpha > 0.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        if alpha > 0.0 {
+            // Touching a material sends it to the GPU again
+            let faded = materials.get(&material.0).is_some_and(|material| {
+                (material.base_color.alpha() - alpha).abs() >= VISIBLE_CHANGE
+            });
+            if faded && let Some(material) = materials.get_mut(&material.0) {
+                material.base_color.set_alpha(alpha);
+            }
+
+            // The sphere's poles are on its Z axis: stand it up, then turn it about the
+            // celestial pole once a day
+            let pole = sky_direction(season.latitude.to_radians(), 0.0);
+            transform.rotation = Quat::from_axis_angle(pole, -clock.progress() * TAU)
+                * Quat::from_rotation_x(-FRAC_PI_2);
+        }
+    }
+
+    if let Ok((material, mut transform, mut visibility)) = disc.single_mut() {
+        let up = moon.direction.y > 0.0;
+        visibility.set_if_neq(if up {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        if up {
+            transform.translation = moon.direction * MOON_DISTANCE;
+            let phase = MoonPhase::new(&moon, clock.sun_direction());
+            let changed = moon_materials
+                .get(&material.0)
+                .is_some_and(|material| phase.differs_from(&material.extension));
+            if changed && let Some(material) = moon_materials.get_mut(&material.0) {
+                material.extension = phase;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stars_come_out_at_night() {
+        assert_eq!(star_visibility(0.5, 0.0), 0.0, "No stars by day");
+        assert_eq!(star_visibility(-0.5, 0.0), 1.0);
+        let twilight = star_visibility(-0.05, 0.0);
+        assert!(twilight > 0.0 && twilight < 1.0);
+        assert_eq!(star_visibility(-0.5, 1.0), MOONLIT_STARS);
+    }
+
+    #[test]
+    fn test_moon_is_only_relit_for_a_visible_change() {
+        let moon = |phase: f32| Moon { phase, ..default() };
+        let lit = MoonPhase::new(&moon(0.25), Vec3::X);
+        assert!(!MoonPhase::new(&moon(0.2501), Vec3::X).differs_from(&lit));
+        assert!(MoonPhase::new(&moon(0.3), Vec3::X).differs_from(&lit));
+        let later = Quat::from_rotation_z(VISIBLE_CHANGE * 2.0) * Vec3::X;
+        assert!(MoonPhase::new(&moon(0.25), later).differs_from(&lit));
+    }
+
+    #[test]
+    fn test_star_map_is_mostly_dark_sky() {
+        let image = star_map(&mut ChaCha8Rng::seed_from_u64(STAR_SEED));
+        let data = image.data.expect("star map has pixel data");
+        let lit = data.chunks(4).filter(|pixel| pixel[3] > 0).count();
+        assert!(lit > STAR_COUNT / 2 && lit <= STAR_COUNT);
+    }
+}
@@ -1,17 +1,25 @@
 use bevy::camera::ScalingMode;
 use bevy::prelude::*;
 use bevy_kira_audio::prelude::SpatialAudioReceiver;
+use serde::Deserialize;
 
 use crate::GameState;
+use crate::astronomy::{self, LunarPosition, SolarPosition};
+use crate::forest::{Forest, ForestSeed};
+use crate::terrain::{self, Terrain, WaterMaterial};
+use crate::tree::{self, TreeModels};
+use crate::weather::Weather;
 
 pub struct ScenePlugin;
 
 impl Plugin for ScenePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Playing), setup_scene)
+        app.init_resource::<DayMode>()
+            .init_resource::<ForestSeed>()
+            .add_systems(OnEnter(GameState::Playing), setup_scene)
             .add_systems(
                 Update,
-                update_day_night_cycle.run_if(in_state(GameState::Playing)),
+                (update_day_night_cycle, sway_perches).run_if(in_state(GameState::Playing)),
             );
     }
 }
@@ -19,6 +27,227 @@ impl Plugin for ScenePlugin {
 #[derive(Component)]
 pub struct Tree;
 
+/// What a spot with [`PerchPoints`] offers the birds that land there.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Destination {
+    /// Cover to rest and sing from
+    Canopy,
+    /// Somewhere to feed
+    Food(FoodSource),
+    /// A bird bath or pond to drink from
+    Water,
+}
+
+/// Where a species finds its food.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum FoodSource {
+    /// Seed scattered on the ground
+    Ground,
+    /// The seed feeder
+    Feeder,
+    /// Insects under the bark of tree trunks
+    Trunk,
+    /// Butterflies and bees, caught on the wing
+    Insects,
+}
+
+/// Height of the ground in the main clearing. Ask [`Terrain`] for the height anywhere else.
+pub const GROUND_LEVEL: f32 = 0.0;
+
+/// A simple collision shape, centered on the entity's `Transform`, that birds steer around.
+#[derive(Component, Clone, Copy, Debug)]
+pub enum Obstacle {
+    Sphere {
+        radius: f32,
+    },
+    /// Upright cylinder
+    Cylinder {
+        radius: f32,
+        half_height: f32,
+    },
+}
+
+impl Obstacle {
+    /// Distance from `point` to the obstacle surface; negative inside.
+    pub fn signed_distance(&self, center: Vec3, point: Vec3) -> f32 {
+        let local = point - center;
+        match *self {
+            Self::Sphere { radius } => local.length() - radius,
+            Self::Cylinder {
+                radius,
+                half_height,
+            } => {
+                let q = Vec2::new(local.xz().length() - radius, local.y.abs() - half_height);
+                q.max_element().min(0.0) + q.max(Vec2::ZERO).length()
+            }
+        }
+    }
+
+    /// Outward surface normal closest to `point`.
+    pub fn normal(&self, center: Vec3, point: Vec3) -> Vec3 {
+        // Central differences keep this shape-agnostic
+        const EPS: f32 = 0.01;
+        let d = |offset: Vec3| {
+            self.signed_distance(center, point + offset)
+                - self.signed_distance(center, point - offset)
+        };
+        Vec3::new(d(Vec3::X * EPS), d(Vec3::Y * EPS), d(Vec3::Z * EPS)).normalize_or(Vec3::Y)
+    }
+}
+
+/// Perch slots per tree canopy, when its branches offer none.
+const PERCH_SLOTS_PER_TREE: usize = 6;
+/// Slots where birds can cling to each trunk.
+const TRUNK_SLOTS: usize = 3;
+/// How far above the canopy surface a perched bird sits.
+const PERCH_CLEARANCE: f32 = 0.15;
+/// Roughly where a small bird's body sits above whatever it stands on.
+const STANDING_HEIGHT: f32 = 0.15;
+
+/// Spots where a single bird can sit: on a tree, at the feeder, on the ground or at the water.
+/// Birds reserve a slot before flying to it.
+#[derive(Component)]
+pub struct PerchPoints {
+    slots: Vec<PerchSlot>,
+}
+
+struct PerchSlot {
+    position: Vec3,
+    occupant: Option<Entity>,
+    /// The limb a slot out on a branch is on, and where on the limb, see [`sway_perches`]
+    branch: Option<(Entity, Vec3)>,
+}
+
+impl PerchPoints {
+    /// Slots spread around the upper half of a spherical canopy, alternating between a lower
+    /// and a higher ring so neighbors don't line up.
+    pub fn on_canopy(center: Vec3, radius: f32, count: usize) -> Self {
+        let slots = (0..count)
+            .map(|i| {
+                let azimuth = i as f32 / count as f32 * std::f32::consts::TAU;
+                let elevation: f32 = if i % 2 == 0 { 0.35 } else { 0.8 };
+                let dir = Vec3::new(
+                    elevation.cos() * azimuth.cos(),
+                    elevation.sin(),
+                    elevation.cos() * azimuth.sin(),
+                );
+                PerchSlot {
+                    position: center + dir * (radius + PERCH_CLEARANCE),
+                    occupant: None,
+                    branch: None,
+                }
+            })
+            .collect();
+        Self { slots }
+    }
+
+    /// Slots spiralling up the lower part of a trunk, below the canopy, where birds cling
+    /// to the bark.
+    pub fn on_trunk(center: Vec3, radius: f32, half_height: f32, count: usize) -> Self {
+        let slots = (0..count)
+            .map(|i| {
+                let t = (i as f32 + 0.5) / count as f32;
+                let azimuth = t * std::f32::consts::TAU;
+                let height = half_height * (-0.4 + t);
+                PerchSlot {
+                    position: center
+                        + Vec3::new(azimuth.cos(), 0.0, azimuth.sin()) * (radius + PERCH_CLEARANCE)
+                        + Vec3::Y * height,
+                    occupant: None,
+                    branch: None,
+                }
+            })
+            .collect();
+        Self { slots }
+    }
+
+    /// Slots out along the branches of a tree, at a point on each limb. They start where
+    /// the limb is at rest, and move with it as it sways.
+    pub fn on_branches(branches: impl IntoIterator<Item = (Vec3, Entity, Vec3)>) -> Self {
+        let slots = branches
+            .into_iter()
+            .map(|(position, limb, on_limb)| PerchSlot {
+                position,
+                occupant: None,
+                branch: Some((limb, on_limb)),
+            })
+            .collect();
+        Self { slots }
+    }
+
+    /// Slots evenly spaced on a level ring, e.g. around a feeder tray, the rim of a bird bath
+    /// or across a patch of seed on the ground. `center` is the surface birds stand on.
+    pub fn on_ring(center: Vec3, radius: f32, count: usize) -> Self {
+        let slots = (0..count)
+            .map(|i| {
+                let azimuth = i as f32 / count as f32 * std::f32::consts::TAU;
+                PerchSlot {
+                    position: center
+                        + Vec3::new(azimuth.cos(), 0.0, azimuth.sin()) * radius
+                        + Vec3::Y * STANDING_HEIGHT,
+                    occupant: None,
+                    branch: None,
+                }
+            })
+            .collect();
+        Self { slots }
+    }
+
+    pub fn has_free_slot(&self) -> bool {
+        self.slots.iter().any(|slot| slot.occupant.is_none())
+    }
+
+    pub fn free_slots(&self) -> Vec<usize> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.occupant.is_none())
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    pub fn position(&self, slot: usize) -> Vec3 {
+        self.slots[slot].position
+    }
+
+    /// Whether the slot is out on a branch, moving as the tree sways.
+    pub fn on_branch(&self, slot: usize) -> bool {
+        self.slots[slot].branch.is_some()
+    }
+
+    /// Returns false if the slot is already taken.
+    pub fn reserve(&mut self, slot: usize, bird: Entity) -> bool {
+        match self.slots[slot].occupant {
+            Some(_) => false,
+            None => {
+                self.slots[slot].occupant = Some(bird);
+                true
+            }
+        }
+    }
+
+    /// Frees the slot, but only if `bird` is the one holding it.
+    pub fn release(&mut self, slot: usize, bird: Entity) {
+        if self.slots[slot].occupant == Some(bird) {
+            self.slots[slot].occupant = None;
+        }
+    }
+}
+
+/// Keeps slots out on the branches where their limb has swayed to, so the birds sitting
+/// there sway with it.
+fn sway_perches(mut spots: Query<&mut PerchPoints>, limbs: Query<&GlobalTransform>) {
+    for mut perches in spots.iter_mut() {
+        for slot in perches.slots.iter_mut() {
+            if let Some((limb, on_limb)) = slot.branch
+                && let Ok(limb) = limbs.get(limb)
+            {
+                slot.position = limb.transform_point(on_limb);
+            }
+        }
+    }
+}
+
 #[derive(Component)]
 struct Sun;
 
@@ -26,29 +255,325 @@ struct Sun;
 const DAY_DURATION: f32 = 120.0;
 const NOON_SHADOW_STRENGTH: f32 = 0.7;
 
+/// How the day/night cycle keeps time. Insert it as a resource to pick a mode; the default
+/// is [`DayMode::Accelerated`].
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub enum DayMode {
+    /// A [`DAY_DURATION`] day, with the calendar moving on a week each day.
+    #[default]
+    Accelerated,
+    /// The real sun over a place on Earth, following the system clock, so the dawn chorus
+    /// happens at actual dawn. Degrees, north and east positive.
+    RealTime { latitude: f32, longitude: f32 },
+    /// Time stands still at this [`DayClock::progress`].
+    Fixed { progress: f32 },
+}
+
+/// Places offered on the menu to follow the real sun over: name, latitude and longitude.
+const PLACES: [(&str, f32, f32); 3] = [
+    ("Seattle", 47.6, -122.3),
+    ("London", 51.5, -0.1),
+    ("Sydney", -33.9, 151.2),
+];
+/// [`DayClock::progress`] at noon.
+const NOON: f32 = 0.25;
+
+impl DayMode {
+    /// The mode after this one on the menu: accelerated, the real sun over each of the
+    /// [`PLACES`], then noon forever.
+    pub fn next(self) -> Self {
+        let choices: Vec<Self> = std::iter::once(Self::Accelerated)
+            .chain(
+                PLACES
+                    .iter()
+                    .map(|&(_, latitude, longitude)| Self::RealTime {
+                        latitude,
+                        longitude,
+                    }),
+            )
+            .chain([Self::Fixed { progress: NOON }])
+            .collect();
+        let index = choices.iter().position(|mode| *mode == self);
+        choices[index.map_or(0, |index| (index + 1) % choices.len())]
+    }
+}
+
+impl std::fmt::Display for DayMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Self::Accelerated => write!(f, "{}-minute days", DAY_DURATION / 60.0),
+            Self::RealTime {
+                latitude,
+                longitude,
+            } => match PLACES
+                .iter()
+                .find(|&&(_, lat, lon)| (lat, lon) == (latitude, longitude))
+            {
+                Some((name, ..)) => write!(f, "Real sun over {name}"),
+                None => write!(f, "Real sun at {latitude:.1}, {longitude:.1}"),
+            },
+            Self::Fixed { progress } if progress == NOON => write!(f, "Always noon"),
+            Self::Fixed { progress } => write!(f, "Stopped {progress:.2} through the day"),
+        }
+    }
+}
+
+impl std::str::FromStr for DayMode {
+    type Err = String;
+
+    /// `accelerated`, `fixed:PROGRESS` (see [`DayClock::progress`]), or `LATITUDE,LONGITUDE`
+    /// in degrees for the real sun over that place.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |value: &str, range: std::ops::RangeInclusive<f32>| {
+            value
+                .trim()
+                .parse::<f32>()
+                .ok()
+                .filter(|number| range.contains(number))
+                .ok_or_else(|| format!("{value:?} isn't a number in {range:?}"))
+        };
+        if s == "accelerated" {
+            Ok(Self::Accelerated)
+        } else if let Some(progress) = s.strip_prefix("fixed:") {
+            Ok(Self::Fixed {
+                progress: number(progress, 0.0..=1.0)?,
+            })
+        } else if let Some((latitude, longitude)) = s.split_once(',') {
+            Ok(Self::RealTime {
+                latitude: number(latitude, -90.0..=90.0)?,
+                longitude: number(longitude, -180.0..=180.0)?,
+            })
+        } else {
+            Err(format!(
+                "expected accelerated, fixed:PROGRESS or LATITUDE,LONGITUDE, not {s:?}"
+            ))
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct DayClock {
     pub elapsed: f32,
+    /// Fraction of the cycle the sun is up, see [`SeasonClock::daylight`]
+    pub daylight: f32,
+    /// Sun elevation at noon, see [`SeasonClock::noon_elevation`]
+    pub noon_elevation: f32,
+    /// Where the real sun is, in [`DayMode::RealTime`]
+    pub solar: Option<SolarPosition>,
+}
+
+impl Default for DayClock {
+    /// An equinox day with the sun passing straight overhead.
+    fn default() -> Self {
+        Self {
+            elapsed: 0.0,
+            daylight: 0.5,
+            noon_elevation: 1.0,
+            solar: None,
+        }
+    }
 }
 
 impl DayClock {
     /// Returns normalized day progress 0..1 where 0=sunrise, 0.25=noon, 0.5=sunset, 0.5..1=night.
+    /// Longer days stretch the first half at the expense of the night.
     pub fn progress(&self) -> f32 {
-        (self.elapsed % DAY_DURATION) / DAY_DURATION
+        let t = (self.elapsed % DAY_DURATION) / DAY_DURATION;
+        if t < self.daylight {
+            0.5 * t / self.daylight
+        } else {
+            0.5 + 0.5 * (t - self.daylight) / (1.0 - self.daylight)
+        }
+    }
+
+    /// Sets the clock to the given day progress, see [`DayClock::progress`].
+    pub fn set_progress(&mut self, progress: f32) {
+        let progress = progress.rem_euclid(1.0);
+        let t = if progress < 0.5 {
+            progress * 2.0 * self.daylight
+        } else {
+            self.daylight + (progress - 0.5) * 2.0 * (1.0 - self.daylight)
+        };
+        self.elapsed = t * DAY_DURATION;
+    }
+
+    /// Sets the clock to match the real sun: sunrise, noon and sunset fall when they do
+    /// outside.
+    pub fn follow_sun(&mut self, sun: SolarPosition, season: &SeasonClock) {
+        self.daylight = season.daylight();
+        self.noon_elevation = season.noon_elevation();
+        // The sun rises at minus half a day's daylight worth of hour angle
+        let since_sunrise = sun.hour_angle / std::f32::consts::TAU + self.daylight / 2.0;
+        self.elapsed = since_sunrise.rem_euclid(1.0) * DAY_DURATION;
+        self.solar = Some(sun);
+    }
+
+    /// Unit vector pointing at the sun.
+    pub fn sun_direction(&self) -> Vec3 {
+        match self.solar {
+            Some(sun) => sky_direction(sun.elevation, sun.azimuth),
+            // Sun orbits in the XY plane, offset on Z so it arcs overhead
+            None => {
+                let sun_angle = self.progress() * std::f32::consts::TAU;
+                Vec3::new(sun_angle.cos(), self.sun_elevation(), 0.3).normalize()
+            }
+        }
     }
 
     /// Sun elevation: positive during day, negative at night.
     pub fn sun_elevation(&self) -> f32 {
-        (self.progress() * std::f32::consts::TAU).sin()
+        if let Some(sun) = self.solar {
+            return sun.elevation.sin();
+        }
+        let elevation = (self.progress() * std::f32::consts::TAU).sin();
+        if elevation > 0.0 {
+            elevation * self.noon_elevation
+        } else {
+            elevation
+        }
+    }
+}
+
+/// Days in the calendar year.
+pub const DAYS_PER_YEAR: f32 = 365.0;
+/// Calendar days that pass with each day/night cycle, so the seasons turn during a session.
+const DAYS_PER_CYCLE: f32 = 7.0;
+/// Unless told otherwise the forest sits in the Pacific Northwest, in degrees north.
+const LATITUDE: f32 = 45.0;
+/// Late April, when the summer birds are back and the dawn chorus is in full swing.
+const START_DAY: f32 = 115.0;
+/// Axial tilt of the Earth, in degrees.
+const AXIAL_TILT: f32 = 23.44;
+/// Day of the year of the March equinox.
+const SPRING_EQUINOX: f32 = 79.0;
+
+/// Days from one new moon to the next.
+const SYNODIC_MONTH: f32 = 29.53;
+/// A waxing gibbous moon to start with, up in the evening.
+const START_MOON_PHASE: f32 = 0.4;
+/// Light on a moonless night.
+const STARLIGHT_LUX: f32 = 50.0;
+/// Extra light from a full moon high in the sky.
+const MOONLIGHT_LUX: f32 = 130.0;
+/// Ambient light added by a lightning flash, lighting up the whole clearing.
+const LIGHTNING_AMBIENT: f32 = 600.0;
+
+/// The moon: its phase and where it is in the sky.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct Moon {
+    /// Fraction of the way from one new moon to the next: 0.25 is first quarter, 0.5 full
+    pub phase: f32,
+    /// Unit vector pointing at the moon; below the horizon once it has set
+    pub direction: Vec3,
+}
+
+impl Default for Moon {
+    fn default() -> Self {
+        Self {
+            phase: START_MOON_PHASE,
+            direction: Vec3::NEG_Y,
+        }
+    }
+}
+
+impl Moon {
+    /// Lit fraction of the disc, 0 at new moon and 1 at full moon.
+    pub fn illumination(&self) -> f32 {
+        (1.0 - (self.phase * std::f32::consts::TAU).cos()) / 2.0
+    }
+
+    /// How much light the moon casts, from 0 (new or set) to 1 (full and well up).
+    pub fn brightness(&self) -> f32 {
+        // Moonlight fades as the moon sinks into the haze near the horizon
+        self.illumination() * (self.direction.y * 4.0).clamp(0.0, 1.0)
+    }
+
+    /// Moves the moon along the same orbit as the sun's in [`DayMode::Accelerated`], trailing
+    /// it by its phase: a full moon rises at sunset.
+    fn follow_clock(&mut self, clock: &DayClock) {
+        let angle = (clock.progress() - self.phase) * std::f32::consts::TAU;
+        self.direction = Vec3::new(angle.cos(), angle.sin(), 0.3).normalize();
+    }
+}
+
+/// Direction to something `elevation` above the horizon at compass bearing `azimuth`. East is
+/// +X and north is -Z.
+pub fn sky_direction(elevation: f32, azimuth: f32) -> Vec3 {
+    Vec3::new(
+        azimuth.sin() * elevation.cos(),
+        elevation.sin(),
+        -azimuth.cos() * elevation.cos(),
+    )
+}
+
+/// Breeding season song: builds up from late winter, in full voice from early spring to
+/// midsummer, then tails off as birds molt.
+const SONG_SEASON: [(f32, f32); 4] = [(45.0, 0.0), (90.0, 1.0), (170.0, 1.0), (215.0, 0.0)];
+
+/// The calendar: which day of the year it is, and the day length and sun height that follow
+/// from it.
+#[derive(Resource, Clone, Debug)]
+pub struct SeasonClock {
+    /// Days since January 1st, `0..DAYS_PER_YEAR`
+    pub day: f32,
+    /// Degrees north of the equator
+    pub latitude: f32,
+}
+
+impl Default for SeasonClock {
+    fn default() -> Self {
+        Self {
+            day: START_DAY,
+            latitude: LATITUDE,
+        }
     }
 }
 
-fn setup_scene(
+impl SeasonClock {
+    pub fn advance(&mut self, days: f32) {
+        self.day = (self.day + days).rem_euclid(DAYS_PER_YEAR);
+    }
+
+    /// The sun's declination in radians: positive in the northern summer.
+    fn declination(&self) -> f32 {
+        let angle = (self.day - SPRING_EQUINOX) / DAYS_PER_YEAR * std::f32::consts::TAU;
+        AXIAL_TILT.to_radians() * angle.sin()
+    }
+
+    /// Fraction of the day the sun is above the horizon, from the sunrise equation.
+    pub fn daylight(&self) -> f32 {
+        let cos_hour_angle = -self.latitude.to_radians().tan() * self.declination().tan();
+        cos_hour_angle.clamp(-1.0, 1.0).acos() / std::f32::consts::PI
+    }
+
+    /// Sine of the sun's altitude at noon.
+    pub fn noon_elevation(&self) -> f32 {
+        (std::f32::consts::FRAC_PI_2 - self.latitude.to_radians() + self.declination()).sin()
+    }
+
+    /// How far into the breeding season it is, from 0 (winter: birds mostly call) to 1
+    /// (spring: full song).
+    pub fn song_season(&self) -> f32 {
+        let day = self.day;
+        let next = SONG_SEASON.partition_point(|(d, _)| *d <= day);
+        match (SONG_SEASON.get(next.wrapping_sub(1)), SONG_SEASON.get(next)) {
+            (Some(&(d0, s0)), Some(&(d1, s1))) => s0.lerp(s1, (day - d0) / (d1 - d0)),
+            _ => 0.0,
+        }
+    }
+}
+
+pub(crate) fn setup_scene(
     mut commands: Commands,
+    forest_seed: Res<ForestSeed>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut water_materials: ResMut<Assets<WaterMaterial>>,
 ) {
-    commands.insert_resource(DayClock { elapsed: 0.0 });
+    // The clock is set from the mode on the first update
+    commands.insert_resource(DayClock::default());
+    commands.insert_resource(SeasonClock::default());
+    commands.insert_resource(Moon::default());
 
     // Orthographic isometric camera (visual only)
     commands.spawn((
@@ -71,8 +596,16 @@ fn setup_scene(
         Transform::from_xyz(20.0, 20.0, 20.0).looking_at(Vec3::ZERO, Vec3::Y),
     ));
 
-    // Spatial audio receiver at ground level in the clearing
-    let listener_pos = Vec3::new(0.0, 1.5, 0.0);
+    let forest = Forest::generate(*forest_seed);
+    info!(
+        "Growing forest {:#x}: {} trees, {} shrubs",
+        forest.seed,
+        forest.trees.len(),
+        forest.undergrowth.len()
+    );
+
+    // Spatial audio receiver at head height in the clearing
+    let listener_pos = forest.terrain.on_ground(0.0, 0.0) + Vec3::Y * 1.5;
     commands.spawn((
         Transform::from_translation(listener_pos),
         SpatialAudioReceiver,
@@ -88,62 +621,89 @@ fn setup_scene(
         Transform::from_translation(listener_pos),
     ));
 
-    // Ground plane
-    let ground_material = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.35, 0.55, 0.25),
-        perceptual_roughness: 0.9,
-        ..default()
-    });
-    commands.spawn((
-        Mesh3d(meshes.add(Plane3d::default().mesh().size(50.0, 50.0))),
-        MeshMaterial3d(ground_material),
-    ));
+    // Ground and pond
+    terrain::spawn_ground(
+        &mut commands,
+        &forest,
+        &mut meshes,
+        &mut materials,
+        &mut water_materials,
+    );
 
-    // Tree materials
-    let trunk_material = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.45, 0.3, 0.15),
-        perceptual_roughness: 0.9,
-        ..default()
-    });
-    let canopy_material = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.2, 0.5, 0.15),
-        perceptual_roughness: 0.8,
+    // Trees, grown from the forest's seed. Birds feed on and steer around invisible stand-ins
+    // for each trunk and crown, and perch out on the swaying limbs
+    let tree_models = TreeModels::grow(forest.seed, &mut meshes);
+    let bark_and_leaves = materials.add(StandardMaterial {
+        perceptual_roughness: 0.85,
         ..default()
     });
-    let trunk_mesh = meshes.add(Cylinder::new(0.2, 2.0));
-    let canopy_mesh = meshes.add(Sphere::new(1.2).mesh().uv(16, 12));
-
-    let tree_positions = [
-        Vec3::new(-8.0, 0.0, -6.0),
-        Vec3::new(-5.0, 0.0, 3.0),
-        Vec3::new(-3.0, 0.0, -9.0),
-        Vec3::new(0.0, 0.0, 7.0),
-        Vec3::new(2.0, 0.0, -4.0),
-        Vec3::new(5.0, 0.0, 1.0),
-        Vec3::new(7.0, 0.0, -7.0),
-        Vec3::new(8.0, 0.0, 5.0),
-        Vec3::new(-6.0, 0.0, 8.0),
-        Vec3::new(4.0, 0.0, -1.0),
-    ];
-
-    for pos in &tree_positions {
-        let trunk_pos = *pos + Vec3::new(0.0, 1.0, 0.0);
-        let canopy_pos = *pos + Vec3::new(0.0, 2.8, 0.0);
+    for tree in &forest.trees {
+        let model = tree_models.get(tree);
+        let limbs = tree::spawn_tree(&mut commands, tree, model, &bark_and_leaves);
 
+        let scale = tree.scale;
+        let placed = tree::tree_transform(tree);
+        let trunk_pos = tree.position + Vec3::Y * model.trunk_height / 2.0 * scale;
         commands.spawn((
-            Mesh3d(trunk_mesh.clone()),
-            MeshMaterial3d(trunk_material.clone()),
             Transform::from_translation(trunk_pos),
+            Destination::Food(FoodSource::Trunk),
+            PerchPoints::on_trunk(
+                trunk_pos,
+                model.trunk_radius * scale,
+                model.trunk_height / 2.0 * scale,
+                TRUNK_SLOTS,
+            ),
+            Obstacle::Cylinder {
+                radius: model.trunk_radius * scale,
+                half_height: model.trunk_height / 2.0 * scale,
+            },
         ));
 
+        let crown_pos = placed.transform_point(model.crown_center);
+        let crown_radius = model.crown_radius * scale;
+        let perches = if model.perches.is_empty() {
+            PerchPoints::on_canopy(crown_pos, crown_radius, PERCH_SLOTS_PER_TREE)
+        } else {
+            PerchPoints::on_branches(model.perches.iter().map(|perch| {
+                let pivot = model.limbs[perch.limb].pivot;
+                (
+                    placed.transform_point(perch.position),
+                    limbs[perch.limb],
+                    perch.position - pivot,
+                )
+            }))
+        };
         commands.spawn((
-            Mesh3d(canopy_mesh.clone()),
-            MeshMaterial3d(canopy_material.clone()),
-            Transform::from_translation(canopy_pos),
+            Transform::from_translation(crown_pos),
             Tree,
+            Destination::Canopy,
+            perches,
+            Obstacle::Sphere {
+                radius: crown_radius,
+            },
         ));
     }
 
+    // Undergrowth: low bushes between the trees, thickest on damp ground
+    let shrub_mesh = meshes.add(Sphere::new(0.5).mesh().ico(2).expect("valid subdivisions"));
+    let shrub_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.18, 0.38, 0.14),
+        perceptual_roughness: 0.85,
+        ..default()
+    });
+    for shrub in &forest.undergrowth {
+        commands.spawn((
+            Mesh3d(shrub_mesh.clone()),
+            MeshMaterial3d(shrub_material.clone()),
+            Transform::from_translation(shrub.position + Vec3::Y * 0.15 * shrub.scale)
+                .with_scale(Vec3::new(1.0, 0.6, 1.0) * shrub.scale),
+        ));
+    }
+
+    spawn_feeding_spots(&mut commands, &forest.terrain, &mut meshes, &mut materials);
+    commands.insert_resource(forest.terrain);
+    commands.insert_resource(forest);
+
     // Sun directional light -- initial position set by update_day_night_cycle
     commands.spawn((
         DirectionalLight {
@@ -163,23 +723,170 @@ fn setup_scene(
     });
 }
 
+/// Patches of seed on the ground, a seed feeder and a bird bath in the open between the trees.
+fn spawn_feeding_spots(
+    commands: &mut Commands,
+    terrain: &Terrain,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) {
+    let wood_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.5, 0.38, 0.22),
+        perceptual_roughness: 0.9,
+        ..default()
+    });
+    let stone_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.62, 0.6, 0.56),
+        perceptual_roughness: 0.95,
+        ..default()
+    });
+
+    // Seed scattered on the ground
+    let seed_mesh = meshes.add(Cylinder::new(0.8, 0.02));
+    let seed_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.55, 0.45, 0.3),
+        perceptual_roughness: 1.0,
+        ..default()
+    });
+    let seed_patches = [
+        terrain.on_ground(-2.5, -2.0),
+        terrain.on_ground(2.5, 4.0),
+        terrain.on_ground(-1.0, 4.5),
+    ];
+    for pos in seed_patches {
+        commands.spawn((
+            Mesh3d(seed_mesh.clone()),
+            MeshMaterial3d(seed_material.clone()),
+            Transform::from_translation(pos + Vec3::Y * 0.01),
+            Destination::Food(FoodSource::Ground),
+            PerchPoints::on_ring(pos, 0.5, 5),
+        ));
+    }
+
+    // Seed feeder: a tray on a post
+    let feeder_pos = terrain.on_ground(1.5, -1.5);
+    let post_height = 1.6;
+    commands.spawn((
+        Mesh3d(meshes.add(Cylinder::new(0.05, post_height))),
+        MeshMaterial3d(wood_material.clone()),
+        Transform::from_translation(feeder_pos + Vec3::Y * post_height / 2.0),
+        Obstacle::Cylinder {
+            radius: 0.05,
+            half_height: post_height / 2.0,
+        },
+    ));
+    let tray_pos = feeder_pos + Vec3::Y * post_height;
+    commands.spawn((
+        Mesh3d(meshes.add(Cylinder::new(0.35, 0.05))),
+        MeshMaterial3d(wood_material),
+        Transform::from_translation(tray_pos),
+        Destination::Food(FoodSource::Feeder),
+        PerchPoints::on_ring(tray_pos, 0.35, 4),
+    ));
+
+    // Bird bath: a basin of water on a stone pedestal
+    let bath_pos = terrain.on_ground(-3.0, 1.5);
+    let pedestal_height = 0.8;
+    commands.spawn((
+        Mesh3d(meshes.add(Cylinder::new(0.12, pedestal_height))),
+        MeshMaterial3d(stone_material.clone()),
+        Transform::from_translation(bath_pos + Vec3::Y * pedestal_height / 2.0),
+        Obstacle::Cylinder {
+            radius: 0.12,
+            half_height: pedestal_height / 2.0,
+        },
+    ));
+    let basin_pos = bath_pos + Vec3::Y * pedestal_height;
+    commands.spawn((
+        Mesh3d(meshes.add(Cylinder::new(0.5, 0.1))),
+        MeshMaterial3d(stone_material),
+        Transform::from_translation(basin_pos),
+        Destination::Water,
+        PerchPoints::on_ring(basin_pos, 0.45, 4),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cylinder::new(0.42, 0.02))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgba(0.35, 0.55, 0.75, 0.8),
+            perceptual_roughness: 0.1,
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        })),
+        Transform::from_translation(basin_pos + Vec3::Y * 0.05),
+    ));
+}
+
+/// Moves the clock, calendar and moon on according to the [`DayMode`].
+fn advance_clock(
+    mode: &DayMode,
+    delta_secs: f32,
+    clock: &mut DayClock,
+    season: &mut SeasonClock,
+    moon: &mut Moon,
+    unix_seconds: impl FnOnce() -> f64,
+) {
+    match *mode {
+        DayMode::Accelerated => {
+            // The calendar moves on with the clock, changing day length and the height of the sun
+            let days = delta_secs / DAY_DURATION * DAYS_PER_CYCLE;
+            clock.elapsed += delta_secs;
+            season.advance(days);
+            clock.daylight = season.daylight();
+            clock.noon_elevation = season.noon_elevation();
+            clock.solar = None;
+            moon.phase = (moon.phase + days / SYNODIC_MONTH).rem_euclid(1.0);
+            moon.follow_clock(clock);
+        }
+        DayMode::RealTime {
+            latitude,
+            longitude,
+        } => {
+            let now = unix_seconds();
+            season.day = astronomy::day_of_year(now);
+            season.latitude = latitude;
+            clock.follow_sun(SolarPosition::at(now, latitude, longitude), season);
+            let lunar = LunarPosition::at(now, latitude, longitude);
+            moon.phase = lunar.phase;
+            moon.direction = sky_direction(lunar.elevation, lunar.azimuth);
+        }
+        DayMode::Fixed { progress } => {
+            clock.daylight = season.daylight();
+            clock.noon_elevation = season.noon_elevation();
+            clock.solar = None;
+            clock.set_progress(progress);
+            moon.follow_clock(clock);
+        }
+    }
+}
+
 /// Map a 0..1 day progress to sun angle, color, illuminance, and ambient values.
+#[allow(clippy::too_many_arguments)]
 fn update_day_night_cycle(
     time: Res<Time>,
+    mode: Res<DayMode>,
     mut clock: ResMut<DayClock>,
+    mut season: ResMut<SeasonClock>,
+    mut moon: ResMut<Moon>,
+    weather: Res<Weather>,
     mut sun_query: Query<(&mut DirectionalLight, &mut Transform), With<Sun>>,
     mut ambient: ResMut<GlobalAmbientLight>,
 ) {
-    clock.elapsed += time.delta_secs();
-    let t = (clock.elapsed % DAY_DURATION) / DAY_DURATION; // 0..1
+    advance_clock(
+        &mode,
+        time.delta_secs(),
+        &mut clock,
+        &mut season,
+        &mut moon,
+        astronomy::now,
+    );
+    let t = clock.progress(); // 0..1
 
     // Sun angle: t=0 sunrise (east horizon), t=0.25 noon (top), t=0.5 sunset (west horizon),
     // t=0.5..1.0 nighttime (sun below horizon)
     let sun_angle = t * std::f32::consts::TAU; // full circle
-    let sun_y = sun_angle.sin();
+    let sun_y = clock.sun_elevation();
     let sun_x = sun_angle.cos();
-    // Sun orbits in the XY plane, offset on Z so it arcs overhead
-    let sun_dir = Vec3::new(sun_x, sun_y, 0.3).normalize();
+    let sun_dir = clock.sun_direction();
 
     let is_day = sun_y > -0.05; // slight grace below horizon for twilight
 
@@ -221,15 +928,22 @@ fn update_day_night_cycle(
             14.0 + 30.0 * frac,
         )
     } else {
-        // Night: cool moonlight with low sky fill
+        // Night: cool moonlight, as bright as the moon allows, with low sky fill
+        let moonlight = moon.brightness();
         (
             Color::srgb(0.52, 0.56, 0.67),
-            120.0,
+            STARLIGHT_LUX + MOONLIGHT_LUX * moonlight,
             Color::srgb(0.07, 0.08, 0.13),
-            12.0,
+            8.0 + 8.0 * moonlight,
         )
     };
 
+    // Clouds dim the sun and moon, and soften the sky fill less than the direct light
+    let conditions = weather.conditions();
+    let sun_lux = sun_lux * conditions.sunlight;
+    let ambient_brightness = ambient_brightness * conditions.sunlight.lerp(1.0, 0.5)
+        + LIGHTNING_AMBIENT * conditions.lightning;
+
     if let Ok((mut light, mut transform)) = sun_query.single_mut() {
         light.color = sun_color;
         light.illuminance = sun_lux;
@@ -242,8 +956,12 @@ fn update_day_night_cycle(
             let sun_pos = sun_dir * 50.0;
             *transform = Transform::from_translation(sun_pos).looking_at(Vec3::ZERO, Vec3::Y);
         } else {
-            // Night: position the light as moonlight from opposite side, high up
-            let moon_dir = Vec3::new(-sun_x, 0.5, -0.3).normalize();
+            // Night: light comes from the moon while it's up, otherwise from high overhead
+            let moon_dir = if moon.direction.y > 0.1 {
+                moon.direction
+            } else {
+                Vec3::new(-sun_x, 0.5, -0.3).normalize()
+            };
             let moon_pos = moon_dir * 50.0;
             *transform = Transform::from_translation(moon_pos).looking_at(Vec3::ZERO, Vec3::Y);
         }
@@ -259,16 +977,18 @@ mod tests {
 
     #[test]
     fn test_day_clock_progress_bounds() {
-        let clock = DayClock { elapsed: 0.0 };
+        let clock = DayClock::default();
         assert_eq!(clock.progress(), 0.0);
 
         let clock = DayClock {
             elapsed: DAY_DURATION / 2.0,
+            ..default()
         };
         assert!((clock.progress() - 0.5).abs() < 0.001);
 
         let clock = DayClock {
             elapsed: DAY_DURATION,
+            ..default()
         };
         assert!(clock.progress() < 0.001);
     }
@@ -278,6 +998,7 @@ mod tests {
         // Test that day clock wraps after full cycle
         let clock = DayClock {
             elapsed: DAY_DURATION * 2.5,
+            ..default()
         };
         let progress = clock.progress();
         assert!((0.0..1.0).contains(&progress));
@@ -289,6 +1010,7 @@ mod tests {
         // At quarter day (noon), sun should be at highest point
         let clock = DayClock {
             elapsed: DAY_DURATION / 4.0,
+            ..default()
         };
         assert!(clock.sun_elevation() > 0.0);
     }
@@ -298,6 +1020,7 @@ mod tests {
         // At three-quarters day (midnight), sun should be below horizon
         let clock = DayClock {
             elapsed: DAY_DURATION * 0.75,
+            ..default()
         };
         assert!(clock.sun_elevation() < 0.0);
     }
@@ -305,7 +1028,7 @@ mod tests {
     #[test]
     fn test_sun_elevation_sunrise_is_zero() {
         // At start of day (sunrise), sun should be near horizon
-        let clock = DayClock { elapsed: 0.0 };
+        let clock = DayClock::default();
         assert!(clock.sun_elevation().abs() < 0.001);
     }
 
@@ -314,10 +1037,314 @@ mod tests {
         // At half day (sunset), sun should be near horizon
         let clock = DayClock {
             elapsed: DAY_DURATION / 2.0,
+            ..default()
         };
         assert!(clock.sun_elevation().abs() < 0.001);
     }
 
+    #[test]
+    fn test_long_days_shorten_the_night() {
+        let summer = DayClock {
+            elapsed: DAY_DURATION * 0.6,
+            daylight: 0.65,
+            ..default()
+        };
+        assert!(
+            summer.sun_elevation() > 0.0,
+            "Still light after the equinox sunset"
+        );
+        let sunset = DayClock {
+            elapsed: DAY_DURATION * 0.65,
+            ..summer
+        };
+        assert!((sunset.progress() - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_summer_days_are_long_and_bright() {
+        let summer = SeasonClock {
+            day: 172.0,
+            ..default()
+        };
+        let winter = SeasonClock {
+            day: 355.0,
+            ..default()
+        };
+        let equinox = SeasonClock {
+            day: SPRING_EQUINOX,
+            ..default()
+        };
+        assert!(summer.daylight() > 0.6, "{}", summer.daylight());
+        assert!(winter.daylight() < 0.4, "{}", winter.daylight());
+        assert!((equinox.daylight() - 0.5).abs() < 0.01);
+        assert!(summer.noon_elevation() > equinox.noon_elevation());
+        assert!(
+            winter.noon_elevation() > 0.0,
+            "The sun still rises in winter"
+        );
+    }
+
+    #[test]
+    fn test_calendar_wraps_at_new_year() {
+        let mut season = SeasonClock {
+            day: 360.0,
+            ..default()
+        };
+        season.advance(DAYS_PER_CYCLE);
+        assert!((season.day - 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_birds_sing_most_in_spring() {
+        let song = |day| SeasonClock { day, ..default() }.song_season();
+        assert_eq!(song(10.0), 0.0);
+        assert_eq!(song(120.0), 1.0);
+        assert_eq!(song(300.0), 0.0);
+        assert!((0.0..1.0).contains(&song(60.0)));
+    }
+
+    #[test]
+    fn test_day_modes_from_the_command_line() {
+        assert_eq!("accelerated".parse(), Ok(DayMode::Accelerated));
+        assert_eq!("fixed:0.7".parse(), Ok(DayMode::Fixed { progress: 0.7 }));
+        assert_eq!(
+            "47.6,-122.3".parse(),
+            Ok(DayMode::RealTime {
+                latitude: 47.6,
+                longitude: -122.3
+            })
+        );
+        assert!(
+            "95,0".parse::<DayMode>().is_err(),
+            "No latitude past the poles"
+        );
+        assert!("fixed:2".parse::<DayMode>().is_err());
+        assert!("dawn".parse::<DayMode>().is_err());
+    }
+
+    #[test]
+    fn test_menu_cycles_through_day_modes() {
+        let mut mode = DayMode::Accelerated;
+        let mut seen = Vec::new();
+        for _ in 0..PLACES.len() + 2 {
+            seen.push(mode.to_string());
+            mode = mode.next();
+        }
+        assert_eq!(mode, DayMode::Accelerated, "Back where it started");
+        assert_eq!(seen[1], "Real sun over Seattle");
+        assert_eq!(seen.last().unwrap(), "Always noon");
+        // A place picked on the command line leads back into the choices
+        let custom = DayMode::RealTime {
+            latitude: 10.0,
+            longitude: 20.0,
+        };
+        assert_eq!(custom.to_string(), "Real sun at 10.0, 20.0");
+        assert_eq!(custom.next(), DayMode::Accelerated);
+    }
+
+    #[test]
+    fn test_fixed_mode_holds_the_time() {
+        let mut clock = DayClock::default();
+        let mut season = SeasonClock::default();
+        let day = season.day;
+        for _ in 0..3 {
+            advance_clock(
+                &DayMode::Fixed { progress: 0.7 },
+                10.0,
+                &mut clock,
+                &mut season,
+                &mut Moon::default(),
+                || unreachable!(),
+            );
+        }
+        assert!((clock.progress() - 0.7).abs() < 0.001);
+        assert_eq!(season.day, day, "The calendar stands still too");
+    }
+
+    #[test]
+    fn test_real_time_mode_follows_the_sun() {
+        // 2024-06-20 20:10 UTC, around solar noon in Seattle
+        let seattle = DayMode::RealTime {
+            latitude: 47.6,
+            longitude: -122.3,
+        };
+        let mut clock = DayClock::default();
+        let mut season = SeasonClock::default();
+        let mut moon = Moon::default();
+        advance_clock(&seattle, 0.0, &mut clock, &mut season, &mut moon, || {
+            1_718_914_200.0
+        });
+        assert!(
+            (clock.progress() - 0.25).abs() < 0.01,
+            "{}",
+            clock.progress()
+        );
+        assert!(clock.sun_elevation() > 0.85);
+        assert!(clock.daylight > 0.6, "Long summer days");
+        assert!((season.day - 172.0).abs() < 1.0);
+
+        // Eight hours later it's night
+        advance_clock(&seattle, 0.0, &mut clock, &mut season, &mut moon, || {
+            1_718_914_200.0 + 8.0 * 3600.0
+        });
+        assert!(clock.progress() > 0.5);
+        assert!(clock.sun_elevation() < 0.0);
+    }
+
+    #[test]
+    fn test_moon_waxes_and_wanes() {
+        let mut clock = DayClock::default();
+        let mut season = SeasonClock::default();
+        let mut moon = Moon {
+            phase: 0.0,
+            ..default()
+        };
+        let new_moon = moon.illumination();
+        // Half a lunar month of accelerated days
+        let half_month = SYNODIC_MONTH / 2.0 / DAYS_PER_CYCLE * DAY_DURATION;
+        let accelerated = DayMode::Accelerated;
+        advance_clock(
+            &accelerated,
+            half_month,
+            &mut clock,
+            &mut season,
+            &mut moon,
+            || unreachable!(),
+        );
+        assert!((moon.phase - 0.5).abs() < 0.001);
+        assert!(new_moon < 0.001 && moon.illumination() > 0.999);
+    }
+
+    #[test]
+    fn test_full_moon_lights_the_night() {
+        let midnight = DayClock {
+            elapsed: DAY_DURATION * 0.75,
+            ..default()
+        };
+        let mut full = Moon {
+            phase: 0.5,
+            ..default()
+        };
+        full.follow_clock(&midnight);
+        assert!(full.direction.y > 0.9, "Full moon is high at midnight");
+        assert!(full.brightness() > 0.9);
+
+        let mut new = Moon {
+            phase: 0.0,
+            ..default()
+        };
+        new.follow_clock(&midnight);
+        assert!(new.direction.y < 0.0, "New moon set with the sun");
+        assert_eq!(new.brightness(), 0.0);
+    }
+
+    #[test]
+    fn test_sphere_obstacle_signed_distance() {
+        let sphere = Obstacle::Sphere { radius: 1.0 };
+        let center = Vec3::new(1.0, 2.0, 3.0);
+        assert!((sphere.signed_distance(center, center + Vec3::X * 3.0) - 2.0).abs() < 0.001);
+        assert!(sphere.signed_distance(center, center) < 0.0);
+        let normal = sphere.normal(center, center + Vec3::new(0.0, 0.0, 2.0));
+        assert!(normal.abs_diff_eq(Vec3::Z, 0.01));
+    }
+
+    #[test]
+    fn test_cylinder_obstacle_signed_distance() {
+        let trunk = Obstacle::Cylinder {
+            radius: 0.2,
+            half_height: 1.0,
+        };
+        let center = Vec3::new(0.0, 1.0, 0.0);
+        // Beside the trunk
+        assert!((trunk.signed_distance(center, Vec3::new(1.2, 1.0, 0.0)) - 1.0).abs() < 0.001);
+        // Above the trunk top
+        assert!((trunk.signed_distance(center, Vec3::new(0.0, 2.5, 0.0)) - 0.5).abs() < 0.001);
+        assert!(trunk.signed_distance(center, center) < 0.0);
+        let normal = trunk.normal(center, Vec3::new(-1.0, 1.2, 0.0));
+        assert!(normal.abs_diff_eq(Vec3::NEG_X, 0.01));
+    }
+
+    #[test]
+    fn test_perch_points_sit_on_canopy_surface() {
+        let center = Vec3::new(2.0, 2.8, -4.0);
+        let perches = PerchPoints::on_canopy(center, 1.2, PERCH_SLOTS_PER_TREE);
+        assert_eq!(perches.free_slots().len(), PERCH_SLOTS_PER_TREE);
+        for slot in perches.free_slots() {
+            let position = perches.position(slot);
+            assert!(((position - center).length() - (1.2 + PERCH_CLEARANCE)).abs() < 0.001);
+            assert!(
+                position.y > center.y,
+                "Perches should be on the upper canopy"
+            );
+        }
+    }
+
+    #[test]
+    fn test_trunk_perches_cling_below_the_canopy() {
+        let trunk = Vec3::new(0.0, 1.0, 0.0);
+        let perches = PerchPoints::on_trunk(trunk, 0.2, 1.0, TRUNK_SLOTS);
+        let obstacle = Obstacle::Cylinder {
+            radius: 0.2,
+            half_height: 1.0,
+        };
+        for slot in perches.free_slots() {
+            let position = perches.position(slot);
+            assert!((obstacle.signed_distance(trunk, position) - PERCH_CLEARANCE).abs() < 0.001);
+            assert!(
+                position.y < 1.6,
+                "Trunk perches should stay below the canopy"
+            );
+        }
+    }
+
+    #[test]
+    fn test_branch_perches_sway_with_their_limb() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        let limb = world.spawn(GlobalTransform::from_xyz(0.0, 2.0, 0.0)).id();
+        let spot = world
+            .spawn(PerchPoints::on_branches([(
+                Vec3::new(1.0, 2.0, 0.0),
+                limb,
+                Vec3::X,
+            )]))
+            .id();
+
+        // Bent a little about its pivot, carrying the perch up with it
+        *world.get_mut::<GlobalTransform>(limb).unwrap() = GlobalTransform::from(
+            Transform::from_xyz(0.0, 2.0, 0.0).with_rotation(Quat::from_rotation_z(0.1)),
+        );
+        world.run_system_once(sway_perches).unwrap();
+        let perches = world.get::<PerchPoints>(spot).unwrap();
+        assert!(perches.on_branch(0));
+        let expected = Vec3::new(0.1f32.cos(), 2.0 + 0.1f32.sin(), 0.0);
+        assert!(perches.position(0).abs_diff_eq(expected, 0.001));
+    }
+
+    #[test]
+    fn test_perch_reserve_and_release() {
+        let mut perches = PerchPoints::on_canopy(Vec3::ZERO, 1.0, 2);
+        let a = Entity::from_raw_u32(1).unwrap();
+        let b = Entity::from_raw_u32(2).unwrap();
+
+        assert!(perches.reserve(0, a));
+        assert!(
+            !perches.reserve(0, b),
+            "A taken slot can't be reserved twice"
+        );
+        assert!(perches.reserve(1, b));
+        assert!(!perches.has_free_slot());
+
+        perches.release(0, b);
+        assert!(
+            !perches.has_free_slot(),
+            "Only the occupant can release a slot"
+        );
+        perches.release(0, a);
+        assert_eq!(perches.free_slots(), vec![0]);
+    }
+
     #[test]
     fn test_day_duration_constant() {
         // Ensure day duration is reasonable (between 30 seconds and 5 minutes)
@@ -0,0 +1,377 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::light::NotShadowReceiver;
+use bevy::prelude::*;
+use bevy::render::render_resource::Face;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::GameState;
+use crate::scene::{DayClock, Moon};
+
+/// A gradient sky dome that follows the sun through the day, fog and background that match
+/// its horizon, and a layer of clouds drifting high over the clearing.
+pub struct SkyPlugin;
+
+impl Plugin for SkyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Clouds>()
+            .add_systems(OnEnter(GameState::Playing), setup_sky)
+            .add_systems(
+                Update,
+                (update_sky, drift_clouds).run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// The cloud layer. Weather changes `cover`; clouds grow in and shrink away to match.
+#[derive(Resource, Clone, Debug)]
+pub struct Clouds {
+    /// Share of the sky covered, from 0 (clear) to 1 (overcast)
+    pub cover: f32,
+    /// Drift velocity over the ground, in units/second
+    pub wind: Vec2,
+}
+
+impl Default for Clouds {
+    fn default() -> Self {
+        Self {
+            cover: 0.3,
+            wind: Vec2::new(0.6, 0.25),
+        }
+    }
+}
+
+/// Radius of the sky dome, just outside the star dome so stars show in front of it.
+const DOME_RADIUS: f32 = 95.0;
+/// The sky is only recolored once the sun has moved this far, in radians, or the moonlight or
+/// cloud cover has changed this much.
+const VISIBLE_CHANGE: f32 = 0.005;
+
+/// Sky colors by sun elevation: (elevation, zenith, horizon), sorted by elevation.
+const SKY_COLORS: [(f32, [f32; 3], [f32; 3]); 5] = [
+    // Night
+    (-0.2, [0.01, 0.012, 0.035], [0.035, 0.045, 0.09]),
+    // Deep twilight
+    (-0.1, [0.05, 0.06, 0.18], [0.25, 0.18, 0.3]),
+    // Sunrise and sunset
+    (0.0, [0.2, 0.28, 0.55], [0.95, 0.55, 0.32]),
+    // Golden hour
+    (0.15, [0.28, 0.45, 0.8], [0.9, 0.78, 0.62]),
+    // Day
+    (0.4, [0.25, 0.47, 0.88], [0.7, 0.82, 0.95]),
+];
+/// Light gray of a fully overcast sky at midday.
+const OVERCAST: f32 = 0.75;
+/// How tightly the glow around a low sun is focused.
+const GLOW_FOCUS: i32 = 6;
+/// Moonlight lifts the night sky by up to this much.
+const MOONLIT_SKY: [f32; 3] = [0.03, 0.04, 0.07];
+
+/// Clouds hang above the camera, out of view but between the sun and the clearing.
+const CLOUD_HEIGHT: std::ops::Range<f32> = 32.0..38.0;
+/// Clouds drift over a square this wide, centered on the clearing, and wrap around.
+const CLOUD_FIELD: f32 = 140.0;
+const CLOUD_COUNT: usize = 24;
+const PUFFS_PER_CLOUD: std::ops::RangeInclusive<usize> = 3..=6;
+/// The same clouds every session.
+const CLOUD_SEED: u64 = 0xc10d;
+
+#[derive(Component)]
+struct SkyDome;
+
+/// One cloud, made of puffs. Its `rank` decides at what cover it shows: the first clouds
+/// are there on fair days, the last only when it's overcast.
+#[derive(Component)]
+struct Cloud {
+    rank: usize,
+}
+
+/// Colors of the sky for one moment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct SkyColors {
+    zenith: LinearRgba,
+    horizon: LinearRgba,
+    /// Added around a low sun, so it has no alpha of its own
+    glow: LinearRgba,
+}
+
+fn setup_sky(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Stand the sphere's poles up so vertex positions are directions in the world, and color
+    // it from the inside with vertex colors
+    let dome = Sphere::new(DOME_RADIUS)
+        .mesh()
+        .uv(48, 24)
+        .rotated_by(Quat::from_rotation_x(-FRAC_PI_2));
+    commands.spawn((
+        SkyDome,
+        Mesh3d(meshes.add(dome)),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            unlit: true,
+            fog_enabled: false,
+            cull_mode: Some(Face::Front),
+            ..default()
+        })),
+        Transform::default(),
+    ));
+
+    let mut rng = ChaCha8Rng::seed_from_u64(CLOUD_SEED);
+    let puff = meshes.add(Sphere::new(1.0).mesh().uv(16, 8));
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.95, 0.95, 0.97),
+        perceptual_roughness: 1.0,
+        ..default()
+    });
+    for rank in 0..CLOUD_COUNT {
+        let half = CLOUD_FIELD / 2.0;
+        let position = Vec3::new(
+            rng.random_range(-half..half),
+            rng.random_range(CLOUD_HEIGHT),
+            rng.random_range(-half..half),
+        );
+        commands
+            .spawn((
+                Cloud { rank },
+                Transform::from_translation(position),
+                Visibility::default(),
+            ))
+            .with_children(|cloud| {
+                for _ in 0..rng.random_range(PUFFS_PER_CLOUD) {
+                    let offset = Vec3::new(
+                        rng.random_range(-4.0..4.0),
+                        rng.random_range(-0.5..0.5),
+                        rng.random_range(-2.5..2.5),
+                    );
+                    let size = rng.random_range(2.0..4.0);
+                    cloud.spawn((
+                        Mesh3d(puff.clone()),
+                        MeshMaterial3d(material.clone()),
+                        Transform::from_translation(offset).with_scale(Vec3::new(
+                            size,
+                            size * 0.45,
+                            size,
+                        )),
+                        NotShadowReceiver,
+                    ));
+                }
+            });
+    }
+}
+
+/// Interpolates [`SKY_COLORS`] for the sun's elevation, then fades toward gray as clouds
+/// cover the sky.
+fn sky_colors(sun_elevation: f32, moonlight: f32, cover: f32) -> SkyColors {
+    let next = SKY_COLORS.partition_point(|(elevation, _, _)| *elevation <= sun_elevation);
+    let (zenith, horizon) = match (SKY_COLORS.get(next.wrapping_sub(1)), SKY_COLORS.get(next)) {
+        (Some((e0, z0, h0)), Some((e1, z1, h1))) => {
+            let t = (sun_elevation - e0) / (e1 - e0);
+            (lerp3(*z0, *z1, t), lerp3(*h0, *h1, t))
+        }
+        (Some((_, zenith, horizon)), None) | (None, Some((_, zenith, horizon))) => {
+            (*zenith, *horizon)
+        }
+        (None, None) => unreachable!("the sky has colors"),
+    };
+    let mut zenith = LinearRgba::from(Color::srgb_from_array(zenith));
+    let mut horizon = LinearRgba::from(Color::srgb_from_array(horizon));
+
+    // Moonlight only shows once it's dark
+    let night = (-sun_elevation / 0.2).clamp(0.0, 1.0);
+    let moonlit = (LinearRgba::from(Color::srgb_from_array(MOONLIT_SKY)) * (moonlight * night))
+        .with_alpha(0.0);
+    zenith += moonlit;
+    horizon += moonlit;
+
+    // The glow around a low sun, strongest at sunrise and sunset
+    let low_sun = 1.0 - (sun_elevation.abs() / 0.25).min(1.0);
+    let mut glow = (horizon * (0.6 * low_sun)).with_alpha(0.0);
+
+    // Clouds gray everything out at the sky's own brightness, and hide the glow
+    let overcast = cover.clamp(0.0, 1.0).powf(1.5) * 0.85;
+    let gray = |color: LinearRgba| {
+        let luminance = color.luminance().max(0.0);
+        let brightness = luminance.min(OVERCAST);
+        LinearRgba::rgb(brightness, brightness, brightness * 1.04)
+    };
+    zenith = zenith.mix(&gray(zenith), overcast);
+    horizon = horizon.mix(&gray(horizon), overcast);
+    glow *= 1.0 - overcast;
+
+    SkyColors {
+        zenith,
+        horizon,
+        glow,
+    }
+}
+
+fn lerp3(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [a[0].lerp(b[0], t), a[1].lerp(b[1], t), a[2].lerp(b[2], t)]
+}
+
+/// What lights the sky: where the sun is, the moonlight and the cloud cover.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct SkyLight {
+    sun: Vec3,
+    moonlight: f32,
+    cover: f32,
+}
+
+impl SkyLight {
+    /// Whether the sky would look any different lit like this instead of like `other`.
+    fn differs_from(&self, other: &Self) -> bool {
+        self.sun.angle_between(other.sun) >= VISIBLE_CHANGE
+            || (self.moonlight - other.moonlight).abs() >= VISIBLE_CHANGE
+            || (self.cover - other.cover).abs() >= VISIBLE_CHANGE
+    }
+}
+
+/// Recolors the dome, the background and the fog to match the sky, whenever it visibly
+/// changes.
+#[allow(clippy::too_many_arguments)]
+fn update_sky(
+    clock: Res<DayClock>,
+    moon: Res<Moon>,
+    clouds: Res<Clouds>,
+    dome: Query<&Mesh3d, With<SkyDome>>,
+    mut fogs: Query<&mut DistanceFog>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut clear_color: ResMut<ClearColor>,
+    mut colored: Local<Option<SkyLight>>,
+) {
+    let light = SkyLight {
+        sun: clock.sun_direction(),
+        moonlight: moon.brightness(),
+        cover: clouds.cover,
+    };
+    if colored.is_some_and(|colored| !light.differs_from(&colored)) {
+        return;
+    }
+    let colors = sky_colors(clock.sun_elevation(), light.moonlight, light.cover);
+    let sun = light.sun;
+
+    if let Ok(dome) = dome.single()
+        && let Some(mesh) = meshes.get_mut(&dome.0)
+        && let Some(positions) = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .and_then(|positions| positions.as_float3())
+    {
+        let vertex_colors: Vec<[f32; 4]> = positions
+            .iter()
+            .map(|position| {
+                let direction = Vec3::from_array(*position) / DOME_RADIUS;
+                // Most of the change happens close to the horizon
+                let height = direction.y.max(0.0).sqrt();
+                let glow = direction.dot(sun).max(0.0).powi(GLOW_FOCUS);
+                let color = colors.horizon.mix(&colors.zenith, height) + colors.glow * glow;
+                color.to_f32_array()
+            })
+            .collect();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vertex_colors);
+        *colored = Some(light);
+    }
+
+    clear_color.0 = colors.horizon.into();
+    for mut fog in fogs.iter_mut() {
+        fog.color = colors.horizon.into();
+        fog.directional_light_color = (colors.horizon + colors.glow).into();
+    }
+}
+
+/// Blows clouds along with the wind, wrapping them around the field, and grows or shrinks
+/// them to match the cloud cover.
+fn drift_clouds(
+    time: Res<Time>,
+    clouds: Res<Clouds>,
+    mut cloud_query: Query<(&Cloud, &mut Transform, &mut Visibility)>,
+) {
+    let drift = clouds.wind * time.delta_secs();
+    let shown = clouds.cover.clamp(0.0, 1.0) * CLOUD_COUNT as f32;
+
+    for (cloud, mut transform, mut visibility) in cloud_query.iter_mut() {
+        let half = CLOUD_FIELD / 2.0;
+        transform.translation.x = wrap(transform.translation.x + drift.x, half);
+        transform.translation.z = wrap(transform.translation.z + drift.y, half);
+
+        let size = (shown - cloud.rank as f32).clamp(0.0, 1.0);
+        transform.scale = Vec3::splat(size.max(0.01));
+        *visibility = if size > 0.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// Wraps a coordinate into `-half..half`.
+fn wrap(coordinate: f32, half: f32) -> f32 {
+    (coordinate + half).rem_euclid(2.0 * half) - half
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_sky_is_blue_overhead() {
+        let day = sky_colors(0.8, 0.0, 0.0);
+        assert!(day.zenith.blue > day.zenith.red);
+        assert!(
+            day.zenith.blue > day.horizon.blue * 0.5,
+            "The horizon is paler, not brighter"
+        );
+        assert_eq!(day.glow.luminance(), 0.0, "No glow with the sun high up");
+    }
+
+    #[test]
+    fn test_sunset_glows_warm() {
+        let sunset = sky_colors(0.0, 0.0, 0.0);
+        assert!(sunset.horizon.red > sunset.horizon.blue);
+        assert!(sunset.glow.red > 0.0);
+    }
+
+    #[test]
+    fn test_night_sky_is_dark_and_moonlit() {
+        let dark = sky_colors(-0.5, 0.0, 0.0);
+        let moonlit = sky_colors(-0.5, 1.0, 0.0);
+        assert!(dark.zenith.luminance() < 0.01);
+        assert!(moonlit.zenith.luminance() > dark.zenith.luminance());
+    }
+
+    #[test]
+    fn test_overcast_sky_is_gray() {
+        let overcast = sky_colors(0.8, 0.0, 1.0);
+        let saturation = |color: LinearRgba| color.blue - color.red;
+        assert!(saturation(overcast.zenith) < saturation(sky_colors(0.8, 0.0, 0.0).zenith));
+    }
+
+    #[test]
+    fn test_sky_recolors_only_on_visible_changes() {
+        let light = SkyLight {
+            sun: Vec3::X,
+            moonlight: 0.0,
+            cover: 0.3,
+        };
+        let later = |angle: f32| SkyLight {
+            sun: Quat::from_rotation_z(angle) * Vec3::X,
+            ..light
+        };
+        assert!(!later(VISIBLE_CHANGE / 2.0).differs_from(&light));
+        assert!(later(VISIBLE_CHANGE * 2.0).differs_from(&light));
+        let cloudier = SkyLight {
+            cover: 0.4,
+            ..light
+        };
+        assert!(cloudier.differs_from(&light));
+    }
+
+    #[test]
+    fn test_clouds_wrap_around_the_field() {
+        assert_eq!(wrap(10.0, 70.0), 10.0);
+        assert!((wrap(71.0, 70.0) - -69.0).abs() < 0.001);
+        assert!((wrap(-71.0, 70.0) - 69.0).abs() < 0.001);
+    }
+}
@@ -0,0 +1,883 @@
+use std::ops::Index;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use bevy_kira_audio::AudioSource;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::loading::SpeciesAssets;
+use crate::scene::FoodSource;
+use crate::wildlife::AnimalKind;
+
+pub struct SpeciesPlugin;
+
+/// Registers the species catalog asset and its loader.
+/// The catalog itself is loaded during `GameState::Loading` (see [`SpeciesAssets`]).
+impl Plugin for SpeciesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<SpeciesCatalog>()
+            .register_asset_loader(SpeciesCatalogLoader);
+    }
+}
+
+// -- Activity --
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum ActivityPeriod {
+    /// Active from sunrise through sunset, peak at dawn/dusk. Most songbirds.
+    Diurnal,
+    /// Strictly daytime -- needs good light. Woodpeckers, visual foragers.
+    StrictlyDiurnal,
+    /// Dawn and dusk specialists, quiet midday. Some sparrows, finches.
+    Crepuscular,
+    /// Active from dusk through dawn. Owls.
+    Nocturnal,
+}
+
+impl ActivityPeriod {
+    /// Returns true if this activity period is active at the given sun elevation.
+    /// sun_elevation: positive = daytime, negative = nighttime
+    /// moonlight: 0 = moonless night, 1 = full moon high in the sky
+    pub fn is_active(&self, sun_elevation: f32, moonlight: f32) -> bool {
+        match self {
+            Self::Diurnal => {
+                // Active when sun is above horizon (with a little twilight grace)
+                sun_elevation > -0.02
+            }
+            Self::StrictlyDiurnal => {
+                // Need good light -- sun well above horizon
+                sun_elevation > 0.15
+            }
+            Self::Crepuscular => {
+                // Active during day, but especially dawn/dusk
+                sun_elevation > -0.04
+            }
+            Self::Nocturnal => {
+                // Active from dusk through dawn. Bright moonlight brings owls out while it's
+                // still twilight; on dark nights they wait until the sun is down.
+                sun_elevation < 0.02 + 0.08 * moonlight
+            }
+        }
+    }
+}
+
+// -- Residency --
+
+/// When in the year a species is around. Days are days of the year, see
+/// [`SeasonClock`](crate::scene::SeasonClock).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum Residency {
+    /// Here all year.
+    #[default]
+    Resident,
+    /// Arrives in spring to breed and leaves at the end of summer.
+    SummerBreeder,
+    /// Breeds farther north and spends the winter here.
+    WinterVisitor,
+    /// Only passes through on its spring and fall migrations.
+    PassageMigrant,
+}
+
+impl Residency {
+    /// Returns true if the species is around on the given day of the year.
+    pub fn is_present(&self, day_of_year: f32) -> bool {
+        let within = |(first, last): (f32, f32)| (first..=last).contains(&day_of_year);
+        match self {
+            Self::Resident => true,
+            // Mid April to mid September
+            Self::SummerBreeder => within((105.0, 258.0)),
+            // Mid October to mid April
+            Self::WinterVisitor => !within((105.0, 288.0)),
+            // Late April to late May, and late August to early October
+            Self::PassageMigrant => within((110.0, 145.0)) || within((232.0, 278.0)),
+        }
+    }
+}
+
+// -- Song --
+
+/// Outside the breeding season birds mostly just call, this fraction as often as they sing
+/// in spring.
+const WINTER_SONG: f32 = 0.35;
+
+/// How much a species sings over the day: keyframes of ([`DayClock::progress`], rate), where a
+/// rate of 1 means the bird sings at every perch and 0 means it stays silent. Rates are
+/// interpolated linearly and wrap around from night back to sunrise.
+///
+/// [`DayClock::progress`]: crate::scene::DayClock::progress
+#[derive(Clone, Debug, PartialEq)]
+pub struct SongCurve(Vec<(f32, f32)>);
+
+impl SongCurve {
+    /// Keyframes must be sorted by progress within `0..1`, with rates within `0..=1`.
+    pub fn new(keyframes: Vec<(f32, f32)>) -> Option<Self> {
+        let in_range = keyframes
+            .iter()
+            .all(|(progress, rate)| (0.0..1.0).contains(progress) && (0.0..=1.0).contains(rate));
+        let sorted = keyframes.windows(2).all(|pair| pair[0].0 < pair[1].0);
+        (!keyframes.is_empty() && in_range && sorted).then_some(Self(keyframes))
+    }
+
+    /// True if the species never sings at any time of day.
+    pub fn is_silent(&self) -> bool {
+        self.0.iter().all(|(_, rate)| *rate == 0.0)
+    }
+
+    /// Default curve for species without their own.
+    pub fn for_activity(activity: ActivityPeriod) -> Self {
+        let keyframes = match activity {
+            // Loud dawn chorus, a quieter midday and a smaller evening peak
+            ActivityPeriod::Diurnal => vec![
+                (0.02, 1.0),
+                (0.08, 0.8),
+                (0.18, 0.35),
+                (0.3, 0.25),
+                (0.45, 0.5),
+                (0.52, 0.2),
+                (0.56, 0.0),
+                (0.95, 0.0),
+                (0.98, 0.3),
+            ],
+            // Starts once it's properly light, busiest mid-morning
+            ActivityPeriod::StrictlyDiurnal => vec![
+                (0.03, 0.0),
+                (0.08, 0.8),
+                (0.2, 0.5),
+                (0.3, 0.4),
+                (0.42, 0.4),
+                (0.47, 0.0),
+            ],
+            // Peaks at dawn and dusk, nearly silent at midday
+            ActivityPeriod::Crepuscular => vec![
+                (0.01, 1.0),
+                (0.07, 0.6),
+                (0.15, 0.15),
+                (0.35, 0.15),
+                (0.46, 0.7),
+                (0.5, 1.0),
+                (0.54, 0.3),
+                (0.58, 0.0),
+                (0.93, 0.0),
+                (0.97, 0.4),
+            ],
+            // Calls through the night, most of all after dusk and before dawn
+            ActivityPeriod::Nocturnal => vec![
+                (0.02, 0.1),
+                (0.05, 0.0),
+                (0.45, 0.0),
+                (0.52, 0.6),
+                (0.58, 1.0),
+                (0.7, 0.6),
+                (0.85, 0.5),
+                (0.95, 0.8),
+            ],
+        };
+        Self(keyframes)
+    }
+
+    /// Singing rate at the given day progress.
+    pub fn rate(&self, progress: f32) -> f32 {
+        let keys = &self.0;
+        let progress = progress.rem_euclid(1.0);
+        let next = keys.partition_point(|(t, _)| *t <= progress);
+
+        // Wrap around sunrise by shifting the neighboring keyframe by a full day
+        let (t0, r0) = match next {
+            0 => {
+                let (t, r) = keys[keys.len() - 1];
+                (t - 1.0, r)
+            }
+            _ => keys[next - 1],
+        };
+        let (t1, r1) = match keys.get(next) {
+            Some(key) => *key,
+            None => (keys[0].0 + 1.0, keys[0].1),
+        };
+
+        if t1 - t0 <= f32::EPSILON {
+            return r0;
+        }
+        r0.lerp(r1, (progress - t0) / (t1 - t0))
+    }
+}
+
+// -- Calls --
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum CallKind {
+    /// Songs and contact calls, given from a perch.
+    Vocal,
+    /// Drumming on a trunk. Woodpeckers.
+    Drum,
+    /// Warns neighbors that a predator is near.
+    Alarm,
+}
+
+/// One sound clip a species can make.
+#[derive(Clone, Debug)]
+pub struct BirdCall {
+    pub kind: CallKind,
+    pub clip: Handle<AudioSource>,
+}
+
+// -- Flight --
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum FlightStyle {
+    /// Steady wingbeats on a straight line. Jays, doves, blackbirds.
+    Direct,
+    /// Bursts of flapping followed by closed-wing dips. Finches, woodpeckers.
+    Bounding,
+    /// Long, silent glides with few wingbeats. Owls.
+    Glide,
+}
+
+// -- Size --
+
+/// Species of similar size share one set of body meshes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SizeClass {
+    /// Chickadees, nuthatches, finches
+    Small,
+    /// Jays, woodpeckers, blackbirds
+    Medium,
+    /// Doves, large owls
+    Large,
+}
+
+impl SizeClass {
+    pub const ALL: [Self; 3] = [Self::Small, Self::Medium, Self::Large];
+
+    pub fn from_radius(radius: f32) -> Self {
+        if radius < 0.17 {
+            Self::Small
+        } else if radius < 0.25 {
+            Self::Medium
+        } else {
+            Self::Large
+        }
+    }
+
+    /// Body radius the shared meshes are built for.
+    pub fn radius(&self) -> f32 {
+        match self {
+            Self::Small => 0.15,
+            Self::Medium => 0.21,
+            Self::Large => 0.3,
+        }
+    }
+}
+
+// -- Catalog --
+
+/// Identifies a species by its position in the [`SpeciesCatalog`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BirdSpecies(usize);
+
+#[cfg(test)]
+impl BirdSpecies {
+    pub fn from_index(index: usize) -> Self {
+        Self(index)
+    }
+}
+
+/// Everything the simulation knows about one species.
+#[derive(Clone, Debug)]
+pub struct SpeciesData {
+    pub name: String,
+    pub color: Color,
+    pub radius: f32,
+    /// Flight speed in units/second
+    pub speed: f32,
+    pub activity: ActivityPeriod,
+    pub residency: Residency,
+    pub flight: FlightStyle,
+    pub song: SongCurve,
+    /// Social species arrive, move and depart as flocks
+    pub social: bool,
+    /// Species whose songs this one answers, including itself
+    pub answers: Vec<BirdSpecies>,
+    /// Predators frighten other birds
+    pub predator: bool,
+    /// Flies at predators instead of hiding from them
+    pub mobs: bool,
+    /// Where this species feeds, most preferred first; empty for species that don't feed here
+    pub forages: Vec<FoodSource>,
+    /// Clings to trunks and hops up them while feeding, drumming as it goes
+    pub climbs: bool,
+    /// Animals this species scolds from the trees when they come into the clearing
+    pub scolds: Vec<AnimalKind>,
+    pub calls: Vec<BirdCall>,
+}
+
+impl SpeciesData {
+    /// Returns true if this species would be active at the given sun elevation and
+    /// moonlight, see [`ActivityPeriod::is_active`].
+    pub fn is_active(&self, sun_elevation: f32, moonlight: f32) -> bool {
+        self.activity.is_active(sun_elevation, moonlight)
+    }
+
+    /// Returns true if this species is around on the given day of the year.
+    pub fn is_present(&self, day_of_year: f32) -> bool {
+        self.residency.is_present(day_of_year)
+    }
+
+    /// How likely this species is to sing at each perch, at the given day progress and point
+    /// in the breeding season (see [`SeasonClock::song_season`]).
+    ///
+    /// [`SeasonClock::song_season`]: crate::scene::SeasonClock::song_season
+    pub fn singing_rate(&self, progress: f32, song_season: f32) -> f32 {
+        self.song.rate(progress) * WINTER_SONG.lerp(1.0, song_season)
+    }
+
+    pub fn size_class(&self) -> SizeClass {
+        SizeClass::from_radius(self.radius)
+    }
+
+    /// Clips of one kind; empty if the species never makes that sound.
+    pub fn calls(&self, kind: CallKind) -> impl Iterator<Item = &Handle<AudioSource>> {
+        self.calls
+            .iter()
+            .filter(move |call| call.kind == kind)
+            .map(|call| &call.clip)
+    }
+}
+
+/// All bird species, loaded from `assets/birds.species.ron`.
+///
+/// Once loading finishes the catalog is also inserted as a resource, so systems can use
+/// `Res<SpeciesCatalog>` instead of going through `Assets<SpeciesCatalog>`.
+#[derive(Asset, Resource, TypePath, Clone, Debug)]
+pub struct SpeciesCatalog {
+    species: Vec<SpeciesData>,
+}
+
+impl SpeciesCatalog {
+    pub fn iter(&self) -> impl Iterator<Item = (BirdSpecies, &SpeciesData)> + '_ {
+        self.species
+            .iter()
+            .enumerate()
+            .map(|(idx, data)| (BirdSpecies(idx), data))
+    }
+}
+
+impl Index<BirdSpecies> for SpeciesCatalog {
+    type Output = SpeciesData;
+
+    fn index(&self, id: BirdSpecies) -> &SpeciesData {
+        &self.species[id.0]
+    }
+}
+
+impl FromWorld for SpeciesCatalog {
+    fn from_world(world: &mut World) -> Self {
+        let handle = world.resource::<SpeciesAssets>().catalog.clone();
+        world
+            .resource::<Assets<SpeciesCatalog>>()
+            .get(&handle)
+            .expect("species catalog is loaded before the loading state finishes")
+            .clone()
+    }
+}
+
+// -- Loader --
+
+/// On-disk shape of one species entry. Call clips are `(kind, path)` pairs, with paths
+/// relative to `assets/`.
+#[derive(Deserialize)]
+struct SpeciesDefinition {
+    name: String,
+    /// sRGB color
+    color: (f32, f32, f32),
+    radius: f32,
+    speed: f32,
+    activity: ActivityPeriod,
+    #[serde(default)]
+    residency: Residency,
+    flight: FlightStyle,
+    /// Song curve keyframes, see [`SongCurve`]. Defaults to one based on `activity`.
+    #[serde(default)]
+    song: Option<Vec<(f32, f32)>>,
+    #[serde(default)]
+    social: bool,
+    /// Names of other species whose calls this one answers. Every species answers its own.
+    #[serde(default)]
+    answers: Vec<String>,
+    #[serde(default)]
+    predator: bool,
+    #[serde(default)]
+    mobs: bool,
+    #[serde(default)]
+    forages: Vec<FoodSource>,
+    #[serde(default)]
+    climbs: bool,
+    #[serde(default)]
+    scolds: Vec<AnimalKind>,
+    /// Silent species (see [`SongCurve::is_silent`]) need no `Vocal` calls
+    calls: Vec<(CallKind, String)>,
+}
+
+#[derive(Debug, Error)]
+pub enum SpeciesCatalogLoaderError {
+    #[error("Could not load species catalog: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse species catalog: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Species {0} sings but has no vocal calls")]
+    NoCalls(String),
+    #[error("Species {0} has an invalid song curve")]
+    InvalidSongCurve(String),
+    #[error("Species {species} answers unknown species {answers}")]
+    UnknownSpecies { species: String, answers: String },
+}
+
+#[derive(Default, TypePath)]
+struct SpeciesCatalogLoader;
+
+impl AssetLoader for SpeciesCatalogLoader {
+    type Asset = SpeciesCatalog;
+    type Settings = ();
+    type Error = SpeciesCatalogLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<SpeciesCatalog, SpeciesCatalogLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let definitions = ron::de::from_bytes::<Vec<SpeciesDefinition>>(&bytes)?;
+
+        let ids: Vec<(String, BirdSpecies)> = definitions
+            .iter()
+            .enumerate()
+            .map(|(idx, definition)| (definition.name.clone(), BirdSpecies(idx)))
+            .collect();
+        let find = |name: &str| ids.iter().find(|(n, _)| n == name).map(|(_, id)| *id);
+
+        let mut species = Vec::with_capacity(definitions.len());
+        for (idx, definition) in definitions.into_iter().enumerate() {
+            let song = match definition.song {
+                Some(keyframes) => SongCurve::new(keyframes).ok_or_else(|| {
+                    SpeciesCatalogLoaderError::InvalidSongCurve(definition.name.clone())
+                })?,
+                None => SongCurve::for_activity(definition.activity),
+            };
+            let vocal = definition
+                .calls
+                .iter()
+                .any(|(kind, _)| *kind == CallKind::Vocal);
+            if !vocal && !song.is_silent() {
+                return Err(SpeciesCatalogLoaderError::NoCalls(definition.name));
+            }
+            let mut answers = vec![BirdSpecies(idx)];
+            for name in &definition.answers {
+                let other =
+                    find(name).ok_or_else(|| SpeciesCatalogLoaderError::UnknownSpecies {
+                        species: definition.name.clone(),
+                        answers: name.clone(),
+                    })?;
+                answers.push(other);
+            }
+            let (r, g, b) = definition.color;
+            species.push(SpeciesData {
+                name: definition.name,
+                color: Color::srgb(r, g, b),
+                radius: definition.radius,
+                speed: definition.speed,
+                activity: definition.activity,
+                residency: definition.residency,
+                flight: definition.flight,
+                song,
+                social: definition.social,
+                answers,
+                predator: definition.predator,
+                mobs: definition.mobs,
+                forages: definition.forages,
+                climbs: definition.climbs,
+                scolds: definition.scolds,
+                // Call clips become dependencies of the catalog, so they finish loading with it
+                calls: definition
+                    .calls
+                    .into_iter()
+                    .map(|(kind, path)| BirdCall {
+                        kind,
+                        clip: load_context.load(path),
+                    })
+                    .collect(),
+            });
+        }
+
+        Ok(SpeciesCatalog { species })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["species.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definitions() -> Vec<SpeciesDefinition> {
+        ron::de::from_str(include_str!("../assets/birds.species.ron"))
+            .expect("birds.species.ron should parse")
+    }
+
+    #[test]
+    fn test_catalog_species_count() {
+        assert_eq!(definitions().len(), 18, "Expected 18 bird species");
+    }
+
+    #[test]
+    fn test_catalog_species_names_unique() {
+        let definitions = definitions();
+        for i in 0..definitions.len() {
+            for j in (i + 1)..definitions.len() {
+                assert_ne!(
+                    definitions[i].name, definitions[j].name,
+                    "Found duplicate species in catalog"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_catalog_species_have_valid_color() {
+        for definition in definitions() {
+            let (r, g, b) = definition.color;
+            for component in [r, g, b] {
+                assert!(
+                    (0.0..=1.0).contains(&component),
+                    "{} has an invalid color component",
+                    definition.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_catalog_species_have_radius() {
+        for definition in definitions() {
+            assert!(
+                definition.radius > 0.0 && definition.radius < 1.0,
+                "Bird radius should be between 0 and 1"
+            );
+        }
+    }
+
+    #[test]
+    fn test_catalog_species_have_speed() {
+        for definition in definitions() {
+            assert!(
+                definition.speed > 0.0 && definition.speed < 10.0,
+                "Bird speed should be reasonable"
+            );
+        }
+    }
+
+    #[test]
+    fn test_catalog_species_have_calls() {
+        for definition in definitions() {
+            let silent = definition
+                .song
+                .clone()
+                .and_then(SongCurve::new)
+                .is_some_and(|song| song.is_silent());
+            assert!(
+                silent
+                    || definition
+                        .calls
+                        .iter()
+                        .any(|(kind, _)| *kind == CallKind::Vocal),
+                "{} should have at least one vocal call",
+                definition.name
+            );
+            for (_, path) in &definition.calls {
+                assert!(
+                    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                        .join("assets")
+                        .join(path)
+                        .exists(),
+                    "Missing call clip {path}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_social_birds_exist() {
+        let social: Vec<String> = definitions()
+            .into_iter()
+            .filter(|d| d.social)
+            .map(|d| d.name)
+            .collect();
+        for name in ["Pine Siskin", "American Goldfinch", "Red-winged Blackbird"] {
+            assert!(social.iter().any(|s| s == name), "{name} should be social");
+        }
+    }
+
+    #[test]
+    fn test_jays_answer_each_other() {
+        let definitions = definitions();
+        let answers = |name: &str| {
+            definitions
+                .iter()
+                .find(|d| d.name == name)
+                .map(|d| d.answers.clone())
+                .unwrap_or_default()
+        };
+        assert!(answers("Steller's Jay").contains(&"California Scrub-Jay".to_string()));
+        assert!(answers("California Scrub-Jay").contains(&"Steller's Jay".to_string()));
+    }
+
+    #[test]
+    fn test_answered_species_exist() {
+        let definitions = definitions();
+        for definition in &definitions {
+            for name in &definition.answers {
+                assert!(
+                    definitions.iter().any(|d| &d.name == name),
+                    "{} answers unknown species {name}",
+                    definition.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_predators_and_alarm_callers_exist() {
+        let definitions = definitions();
+        let find = |name: &str| {
+            definitions
+                .iter()
+                .find(|d| d.name == name)
+                .unwrap_or_else(|| panic!("{name} should be in the catalog"))
+        };
+        assert!(find("Cooper's Hawk").predator);
+        assert!(find("Great Horned Owl").predator);
+        assert!(
+            find("Black-capped Chickadee")
+                .calls
+                .iter()
+                .any(|(kind, _)| *kind == CallKind::Alarm)
+        );
+        assert!(find("Steller's Jay").mobs);
+        assert!(
+            definitions.iter().all(|d| !(d.predator && d.mobs)),
+            "Predators don't mob"
+        );
+    }
+
+    #[test]
+    fn test_foraging_preferences() {
+        let definitions = definitions();
+        let favorite = |name: &str| {
+            definitions
+                .iter()
+                .find(|d| d.name == name)
+                .and_then(|d| d.forages.first().copied())
+        };
+        assert_eq!(favorite("White-crowned Sparrow"), Some(FoodSource::Ground));
+        assert_eq!(favorite("Mourning Dove"), Some(FoodSource::Ground));
+        assert_eq!(favorite("House Finch"), Some(FoodSource::Feeder));
+        assert_eq!(favorite("Downy Woodpecker"), Some(FoodSource::Trunk));
+        assert!(
+            definitions
+                .iter()
+                .filter(|d| d.predator)
+                .all(|d| d.forages.is_empty()),
+            "Predators don't come for seed"
+        );
+    }
+
+    #[test]
+    fn test_woodpeckers_climb_and_drum() {
+        for definition in definitions() {
+            let drums = definition
+                .calls
+                .iter()
+                .any(|(kind, _)| *kind == CallKind::Drum);
+            assert_eq!(
+                drums, definition.climbs,
+                "{} should drum exactly if it climbs",
+                definition.name
+            );
+            for (kind, path) in &definition.calls {
+                assert_eq!(
+                    path.contains("Drum"),
+                    *kind == CallKind::Drum,
+                    "{path} is listed as {kind:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_residency_follows_the_seasons() {
+        let (spring, summer, fall, winter) = (125.0, 190.0, 250.0, 20.0);
+        assert!(Residency::SummerBreeder.is_present(summer));
+        assert!(!Residency::SummerBreeder.is_present(winter));
+        assert!(Residency::WinterVisitor.is_present(winter));
+        assert!(!Residency::WinterVisitor.is_present(summer));
+        assert!(Residency::PassageMigrant.is_present(spring));
+        assert!(Residency::PassageMigrant.is_present(fall));
+        assert!(!Residency::PassageMigrant.is_present(summer));
+        assert!(!Residency::PassageMigrant.is_present(winter));
+
+        let definitions = definitions();
+        for residency in [
+            Residency::SummerBreeder,
+            Residency::WinterVisitor,
+            Residency::PassageMigrant,
+        ] {
+            assert!(
+                definitions.iter().any(|d| d.residency == residency),
+                "No {residency:?} species"
+            );
+        }
+    }
+
+    #[test]
+    fn test_owls_glide() {
+        for definition in definitions() {
+            if definition.name.ends_with("Owl") {
+                assert_eq!(
+                    definition.flight,
+                    FlightStyle::Glide,
+                    "{} should glide",
+                    definition.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_size_classes() {
+        for definition in definitions() {
+            let size = SizeClass::from_radius(definition.radius);
+            match definition.name.as_str() {
+                "Pine Siskin" | "Black-capped Chickadee" => assert_eq!(size, SizeClass::Small),
+                "Steller's Jay" | "Downy Woodpecker" => assert_eq!(size, SizeClass::Medium),
+                "Great Horned Owl" | "Mourning Dove" => assert_eq!(size, SizeClass::Large),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_nocturnal_birds_exist() {
+        let nocturnal_count = definitions()
+            .iter()
+            .filter(|d| d.activity == ActivityPeriod::Nocturnal)
+            .count();
+        assert!(
+            nocturnal_count >= 3,
+            "Should have at least 3 nocturnal species (owls)"
+        );
+    }
+
+    #[test]
+    fn test_diurnal_birds_exist() {
+        let diurnal_count = definitions()
+            .iter()
+            .filter(|d| d.activity != ActivityPeriod::Nocturnal)
+            .count();
+        assert!(
+            diurnal_count > 0,
+            "Should have at least one diurnal species"
+        );
+    }
+
+    #[test]
+    fn test_song_curves_in_catalog_are_valid() {
+        for definition in definitions() {
+            if let Some(keyframes) = definition.song {
+                assert!(
+                    SongCurve::new(keyframes).is_some(),
+                    "{} has an invalid song curve",
+                    definition.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_song_curves_rejected() {
+        assert_eq!(SongCurve::new(vec![]), None, "Empty curve");
+        assert_eq!(
+            SongCurve::new(vec![(0.5, 1.0), (0.2, 0.0)]),
+            None,
+            "Unsorted curve"
+        );
+        assert_eq!(SongCurve::new(vec![(0.5, 2.0)]), None, "Rate above 1");
+    }
+
+    #[test]
+    fn test_dawn_chorus_louder_than_midday() {
+        let song = SongCurve::for_activity(ActivityPeriod::Diurnal);
+        assert!(song.rate(0.03) > 2.0 * song.rate(0.25));
+    }
+
+    #[test]
+    fn test_crepuscular_dusk_peak() {
+        let song = SongCurve::for_activity(ActivityPeriod::Crepuscular);
+        assert!(song.rate(0.5) > song.rate(0.25));
+        assert_eq!(song.rate(0.75), 0.0, "Quiet at midnight");
+    }
+
+    #[test]
+    fn test_owls_call_at_night() {
+        let song = SongCurve::for_activity(ActivityPeriod::Nocturnal);
+        assert!(song.rate(0.75) > 0.0, "Owls call through the night");
+        assert_eq!(song.rate(0.25), 0.0, "Owls are quiet at noon");
+    }
+
+    #[test]
+    fn test_song_curve_wraps_around() {
+        for activity in [
+            ActivityPeriod::Diurnal,
+            ActivityPeriod::StrictlyDiurnal,
+            ActivityPeriod::Crepuscular,
+            ActivityPeriod::Nocturnal,
+        ] {
+            let song = SongCurve::for_activity(activity);
+            assert!(
+                (song.rate(0.9999) - song.rate(0.0)).abs() < 1e-2,
+                "{activity:?} curve should be continuous at sunrise"
+            );
+        }
+    }
+
+    #[test]
+    fn test_activity_period_is_active_logic() {
+        assert!(
+            ActivityPeriod::Nocturnal.is_active(-0.5, 0.0),
+            "Nocturnal bird should be active at night"
+        );
+        assert!(
+            ActivityPeriod::Crepuscular.is_active(0.5, 0.0),
+            "Crepuscular bird should be active during day"
+        );
+        assert!(
+            !ActivityPeriod::StrictlyDiurnal.is_active(0.05, 0.0),
+            "Strictly diurnal bird should wait for good light"
+        );
+    }
+
+    #[test]
+    fn test_moonlight_brings_owls_out_early() {
+        let dusk = 0.05;
+        assert!(ActivityPeriod::Nocturnal.is_active(dusk, 1.0));
+        assert!(!ActivityPeriod::Nocturnal.is_active(dusk, 0.0));
+        assert_eq!(
+            ActivityPeriod::Diurnal.is_active(dusk, 1.0),
+            ActivityPeriod::Diurnal.is_active(dusk, 0.0),
+            "Only owls care about the moon"
+        );
+    }
+}
@@ -0,0 +1,514 @@
+//! The ground: hills from smooth noise with a pond in one of the hollows, covered in grass,
+//! dirt, leaf litter and rock blended by slope, moisture and the shade of the trees.
+//!
+//! [`Terrain`] is a resource once the game is playing. Ask it for the height of the ground
+//! anywhere, so whatever stands on it sits right.
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::mesh::VertexAttributeValues;
+use bevy::pbr::{ExtendedMaterial, MaterialExtension};
+use bevy::prelude::*;
+use bevy::render::render_resource::AsBindGroup;
+use bevy::shader::ShaderRef;
+use rand::Rng;
+
+use crate::GameState;
+use crate::forest::Forest;
+use crate::scene::{Destination, GROUND_LEVEL, PerchPoints};
+use crate::weather::{WIND_DIRECTION, Weather};
+
+pub struct TerrainPlugin;
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<WaterMaterial>::default())
+            .add_systems(Update, stir_water.run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// The clearing in the middle, around the feeder and the bird bath, is always open and flat.
+pub const MAIN_CLEARING: f32 = 6.0;
+/// Beyond this the hills reach their full height.
+pub const HILLS_START: f32 = 10.0;
+/// Height of the tallest hills above the clearing, and how far apart they are.
+pub const HILL_HEIGHT: f32 = 1.5;
+const HILL_SCALE: f32 = 14.0;
+/// Size of wet and dry patches.
+const MOISTURE_SCALE: f32 = 9.0;
+const NOISE_OCTAVES: u32 = 3;
+
+/// Size of the pond, and how far out in the hills it lies, clear of the main clearing.
+const POND_RADIUS: std::ops::Range<f32> = 2.0..3.0;
+const POND_DISTANCE: std::ops::Range<f32> = 11.0..12.5;
+/// Spots tried for the pond; it fills the lowest.
+const POND_CANDIDATES: usize = 24;
+/// Depth of the water in the middle.
+const POND_DEPTH: f32 = 0.5;
+/// Width of the banks, where the ground slopes down from the hills to the water.
+const BANK_WIDTH: f32 = 2.0;
+/// How steeply the banks rise from the water's edge.
+const BANK_SLOPE: f32 = 0.15;
+/// How far the water stays below the lowest point of its banks.
+const FREEBOARD: f32 = 0.05;
+/// Spots to drink from around the edge of the pond.
+const POND_SLOTS: usize = 6;
+
+/// Width of the ground, a little wider than the forest so its edge stays out of sight.
+const GROUND_SIZE: f32 = 50.0;
+/// Squares along each side of the ground mesh.
+const GROUND_SUBDIVISIONS: u32 = 100;
+
+/// Steepness (rise over run) where bare rock starts to show, and where nothing else clings.
+const ROCK_STEEPNESS: std::ops::Range<f32> = 0.3..0.6;
+/// Share of [`HILL_HEIGHT`] above which dry hilltops turn to rock.
+const OUTCROPS: std::ops::Range<f32> = 0.6..1.0;
+/// Moisture where the ground turns to bare, muddy dirt.
+const MUD: std::ops::Range<f32> = 0.7..0.9;
+/// Leaves pile up thickest within the first distance of a trunk, and thin out by the second.
+/// Both grow with the tree.
+const LITTER_REACH: std::ops::Range<f32> = 0.5..2.0;
+/// How much ground colors vary from patch to patch.
+const SPECKLE: f32 = 0.12;
+const SPECKLE_SCALE: f32 = 0.7;
+
+const GRASS_COLOR: Color = Color::srgb(0.35, 0.55, 0.25);
+const DIRT_COLOR: Color = Color::srgb(0.4, 0.3, 0.2);
+const LITTER_COLOR: Color = Color::srgb(0.48, 0.33, 0.17);
+const ROCK_COLOR: Color = Color::srgb(0.5, 0.5, 0.47);
+
+/// The water shader, see `assets/shaders/water.wgsl`.
+const WATER_SHADER: &str = "shaders/water.wgsl";
+/// Ripples per unit, and how fast they run.
+const RIPPLE_SCALE: f32 = 2.5;
+const RIPPLE_SPEED: f32 = 1.5;
+
+/// Height and moisture of the ground, from smooth noise, and the pond.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct Terrain {
+    seed: u32,
+    pub pond: Pond,
+}
+
+/// Standing water in a hollow.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pond {
+    pub center: Vec2,
+    pub radius: f32,
+    /// Height of the water's surface
+    pub level: f32,
+}
+
+impl Pond {
+    /// How far `point` is from the water's edge: negative in the water.
+    pub fn shore_distance(&self, point: Vec2) -> f32 {
+        self.center.distance(point) - self.radius
+    }
+}
+
+impl Terrain {
+    /// Lays out new terrain. The pond fills the lowest of a few hollows in the hills.
+    pub fn generate(rng: &mut impl Rng) -> Self {
+        let seed = rng.random();
+        let hills = |point: Vec2| hills(seed, point);
+        let center = (0..POND_CANDIDATES)
+            .map(|_| {
+                Vec2::from_angle(rng.random_range(0.0..TAU))
+                    * rng.random_range(POND_DISTANCE.clone())
+            })
+            .min_by(|a, b| hills(*a).total_cmp(&hills(*b)))
+            .expect("there are always pond candidates");
+        let radius = rng.random_range(POND_RADIUS);
+        // Fill it only as high as the lowest point around it, so it never spills
+        let level = (0..32)
+            .flat_map(|i| {
+                let direction = Vec2::from_angle(i as f32 / 32.0 * TAU);
+                [radius, radius + BANK_WIDTH / 2.0, radius + BANK_WIDTH]
+                    .map(|distance| hills(center + direction * distance))
+            })
+            .fold(f32::MAX, f32::min)
+            - FREEBOARD;
+        Self {
+            seed,
+            pond: Pond {
+                center,
+                radius,
+                level,
+            },
+        }
+    }
+
+    /// Height of the ground at `x`, `z`. Flat in the main clearing, rising and falling into
+    /// hills further out, and sloping down into the pond.
+    pub fn height(&self, x: f32, z: f32) -> f32 {
+        let point = Vec2::new(x, z);
+        let pond = &self.pond;
+        let shore = pond.shore_distance(point);
+        let basin = if shore < 0.0 {
+            let depth = 1.0 - (pond.center.distance(point) / pond.radius).powi(2);
+            pond.level - POND_DEPTH * depth
+        } else {
+            pond.level + shore * BANK_SLOPE
+        };
+        hills(self.seed, point).lerp(basin, smoothstep(BANK_WIDTH, 0.0, shore))
+    }
+
+    /// The spot on the ground at `x`, `z`.
+    pub fn on_ground(&self, x: f32, z: f32) -> Vec3 {
+        Vec3::new(x, self.height(x, z), z)
+    }
+
+    /// Which way the ground faces at `x`, `z`.
+    pub fn normal(&self, x: f32, z: f32) -> Vec3 {
+        const EPS: f32 = 0.05;
+        Vec3::new(
+            self.height(x - EPS, z) - self.height(x + EPS, z),
+            2.0 * EPS,
+            self.height(x, z - EPS) - self.height(x, z + EPS),
+        )
+        .normalize()
+    }
+
+    /// How damp the ground is at `x`, `z`, from 0 (dry) to 1 (wet). Low ground and the banks
+    /// of the pond are wetter.
+    pub fn moisture(&self, x: f32, z: f32) -> f32 {
+        let point = Vec2::new(x, z);
+        let patches = fractal_noise(self.seed ^ 0x9e37_79b9, point / MOISTURE_SCALE);
+        let lowland = -(self.height(x, z) - GROUND_LEVEL) / HILL_HEIGHT;
+        let banks = smoothstep(BANK_WIDTH * 2.0, 0.0, self.pond.shore_distance(point));
+        (0.5 + 0.35 * patches + 0.25 * lowland + 0.6 * banks).clamp(0.0, 1.0)
+    }
+}
+
+/// Height of the rolling hills alone, without the pond.
+fn hills(seed: u32, point: Vec2) -> f32 {
+    let hills = smoothstep(MAIN_CLEARING, HILLS_START, point.length());
+    GROUND_LEVEL + HILL_HEIGHT * hills * fractal_noise(seed, point / HILL_SCALE)
+}
+
+/// How much of each kind of ground cover there is at a spot. The shares add up to 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroundCover {
+    pub grass: f32,
+    pub dirt: f32,
+    pub litter: f32,
+    pub rock: f32,
+}
+
+impl GroundCover {
+    /// Rock on steep slopes and dry hilltops, mud on wet ground, fallen leaves in the shade of
+    /// the trees, and grass everywhere else. `steepness` is rise over run, `height` is above
+    /// the clearing, and `shade` goes from 0 in the open to 1 under a tree.
+    pub fn at(steepness: f32, height: f32, moisture: f32, shade: f32) -> Self {
+        let cliff = smoothstep(ROCK_STEEPNESS.start, ROCK_STEEPNESS.end, steepness);
+        let hilltop = smoothstep(OUTCROPS.start, OUTCROPS.end, height / HILL_HEIGHT);
+        let rock = cliff.max(hilltop * (1.0 - moisture));
+        let dirt = (1.0 - rock) * smoothstep(MUD.start, MUD.end, moisture);
+        let litter = (1.0 - rock - dirt) * shade;
+        Self {
+            grass: 1.0 - rock - dirt - litter,
+            dirt,
+            litter,
+            rock,
+        }
+    }
+
+    /// The blended color of the ground.
+    pub fn color(&self) -> LinearRgba {
+        GRASS_COLOR.to_linear() * self.grass
+            + DIRT_COLOR.to_linear() * self.dirt
+            + LITTER_COLOR.to_linear() * self.litter
+            + ROCK_COLOR.to_linear() * self.rock
+    }
+}
+
+/// What covers the ground at `x`, `z`.
+pub fn ground_cover(forest: &Forest, x: f32, z: f32) -> GroundCover {
+    let terrain = &forest.terrain;
+    let normal = terrain.normal(x, z);
+    let shade = forest
+        .trees
+        .iter()
+        .map(|tree| {
+            let distance = tree.position.xz().distance(Vec2::new(x, z)) / tree.scale;
+            smoothstep(LITTER_REACH.end, LITTER_REACH.start, distance)
+        })
+        .fold(0.0, f32::max);
+    GroundCover::at(
+        normal.xz().length() / normal.y,
+        terrain.height(x, z) - GROUND_LEVEL,
+        terrain.moisture(x, z),
+        shade,
+    )
+}
+
+/// A grid raised and lowered to the terrain, colored by what covers the ground.
+fn ground_mesh(forest: &Forest) -> Mesh {
+    let terrain = &forest.terrain;
+    let mut mesh = Plane3d::default()
+        .mesh()
+        .size(GROUND_SIZE, GROUND_SIZE)
+        .subdivisions(GROUND_SUBDIVISIONS)
+        .build();
+    let mut colors = Vec::new();
+    if let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    {
+        for position in positions.iter_mut() {
+            let (x, z) = (position[0], position[2]);
+            position[1] = terrain.height(x, z);
+            let cover = ground_cover(forest, x, z);
+            let speckle =
+                1.0 + SPECKLE * value_noise(terrain.seed, Vec2::new(x, z) / SPECKLE_SCALE);
+            colors.push((cover.color() * speckle).with_alpha(1.0).to_f32_array());
+        }
+    }
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.compute_normals();
+    mesh
+}
+
+/// Spawns the ground and the pond, with spots to drink from around the water's edge.
+pub fn spawn_ground(
+    commands: &mut Commands,
+    forest: &Forest,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    water_materials: &mut Assets<WaterMaterial>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(ground_mesh(forest))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            perceptual_roughness: 0.9,
+            ..default()
+        })),
+    ));
+
+    // The water reaches a little way into the banks, which hide its edge
+    let pond = forest.terrain.pond;
+    let surface = Vec3::new(pond.center.x, pond.level, pond.center.y);
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Circle::new(pond.radius + BANK_WIDTH / 2.0)
+                    .mesh()
+                    .resolution(48),
+            ),
+        ),
+        MeshMaterial3d(water_materials.add(WaterMaterial {
+            base: StandardMaterial {
+                base_color: Color::srgba(0.2, 0.32, 0.35, 0.85),
+                perceptual_roughness: 0.08,
+                reflectance: 0.6,
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            },
+            extension: Water {
+                ripple_scale: RIPPLE_SCALE,
+                ripple_speed: RIPPLE_SPEED,
+                wind: 0.0,
+                rain: 0.0,
+                downwind: WIND_DIRECTION,
+            },
+        })),
+        Transform::from_translation(surface).with_rotation(Quat::from_rotation_x(-FRAC_PI_2)),
+    ));
+    commands.spawn((
+        Transform::from_translation(surface),
+        Destination::Water,
+        PerchPoints::on_ring(surface, pond.radius, POND_SLOTS),
+    ));
+}
+
+pub type WaterMaterial = ExtendedMaterial<StandardMaterial, Water>;
+
+/// Ripples on open water, running with the wind and ringed by raindrops.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+pub struct Water {
+    #[uniform(100)]
+    ripple_scale: f32,
+    #[uniform(100)]
+    ripple_speed: f32,
+    /// From the weather: calm to gale, 0 to 1
+    #[uniform(100)]
+    wind: f32,
+    /// From the weather: dry to downpour, 0 to 1
+    #[uniform(100)]
+    rain: f32,
+    /// Direction the waves run in, across the ground
+    #[uniform(100)]
+    downwind: Vec2,
+}
+
+impl MaterialExtension for Water {
+    fn fragment_shader() -> ShaderRef {
+        WATER_SHADER.into()
+    }
+
+    // Nothing under the water needs its shadow, and blended water has no prepass
+    fn enable_prepass() -> bool {
+        false
+    }
+
+    fn enable_shadows() -> bool {
+        false
+    }
+}
+
+/// Passes the wind and rain on to the water, whenever they change.
+fn stir_water(
+    weather: Res<Weather>,
+    ponds: Query<&MeshMaterial3d<WaterMaterial>>,
+    mut materials: ResMut<Assets<WaterMaterial>>,
+) {
+    let conditions = weather.conditions();
+    for pond in &ponds {
+        // Touching a material sends it to the GPU again
+        let current = materials.get(&pond.0).is_some_and(|material| {
+            material.extension.wind == conditions.wind && material.extension.rain == conditions.rain
+        });
+        if current {
+            continue;
+        }
+        if let Some(material) = materials.get_mut(&pond.0) {
+            material.extension.wind = conditions.wind;
+            material.extension.rain = conditions.rain;
+        }
+    }
+}
+
+pub(crate) fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// A repeatable value from -1 to 1 for each lattice point.
+fn lattice(seed: u32, x: i32, y: i32) -> f32 {
+    let mut hash =
+        seed ^ (x as u32).wrapping_mul(0x27d4_eb2d) ^ (y as u32).wrapping_mul(0x1656_67b1);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x2c1b_3c6d);
+    hash ^= hash >> 12;
+    hash = hash.wrapping_mul(0x297a_2d39);
+    hash ^= hash >> 15;
+    hash as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+/// Smoothly interpolated value noise, from -1 to 1.
+pub(crate) fn value_noise(seed: u32, point: Vec2) -> f32 {
+    let floor = point.floor();
+    let (x, y) = (floor.x as i32, floor.y as i32);
+    let t = point - floor;
+    let t = t * t * (3.0 - 2.0 * t);
+    let bottom = lattice(seed, x, y).lerp(lattice(seed, x + 1, y), t.x);
+    let top = lattice(seed, x, y + 1).lerp(lattice(seed, x + 1, y + 1), t.x);
+    bottom.lerp(top, t.y)
+}
+
+/// A few octaves of value noise, each half the size and strength of the last, from -1 to 1.
+fn fractal_noise(seed: u32, point: Vec2) -> f32 {
+    let (sum, total) = (0..NOISE_OCTAVES).fold((0.0, 0.0), |(sum, total), octave| {
+        let scale = 2.0_f32.powi(octave as i32);
+        let octave_seed = seed.wrapping_add(octave.wrapping_mul(0x85eb_ca6b));
+        (
+            sum + value_noise(octave_seed, point * scale) / scale,
+            total + 1.0 / scale,
+        )
+    });
+    sum / total
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::forest::FOREST_HALF_SIZE;
+
+    fn terrain(seed: u64) -> Terrain {
+        Terrain::generate(&mut ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    #[test]
+    fn test_pond_holds_water() {
+        for seed in 0..20 {
+            let terrain = terrain(seed);
+            let pond = terrain.pond;
+            assert!(pond.center.length() - pond.radius - BANK_WIDTH >= MAIN_CLEARING);
+            assert!(pond.center.length() + pond.radius < FOREST_HALF_SIZE);
+
+            // Deepest in the middle, and banked all the way round
+            let middle = terrain.height(pond.center.x, pond.center.y);
+            assert!((middle - (pond.level - POND_DEPTH)).abs() < 1e-4);
+            for i in 0..64 {
+                let direction = Vec2::from_angle(i as f32 / 64.0 * TAU);
+                let under = pond.center + direction * pond.radius * 0.9;
+                assert!(terrain.height(under.x, under.y) < pond.level);
+                for width in [0.05, 0.5, 1.0] {
+                    let bank = pond.center + direction * (pond.radius + BANK_WIDTH * width);
+                    assert!(terrain.height(bank.x, bank.y) > pond.level, "{seed} {bank}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_things_stand_on_the_ground() {
+        let terrain = terrain(3);
+        for (x, z) in [(0.0, 0.0), (12.0, -4.0), (-8.5, 9.25)] {
+            assert_eq!(
+                terrain.on_ground(x, z),
+                Vec3::new(x, terrain.height(x, z), z)
+            );
+        }
+        // Level in the clearing, tilted where the hills rise
+        assert_eq!(terrain.normal(1.0, 2.0), Vec3::Y);
+        let pond = terrain.pond;
+        let bank = pond.center + pond.center.normalize() * (pond.radius + 0.5);
+        assert!(terrain.normal(bank.x, bank.y).y < 0.999);
+    }
+
+    #[test]
+    fn test_ground_cover_suits_the_spot() {
+        let main = |cover: GroundCover| {
+            [
+                ("grass", cover.grass),
+                ("dirt", cover.dirt),
+                ("litter", cover.litter),
+                ("rock", cover.rock),
+            ]
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap()
+            .0
+        };
+        assert_eq!(main(GroundCover::at(0.0, 0.0, 0.5, 0.0)), "grass");
+        assert_eq!(main(GroundCover::at(0.0, 0.0, 0.95, 0.0)), "dirt");
+        assert_eq!(main(GroundCover::at(0.1, 0.3, 0.4, 1.0)), "litter");
+        assert_eq!(main(GroundCover::at(0.8, 0.0, 0.5, 0.5)), "rock");
+        assert_eq!(main(GroundCover::at(0.0, HILL_HEIGHT, 0.1, 0.0)), "rock");
+
+        for steepness in [0.0, 0.35, 0.5, 1.0] {
+            for moisture in [0.0, 0.75, 0.85, 1.0] {
+                for shade in [0.0, 0.5, 1.0] {
+                    let cover = GroundCover::at(steepness, 1.0, moisture, shade);
+                    let total = cover.grass + cover.dirt + cover.litter + cover.rock;
+                    assert!((total - 1.0).abs() < 1e-5);
+                    assert!(cover.grass >= -1e-6);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_noise_is_smooth_and_bounded() {
+        for i in 0..1000 {
+            let point = Vec2::new(i as f32 * 0.37, i as f32 * -0.21);
+            let value = fractal_noise(3, point);
+            assert!((-1.0..=1.0).contains(&value));
+            let nearby = fractal_noise(3, point + Vec2::splat(0.01));
+            assert!((value - nearby).abs() < 0.05);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Grown tree models. Each kind of tree is grown a few times from its L-system, and every
+//! tree in the forest uses one of these models, turned and scaled. Close up a tree is drawn in
+//! full, with its limbs bending in the wind; far away it is a trunk under a simple crown.
+
+mod lsystem;
+mod model;
+
+use bevy::camera::visibility::VisibilityRange;
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::GameState;
+use crate::forest::{ForestTree, TreeKind};
+use crate::weather::{GUST_SPEED, GUST_WAVE, WIND_DIRECTION, Weather};
+pub use model::TreeModel;
+
+pub struct TreePlugin;
+
+impl Plugin for TreePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, sway_trees.run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// Models grown for each kind of tree.
+const VARIANTS: usize = 3;
+/// Distance from the camera where the full model fades into the simple one.
+const DETAIL_DISTANCE: std::ops::Range<f32> = 40.0..43.0;
+/// Beyond any distance the camera can get from a tree.
+const OUT_OF_SIGHT: f32 = 1000.0;
+
+/// How far limbs bend in still air and in a full gale, in radians.
+const CALM_SWAY: f32 = 0.01;
+const WIND_SWAY: f32 = 0.12;
+/// The trunk bends this much less than its limbs.
+const TRUNK_FLEX: f32 = 0.35;
+/// Share of the bend that stays while the wind blows, between gusts.
+const STEADY_LEAN: f32 = 0.5;
+/// Quick shaking of the limbs in strong wind.
+const FLUTTER: f32 = 0.02;
+const FLUTTER_SPEED: f32 = 7.0;
+
+/// A few models of each kind of tree, grown from the forest's seed.
+pub struct TreeModels {
+    conifers: Vec<TreeModel>,
+    oaks: Vec<TreeModel>,
+    aspens: Vec<TreeModel>,
+}
+
+impl TreeModels {
+    pub fn grow(seed: u64, meshes: &mut Assets<Mesh>) -> Self {
+        let mut grow = |kind: TreeKind| {
+            let growth = lsystem::Growth::of(kind);
+            (0..VARIANTS)
+                .map(|variant| {
+                    let mut rng = ChaCha8Rng::seed_from_u64(
+                        seed ^ ((kind as u64) << 32 | variant as u64).wrapping_mul(0x9e37_79b9),
+                    );
+                    let skeleton = growth.grow(&mut rng);
+                    TreeModel::build(kind, &skeleton, &mut rng, meshes)
+                })
+                .collect()
+        };
+        Self {
+            conifers: grow(TreeKind::Conifer),
+            oaks: grow(TreeKind::Oak),
+            aspens: grow(TreeKind::Aspen),
+        }
+    }
+
+    pub fn get(&self, tree: &ForestTree) -> &TreeModel {
+        let models = match tree.kind {
+            TreeKind::Conifer => &self.conifers,
+            TreeKind::Oak => &self.oaks,
+            TreeKind::Aspen => &self.aspens,
+        };
+        &models[tree.variant % models.len()]
+    }
+}
+
+/// Where a tree stands, how it's turned and how big it is.
+pub fn tree_transform(tree: &ForestTree) -> Transform {
+    Transform::from_translation(tree.position)
+        .with_rotation(Quat::from_rotation_y(tree.yaw))
+        .with_scale(Vec3::splat(tree.scale))
+}
+
+/// Part of a tree that bends in the wind, about its own origin.
+#[derive(Component)]
+struct Sway {
+    /// Axis it bends about, in its parent's space, so it leans downwind
+    axis: Vec3,
+    flex: f32,
+    phase: f32,
+}
+
+/// Spawns what can be seen of a tree: the full model bending in the wind close up, and the
+/// simple one further away. Returns the limbs, in the order of [`TreeModel::limbs`], each with
+/// its origin at the pivot it bends about.
+pub fn spawn_tree(
+    commands: &mut Commands,
+    tree: &ForestTree,
+    model: &TreeModel,
+    material: &Handle<StandardMaterial>,
+) -> Vec<Entity> {
+    let transform = tree_transform(tree);
+    let downwind = Vec3::new(WIND_DIRECTION.x, 0.0, WIND_DIRECTION.y);
+    let axis = transform.rotation.inverse() * Vec3::Y.cross(downwind).normalize();
+    // Gusts sweep through the forest with the wind
+    let phase = -tree.position.xz().dot(WIND_DIRECTION) * GUST_WAVE;
+    let near = VisibilityRange {
+        start_margin: 0.0..0.0,
+        end_margin: DETAIL_DISTANCE,
+        use_aabb: false,
+    };
+    let far = VisibilityRange {
+        start_margin: DETAIL_DISTANCE,
+        end_margin: OUT_OF_SIGHT..OUT_OF_SIGHT,
+        use_aabb: false,
+    };
+
+    let mut limbs = Vec::with_capacity(model.limbs.len());
+    commands
+        .spawn((transform, Visibility::default()))
+        .with_children(|root| {
+            root.spawn((
+                Mesh3d(model.trunk.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::IDENTITY,
+                near.clone(),
+                Sway {
+                    axis,
+                    flex: TRUNK_FLEX,
+                    phase,
+                },
+            ))
+            .with_children(|trunk| {
+                for (index, limb) in model.limbs.iter().enumerate() {
+                    let spawned = trunk.spawn((
+                        Mesh3d(limb.mesh.clone()),
+                        MeshMaterial3d(material.clone()),
+                        Transform::from_translation(limb.pivot),
+                        near.clone(),
+                        Sway {
+                            axis,
+                            flex: 1.0,
+                            phase: phase + index as f32 * 1.7,
+                        },
+                    ));
+                    limbs.push(spawned.id());
+                }
+            });
+            root.spawn((
+                Mesh3d(model.far.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::IDENTITY,
+                far,
+            ));
+        });
+    limbs
+}
+
+/// How far a part of a tree bends at time `t`, in radians.
+fn bend(flex: f32, phase: f32, wind: f32, t: f32) -> f32 {
+    let gust = (t * GUST_SPEED + phase).sin() * 0.5 + 0.5;
+    let lean = (CALM_SWAY + WIND_SWAY * wind) * STEADY_LEAN.lerp(1.0, gust);
+    let flutter = FLUTTER * wind * wind * (t * FLUTTER_SPEED + phase * 3.0).sin();
+    flex * (lean + flutter)
+}
+
+fn sway_trees(time: Res<Time>, weather: Res<Weather>, mut parts: Query<(&Sway, &mut Transform)>) {
+    let wind = weather.conditions().wind;
+    let t = time.elapsed_secs();
+    for (sway, mut transform) in &mut parts {
+        transform.rotation = Quat::from_axis_angle(sway.axis, bend(sway.flex, sway.phase, wind, t));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trees_bend_more_in_wind() {
+        let most = |wind| {
+            (0..200)
+                .map(|i| bend(1.0, 0.0, wind, i as f32 * 0.05))
+                .fold(0.0, f32::max)
+        };
+        assert!(most(0.0) < 0.02, "Barely stirring in still air");
+        assert!(most(1.0) > most(0.3) * 2.0);
+        // Always bent downwind, never whipping back past upright
+        assert!((0..200).all(|i| bend(1.0, 0.0, 1.0, i as f32 * 0.05) > 0.0));
+    }
+
+    #[test]
+    fn test_trees_share_a_few_models() {
+        let mut meshes = Assets::default();
+        let models = TreeModels::grow(7, &mut meshes);
+        let tree = |kind, variant| ForestTree {
+            position: Vec3::ZERO,
+            kind,
+            scale: 1.0,
+            variant,
+            yaw: 0.0,
+        };
+        let a = models.get(&tree(TreeKind::Oak, 1));
+        let b = models.get(&tree(TreeKind::Oak, 1 + VARIANTS));
+        assert_eq!(a.trunk, b.trunk, "Variants wrap around");
+        assert_ne!(a.trunk, models.get(&tree(TreeKind::Oak, 2)).trunk);
+        assert_ne!(a.trunk, models.get(&tree(TreeKind::Aspen, 1)).trunk);
+    }
+}
@@ -0,0 +1,322 @@
+//! Grows tree skeletons with an L-system: a few rewriting rules expanded into a string of
+//! turtle commands, which a turtle then walks to lay down branches and leaves.
+//!
+//! | Symbol | Turtle command |
+//! |--------|----------------|
+//! | `F` | Grow a branch segment forward |
+//! | `[` `]` | Start a side branch, and go back to where it started |
+//! | `&` `^` | Pitch down and up |
+//! | `+` `-` | Turn left and right |
+//! | `/` | Roll around the branch |
+//! | anything else | A leaf cluster, so unfinished buds leaf out |
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::forest::TreeKind;
+
+/// Rewriting rules, and the turtle settings that turn the result into a tree.
+pub(super) struct Growth {
+    axiom: &'static str,
+    rules: &'static [(char, &'static str)],
+    iterations: usize,
+    /// Length of a trunk segment
+    length: f32,
+    /// Side branches are this much shorter than the branch they grow from
+    length_decay: f32,
+    /// Trunk radius at the ground
+    radius: f32,
+    /// Side branches are this much thinner than the branch they grow from
+    radius_decay: f32,
+    /// Each segment is this much thinner at its tip
+    taper: f32,
+    /// Pitch and turn angle
+    angle: f32,
+    /// Roll angle
+    roll: f32,
+    /// How far lengths and angles vary, as a fraction
+    jitter: f32,
+    /// Pull on branches each segment: down for drooping boughs, up for branches reaching for
+    /// light
+    tropism: Vec3,
+    /// Radius of a leaf cluster
+    pub leaf_size: f32,
+}
+
+impl Growth {
+    pub(super) fn of(kind: TreeKind) -> Self {
+        match kind {
+            // A straight leader with whorls of boughs. Boughs keep lengthening every year, so
+            // the oldest at the bottom are the longest and the tree grows into a cone
+            TreeKind::Conifer => Self {
+                axiom: "FFA",
+                rules: &[
+                    ('A', "F[&&&B]////[&&&B]////[&&&B]////[&&&B]//A"),
+                    ('B', "FLB"),
+                ],
+                iterations: 5,
+                length: 0.55,
+                length_decay: 0.55,
+                radius: 0.18,
+                radius_decay: 0.35,
+                taper: 0.9,
+                angle: 28.0_f32.to_radians(),
+                roll: 25.0_f32.to_radians(),
+                jitter: 0.15,
+                tropism: Vec3::new(0.0, -0.12, 0.0),
+                leaf_size: 0.4,
+            },
+            // A short trunk that splits again and again into a broad, rounded crown
+            TreeKind::Oak => Self {
+                axiom: "FFFA",
+                rules: &[('A', "[&FLA]/////[&FLA]///////[&FLA]")],
+                iterations: 3,
+                length: 0.7,
+                length_decay: 0.75,
+                radius: 0.22,
+                radius_decay: 0.62,
+                taper: 0.85,
+                angle: 34.0_f32.to_radians(),
+                roll: 25.0_f32.to_radians(),
+                jitter: 0.2,
+                tropism: Vec3::new(0.0, 0.06, 0.0),
+                leaf_size: 0.55,
+            },
+            // A tall, slim trunk with short side shoots all the way up
+            TreeKind::Aspen => Self {
+                axiom: "FFFA",
+                rules: &[('A', "F[&&FL[+FL][-FL]]//////[&&FL[+FL][-FL]]//////A")],
+                iterations: 4,
+                length: 0.65,
+                length_decay: 0.5,
+                radius: 0.12,
+                radius_decay: 0.5,
+                taper: 0.92,
+                angle: 26.0_f32.to_radians(),
+                roll: 25.0_f32.to_radians(),
+                jitter: 0.15,
+                tropism: Vec3::new(0.0, 0.08, 0.0),
+                leaf_size: 0.35,
+            },
+        }
+    }
+
+    /// Applies the rules to the axiom `iterations` times.
+    fn expand(&self) -> String {
+        (0..self.iterations).fold(self.axiom.to_string(), |word, _| {
+            word.chars()
+                .map(|symbol| {
+                    self.rules
+                        .iter()
+                        .find(|(from, _)| *from == symbol)
+                        .map_or_else(|| symbol.to_string(), |(_, to)| to.to_string())
+                })
+                .collect()
+        })
+    }
+
+    /// Grows one tree, varied by `rng`.
+    pub(super) fn grow(&self, rng: &mut impl Rng) -> Skeleton {
+        let mut skeleton = Skeleton::default();
+        let mut turtle = Turtle {
+            position: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            length: self.length,
+            radius: self.radius,
+            depth: 0,
+            limb: None,
+        };
+        let mut stack = Vec::new();
+        let mut vary = |value: f32| value * rng.random_range(1.0 - self.jitter..1.0 + self.jitter);
+
+        for symbol in self.expand().chars() {
+            match symbol {
+                'F' => {
+                    // Bend the heading toward the tropism, more for thinner branches
+                    let heading = turtle.rotation * Vec3::Y;
+                    let pull = self.tropism * turtle.depth as f32;
+                    let bent = (heading + pull).normalize_or(heading);
+                    turtle.rotation = Quat::from_rotation_arc(heading, bent) * turtle.rotation;
+
+                    let start = turtle.position;
+                    let end = start + bent * vary(turtle.length);
+                    skeleton.segments.push(Segment {
+                        start,
+                        end,
+                        radius_start: turtle.radius,
+                        radius_end: turtle.radius * self.taper,
+                        depth: turtle.depth,
+                        limb: turtle.limb,
+                    });
+                    turtle.position = end;
+                    turtle.radius *= self.taper;
+                }
+                '[' => {
+                    stack.push(turtle);
+                    if turtle.depth == 0 {
+                        skeleton.limbs.push(turtle.position);
+                        turtle.limb = Some(skeleton.limbs.len() - 1);
+                        skeleton.fork_height.get_or_insert(turtle.position.y);
+                    }
+                    turtle.depth += 1;
+                    turtle.length *= self.length_decay;
+                    turtle.radius *= self.radius_decay;
+                }
+                ']' => {
+                    if let Some(saved) = stack.pop() {
+                        turtle = saved;
+                    }
+                }
+                '&' => turtle.rotation *= Quat::from_rotation_x(vary(self.angle)),
+                '^' => turtle.rotation *= Quat::from_rotation_x(-vary(self.angle)),
+                '+' => turtle.rotation *= Quat::from_rotation_z(vary(self.angle)),
+                '-' => turtle.rotation *= Quat::from_rotation_z(-vary(self.angle)),
+                '/' => turtle.rotation *= Quat::from_rotation_y(vary(self.roll)),
+                _ => skeleton.leaves.push(Leaf {
+                    position: turtle.position,
+                    size: vary(self.leaf_size),
+                    limb: turtle.limb,
+                }),
+            }
+        }
+        skeleton
+    }
+}
+
+/// The turtle's state. Branching saves it on a stack.
+#[derive(Clone, Copy)]
+struct Turtle {
+    position: Vec3,
+    /// Turns the turtle's local frame into the tree's: it heads along local Y
+    rotation: Quat,
+    length: f32,
+    radius: f32,
+    /// How many branchings from the trunk
+    depth: u32,
+    limb: Option<usize>,
+}
+
+/// One tapered piece of trunk or branch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Segment {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub radius_start: f32,
+    pub radius_end: f32,
+    /// 0 for the trunk
+    pub depth: u32,
+    /// The limb this belongs to, or `None` for the trunk
+    pub limb: Option<usize>,
+}
+
+impl Segment {
+    pub(super) fn direction(&self) -> Vec3 {
+        (self.end - self.start).normalize_or(Vec3::Y)
+    }
+}
+
+/// A cluster of leaves or needles.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Leaf {
+    pub position: Vec3,
+    pub size: f32,
+    pub limb: Option<usize>,
+}
+
+/// A grown tree, with its base at the origin. Each branch leaving the trunk starts a limb,
+/// which sways on its own.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct Skeleton {
+    pub segments: Vec<Segment>,
+    pub leaves: Vec<Leaf>,
+    /// Where each limb leaves the trunk
+    pub limbs: Vec<Vec3>,
+    /// Height of the lowest limb
+    pub fork_height: Option<f32>,
+}
+
+impl Skeleton {
+    /// Height of the highest leaf or branch.
+    pub(super) fn height(&self) -> f32 {
+        self.segments
+            .iter()
+            .map(|segment| segment.end.y)
+            .chain(self.leaves.iter().map(|leaf| leaf.position.y + leaf.size))
+            .fold(0.0, f32::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    const KINDS: [TreeKind; 3] = [TreeKind::Conifer, TreeKind::Oak, TreeKind::Aspen];
+
+    #[test]
+    fn test_rules_expand() {
+        let growth = Growth {
+            iterations: 2,
+            ..Growth::of(TreeKind::Conifer)
+        };
+        let word = growth.expand();
+        assert_eq!(word.matches('[').count(), 8, "Two whorls of four boughs");
+        assert_eq!(word.matches('[').count(), word.matches(']').count());
+    }
+
+    #[test]
+    fn test_same_seed_same_tree() {
+        for kind in KINDS {
+            let growth = Growth::of(kind);
+            let tree = growth.grow(&mut ChaCha8Rng::seed_from_u64(1));
+            assert_eq!(tree, growth.grow(&mut ChaCha8Rng::seed_from_u64(1)));
+            assert_ne!(tree, growth.grow(&mut ChaCha8Rng::seed_from_u64(2)));
+        }
+    }
+
+    #[test]
+    fn test_trees_have_their_own_shapes() {
+        let grow = |kind| Growth::of(kind).grow(&mut ChaCha8Rng::seed_from_u64(5));
+        let (conifer, oak, aspen) = (
+            grow(TreeKind::Conifer),
+            grow(TreeKind::Oak),
+            grow(TreeKind::Aspen),
+        );
+        for tree in [&conifer, &oak, &aspen] {
+            assert!(
+                tree.height() > 3.0 && tree.height() < 6.5,
+                "{}",
+                tree.height()
+            );
+            assert!(!tree.limbs.is_empty() && !tree.leaves.is_empty());
+        }
+
+        let spread = |tree: &Skeleton| {
+            tree.leaves
+                .iter()
+                .map(|leaf| leaf.position.xz().length())
+                .fold(0.0, f32::max)
+        };
+        // Oaks spread wide, aspens stay slim
+        assert!(spread(&oak) / oak.height() > spread(&aspen) / aspen.height());
+        // Conifers branch low, oaks fork higher up
+        assert!(conifer.fork_height < oak.fork_height);
+    }
+
+    #[test]
+    fn test_branches_connect() {
+        let tree = Growth::of(TreeKind::Oak).grow(&mut ChaCha8Rng::seed_from_u64(9));
+        for segment in &tree.segments {
+            assert!(segment.radius_end < segment.radius_start);
+            if segment.depth == 0 {
+                assert_eq!(segment.limb, None);
+            } else {
+                // Every branch grows on from another, and none hang straight down
+                assert!(tree.segments.iter().any(|other| other.end == segment.start));
+                assert!(segment.direction().y > -0.5, "{segment:?}");
+            }
+        }
+    }
+}
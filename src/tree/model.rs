@@ -0,0 +1,313 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::PrimitiveTopology;
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::lsystem::{Growth, Segment, Skeleton};
+use crate::forest::TreeKind;
+
+/// Sides around each branch, close up and from afar.
+const BARK_SIDES: u32 = 6;
+const FAR_BARK_SIDES: u32 = 4;
+/// Subdivisions of each leaf cluster.
+const LEAF_DETAIL: u32 = 1;
+/// Places to perch out along the branches, spread around the tree.
+const PERCH_SLOTS: usize = 6;
+/// Branches steeper than this are no good to perch on.
+const PERCH_SLOPE: f32 = 0.75;
+/// How far above a branch a perched bird sits.
+const PERCH_CLEARANCE: f32 = 0.12;
+/// The crown birds steer around never gets smaller than this.
+const MIN_CROWN_RADIUS: f32 = 0.3;
+
+/// Meshes and measurements for one grown tree, in the tree's own space with the base of its
+/// trunk at the origin.
+pub struct TreeModel {
+    /// Trunk, with any leaves growing straight from it
+    pub trunk: Handle<Mesh>,
+    pub limbs: Vec<LimbModel>,
+    /// Trunk and a simple crown in one mesh, for trees far from the camera
+    pub far: Handle<Mesh>,
+    pub trunk_radius: f32,
+    /// Height of the bare trunk, below the lowest limb
+    pub trunk_height: f32,
+    /// The dense middle of the crown, clear of every perch
+    pub crown_center: Vec3,
+    pub crown_radius: f32,
+    /// Where birds can sit out on the branches
+    pub perches: Vec<BranchPerch>,
+}
+
+/// A spot out on a branch where a bird can sit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BranchPerch {
+    /// Index into [`TreeModel::limbs`] of the limb it's on, which it sways with
+    pub limb: usize,
+    pub position: Vec3,
+}
+
+/// One branch leaving the trunk, with everything growing on it.
+pub struct LimbModel {
+    /// Where it leaves the trunk. The mesh is built around this point, so it bends from there
+    pub pivot: Vec3,
+    pub mesh: Handle<Mesh>,
+}
+
+impl TreeModel {
+    pub(super) fn build(
+        kind: TreeKind,
+        skeleton: &Skeleton,
+        rng: &mut impl Rng,
+        meshes: &mut Assets<Mesh>,
+    ) -> Self {
+        let (bark, foliage) = colors(kind);
+        let mut leaf_color = || (foliage * rng.random_range(0.8..1.15)).with_alpha(1.0);
+        let leaf_colors: Vec<LinearRgba> = skeleton.leaves.iter().map(|_| leaf_color()).collect();
+
+        // Close up: the trunk, and each limb built around the point it bends from
+        let near = |limb: Option<usize>, pivot: Vec3| {
+            let mut parts = MeshParts::default();
+            for segment in skeleton.segments.iter().filter(|s| s.limb == limb) {
+                parts.add_branch(segment, BARK_SIDES, pivot, bark);
+            }
+            for (leaf, color) in skeleton.leaves.iter().zip(&leaf_colors) {
+                if leaf.limb == limb {
+                    let shape = leaf_shape(kind) * leaf.size;
+                    parts.add(
+                        leaf_mesh(),
+                        Transform::from_translation(leaf.position - pivot).with_scale(shape),
+                        *color,
+                    );
+                }
+            }
+            parts.build()
+        };
+        let trunk = meshes.add(near(None, Vec3::ZERO));
+        let limbs = skeleton
+            .limbs
+            .iter()
+            .enumerate()
+            .map(|(index, pivot)| LimbModel {
+                pivot: *pivot,
+                mesh: meshes.add(near(Some(index), *pivot)),
+            })
+            .collect();
+
+        // From afar: just the trunk inside one shape the size of the crown
+        let (crown_min, crown_max) = skeleton.leaves.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), leaf| {
+                (
+                    min.min(leaf.position - leaf.size),
+                    max.max(leaf.position + leaf.size),
+                )
+            },
+        );
+        let mut far = MeshParts::default();
+        for segment in skeleton.segments.iter().filter(|s| s.depth == 0) {
+            far.add_branch(segment, FAR_BARK_SIDES, Vec3::ZERO, bark);
+        }
+        if crown_min.x <= crown_max.x {
+            let center = (crown_min + crown_max) / 2.0;
+            let half = (crown_max - crown_min) / 2.0;
+            let crown = match kind {
+                TreeKind::Conifer => Cone::new(half.x.max(half.z), half.y * 2.0)
+                    .mesh()
+                    .resolution(8)
+                    .build(),
+                TreeKind::Oak | TreeKind::Aspen => leaf_mesh().scaled_by(half),
+            };
+            far.add(crown, Transform::from_translation(center), foliage);
+        }
+
+        let perches = perches(&skeleton.segments);
+        let crown_center = skeleton
+            .leaves
+            .iter()
+            .map(|leaf| leaf.position)
+            .sum::<Vec3>()
+            / skeleton.leaves.len().max(1) as f32;
+        let crown_radius = perches
+            .iter()
+            .map(|perch| perch.position.distance(crown_center) - PERCH_CLEARANCE)
+            .fold(f32::MAX, f32::min)
+            .clamp(MIN_CROWN_RADIUS, Growth::of(kind).leaf_size * 3.0);
+
+        Self {
+            trunk,
+            limbs,
+            far: meshes.add(far.build()),
+            trunk_radius: skeleton
+                .segments
+                .first()
+                .map_or(MIN_CROWN_RADIUS, |segment| segment.radius_start),
+            trunk_height: skeleton.fork_height.unwrap_or(skeleton.height() / 2.0),
+            crown_center,
+            crown_radius,
+            perches,
+        }
+    }
+}
+
+/// Bark and leaf colors for each kind of tree.
+fn colors(kind: TreeKind) -> (LinearRgba, LinearRgba) {
+    let (bark, leaves) = match kind {
+        TreeKind::Conifer => (Color::srgb(0.36, 0.24, 0.15), Color::srgb(0.1, 0.3, 0.17)),
+        TreeKind::Oak => (Color::srgb(0.42, 0.32, 0.22), Color::srgb(0.2, 0.45, 0.14)),
+        TreeKind::Aspen => (Color::srgb(0.82, 0.8, 0.72), Color::srgb(0.45, 0.62, 0.2)),
+    };
+    (bark.into(), leaves.into())
+}
+
+/// Flat pads of needles, round clumps of oak leaves, and taller aspen sprays.
+fn leaf_shape(kind: TreeKind) -> Vec3 {
+    match kind {
+        TreeKind::Conifer => Vec3::new(1.3, 0.45, 1.3),
+        TreeKind::Oak => Vec3::ONE,
+        TreeKind::Aspen => Vec3::new(0.9, 1.2, 0.9),
+    }
+}
+
+/// A unit ball of leaves.
+fn leaf_mesh() -> Mesh {
+    Sphere::new(1.0)
+        .mesh()
+        .ico(LEAF_DETAIL)
+        .expect("low subdivision counts are always valid")
+}
+
+/// Spots out along level branches, the one furthest from the trunk in each direction around
+/// the tree.
+fn perches(segments: &[Segment]) -> Vec<BranchPerch> {
+    let mut best: [Option<(BranchPerch, f32)>; PERCH_SLOTS] = [None; PERCH_SLOTS];
+    let level = segments
+        .iter()
+        .filter(|segment| segment.depth > 0 && segment.direction().y.abs() < PERCH_SLOPE);
+    for segment in level {
+        let Some(limb) = segment.limb else {
+            continue;
+        };
+        let spot = segment.end + Vec3::Y * (segment.radius_end + PERCH_CLEARANCE);
+        let reach = spot.xz().length();
+        let azimuth = spot.z.atan2(spot.x).rem_euclid(std::f32::consts::TAU);
+        let sector =
+            ((azimuth / std::f32::consts::TAU * PERCH_SLOTS as f32) as usize).min(PERCH_SLOTS - 1);
+        if best[sector].is_none_or(|(_, best_reach)| reach > best_reach) {
+            best[sector] = Some((
+                BranchPerch {
+                    limb,
+                    position: spot,
+                },
+                reach,
+            ));
+        }
+    }
+    best.into_iter().flatten().map(|(perch, _)| perch).collect()
+}
+
+/// Merges colored pieces into one mesh, so a whole limb draws at once.
+#[derive(Default)]
+struct MeshParts(Option<Mesh>);
+
+impl MeshParts {
+    fn add(&mut self, part: Mesh, transform: Transform, color: LinearRgba) {
+        let vertices = part.count_vertices();
+        let part = part
+            .transformed_by(transform)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, vec![color.to_f32_array(); vertices]);
+        match &mut self.0 {
+            Some(mesh) => mesh
+                .merge(&part)
+                .expect("tree parts are all built with the same attributes"),
+            None => self.0 = Some(part),
+        }
+    }
+
+    /// A tapered piece of branch, relative to `pivot`.
+    fn add_branch(&mut self, segment: &Segment, sides: u32, pivot: Vec3, color: LinearRgba) {
+        let frustum = ConicalFrustum {
+            radius_top: segment.radius_end,
+            radius_bottom: segment.radius_start,
+            height: segment.start.distance(segment.end),
+        };
+        let transform = Transform::from_translation((segment.start + segment.end) / 2.0 - pivot)
+            .with_rotation(Quat::from_rotation_arc(Vec3::Y, segment.direction()));
+        self.add(frustum.mesh().resolution(sides).build(), transform, color);
+    }
+
+    fn build(self) -> Mesh {
+        self.0.unwrap_or_else(|| {
+            Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn model(kind: TreeKind, seed: u64) -> (Skeleton, TreeModel) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let skeleton = Growth::of(kind).grow(&mut rng);
+        let model = TreeModel::build(kind, &skeleton, &mut rng, &mut Assets::default());
+        (skeleton, model)
+    }
+
+    #[test]
+    fn test_birds_perch_out_on_the_branches() {
+        for kind in [TreeKind::Conifer, TreeKind::Oak, TreeKind::Aspen] {
+            let (skeleton, model) = model(kind, 3);
+            assert!(model.perches.len() >= 3, "{kind:?}: {:?}", model.perches);
+            for perch in &model.perches {
+                // Clear of the crown birds steer around, and out away from the trunk
+                let at = perch.position;
+                assert!(at.distance(model.crown_center) > model.crown_radius);
+                assert!(at.xz().length() > model.trunk_radius, "{kind:?} {at}");
+                // On the limb it sways with
+                assert!(
+                    skeleton
+                        .segments
+                        .iter()
+                        .filter(|segment| segment.limb == Some(perch.limb))
+                        .any(|segment| segment.end.distance(at) < 0.5)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_limbs_bend_from_the_trunk() {
+        let (skeleton, model) = model(TreeKind::Oak, 8);
+        assert_eq!(model.limbs.len(), skeleton.limbs.len());
+        for limb in &model.limbs {
+            assert!(limb.pivot.xz().length() < 0.01, "Limbs start on the trunk");
+            assert!(limb.pivot.y >= model.trunk_height);
+        }
+    }
+
+    #[test]
+    fn test_far_model_is_simpler() {
+        let mut meshes = Assets::default();
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+        let skeleton = Growth::of(TreeKind::Oak).grow(&mut rng);
+        let model = TreeModel::build(TreeKind::Oak, &skeleton, &mut rng, &mut meshes);
+        let vertices = |handle: &Handle<Mesh>| meshes.get(handle).unwrap().count_vertices();
+        let near = vertices(&model.trunk)
+            + model
+                .limbs
+                .iter()
+                .map(|limb| vertices(&limb.mesh))
+                .sum::<usize>();
+        assert!(
+            vertices(&model.far) * 5 < near,
+            "{} {near}",
+            vertices(&model.far)
+        );
+    }
+}
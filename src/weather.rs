@@ -0,0 +1,495 @@
+use std::time::Duration;
+
+use bevy::light::NotShadowCaster;
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::GameState;
+use crate::audio::{SILENT, decibels};
+use crate::loading::AudioAssets;
+use crate::sky::Clouds;
+
+/// Weather that drifts from one state to the next over time: clouds, rain, wind, fog and
+/// thunderstorms, with falling rain, lightning and the sound of rain to match. The wind is
+/// heard in the ambient soundscape, see [`crate::audio`].
+///
+/// The weather itself is a seeded [`Weather`] resource, so it can be replayed and tested
+/// without a window. Insert `Weather::new(seed)` before adding the plugin for a fixed
+/// sequence.
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Weather>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (setup_rain, start_weather_sounds),
+            )
+            .add_systems(
+                Update,
+                (
+                    advance_weather,
+                    (apply_weather, fall_rain, mix_weather_sounds),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// Rain at least this heavy sends birds for cover and keeps new ones away.
+pub const HEAVY_RAIN: f32 = 0.6;
+
+/// Seconds a new weather takes to blend in.
+const TRANSITION_TIME: f32 = 20.0;
+/// How long each weather lasts, in seconds.
+const WEATHER_DURATION: std::ops::Range<f32> = 60.0..240.0;
+/// Seconds after heavy rain during which birds sing more, as the sun comes back.
+const AFTER_RAIN: f32 = 90.0;
+/// Chance of a lightning strike per second at the height of a thunderstorm.
+const LIGHTNING_RATE: f32 = 0.08;
+/// How quickly a lightning flash fades, per second.
+const FLASH_DECAY: f32 = 5.0;
+
+/// Direction the wind blows over the clearing.
+pub const WIND_DIRECTION: Vec2 = Vec2::new(0.92, 0.38);
+/// Gusts per second, roughly. Gusts sweep downwind through the trees and grass alike.
+pub const GUST_SPEED: f32 = 1.3;
+/// How far along the wind a gust travels before the next one, in radians of phase per unit.
+pub const GUST_WAVE: f32 = 0.25;
+/// Cloud drift speed in still air and at full wind, in units/second.
+const CLOUD_SPEED: std::ops::Range<f32> = 0.3..3.0;
+
+/// Rain falls through a box this wide around the clearing, from this high.
+const RAIN_AREA: f32 = 28.0;
+const RAIN_HEIGHT: f32 = 16.0;
+const RAIN_DROPS: usize = 600;
+/// Fall speed of a raindrop, and how far the strongest wind blows it sideways.
+const RAIN_SPEED: f32 = 12.0;
+const RAIN_DRIFT: f32 = 5.0;
+
+/// Fog distances in clear air and in thick fog.
+const CLEAR_FOG: (f32, f32) = (28.0, 100.0);
+const THICK_FOG: (f32, f32) = (2.0, 26.0);
+
+/// Loudest the rain gets, as an amplitude.
+const RAIN_VOLUME: f32 = 0.6;
+/// How long each volume change takes.
+const VOLUME_TWEEN: Duration = Duration::from_millis(500);
+
+/// Kinds of weather, each with its own [`WeatherConditions`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum WeatherKind {
+    #[default]
+    Clear,
+    Overcast,
+    LightRain,
+    HeavyRain,
+    Fog,
+    Windy,
+    Thunderstorm,
+}
+
+impl WeatherKind {
+    /// What this weather looks, sounds and feels like once it has fully set in.
+    pub fn conditions(self) -> WeatherConditions {
+        let (cloud_cover, rain, wind, fog, sunlight) = match self {
+            Self::Clear => (0.1, 0.0, 0.15, 0.0, 1.0),
+            Self::Overcast => (0.85, 0.0, 0.25, 0.1, 0.45),
+            Self::LightRain => (0.9, 0.35, 0.3, 0.2, 0.35),
+            Self::HeavyRain => (1.0, 1.0, 0.5, 0.35, 0.2),
+            Self::Fog => (0.6, 0.0, 0.02, 1.0, 0.5),
+            Self::Windy => (0.45, 0.0, 1.0, 0.0, 0.85),
+            Self::Thunderstorm => (1.0, 0.9, 0.8, 0.3, 0.12),
+        };
+        WeatherConditions {
+            cloud_cover,
+            rain,
+            wind,
+            fog,
+            sunlight,
+            storm: if self == Self::Thunderstorm { 1.0 } else { 0.0 },
+            lightning: 0.0,
+        }
+    }
+
+    /// Weathers that can follow this one, and how likely each is. Weather builds and clears
+    /// gradually: clear skies cloud over before it rains, and storms ease into rain.
+    fn next(self) -> &'static [(WeatherKind, f32)] {
+        use WeatherKind::*;
+        match self {
+            Clear => &[(Clear, 0.45), (Overcast, 0.3), (Windy, 0.15), (Fog, 0.1)],
+            Overcast => &[
+                (Clear, 0.3),
+                (Overcast, 0.15),
+                (LightRain, 0.35),
+                (Fog, 0.1),
+                (Windy, 0.1),
+            ],
+            LightRain => &[
+                (Overcast, 0.4),
+                (LightRain, 0.2),
+                (HeavyRain, 0.3),
+                (Thunderstorm, 0.1),
+            ],
+            HeavyRain => &[(LightRain, 0.5), (Overcast, 0.3), (Thunderstorm, 0.2)],
+            Fog => &[(Clear, 0.5), (Overcast, 0.5)],
+            Windy => &[(Clear, 0.4), (Overcast, 0.4), (Thunderstorm, 0.2)],
+            Thunderstorm => &[(HeavyRain, 0.6), (LightRain, 0.4)],
+        }
+    }
+}
+
+/// How the weather affects the clearing right now, each from 0 to 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WeatherConditions {
+    /// Share of the sky covered by cloud
+    pub cloud_cover: f32,
+    /// How hard it's raining
+    pub rain: f32,
+    /// How hard the wind blows
+    pub wind: f32,
+    /// How thick the fog is
+    pub fog: f32,
+    /// Share of the sun or moon's light that gets through the clouds
+    pub sunlight: f32,
+    /// How much of a thunderstorm is overhead
+    pub storm: f32,
+    /// Brightness of a lightning flash, fading from 1
+    pub lightning: f32,
+}
+
+impl WeatherConditions {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Self {
+            cloud_cover: self.cloud_cover.lerp(other.cloud_cover, t),
+            rain: self.rain.lerp(other.rain, t),
+            wind: self.wind.lerp(other.wind, t),
+            fog: self.fog.lerp(other.fog, t),
+            sunlight: self.sunlight.lerp(other.sunlight, t),
+            storm: self.storm.lerp(other.storm, t),
+            lightning: self.lightning.lerp(other.lightning, t),
+        }
+    }
+}
+
+/// The weather over the clearing. Each weather lasts a while, then gives way to one of the
+/// weathers that can follow it, blending in over [`TRANSITION_TIME`].
+#[derive(Resource, Debug)]
+pub struct Weather {
+    current: WeatherKind,
+    previous: WeatherKind,
+    /// Seconds since the weather last changed
+    since_change: f32,
+    /// Seconds the current weather lasts
+    duration: f32,
+    /// Seconds since it last rained hard, if it has
+    since_rain: Option<f32>,
+    /// Brightness of the current lightning flash
+    flash: f32,
+    rng: ChaCha8Rng,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self::new(rand::rng().random())
+    }
+}
+
+impl Weather {
+    /// Clear skies, then the same weather every time for the same seed.
+    pub fn new(seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        Self {
+            current: WeatherKind::Clear,
+            previous: WeatherKind::Clear,
+            since_change: TRANSITION_TIME,
+            duration: rng.random_range(WEATHER_DURATION),
+            since_rain: None,
+            flash: 0.0,
+            rng,
+        }
+    }
+
+    pub fn current(&self) -> WeatherKind {
+        self.current
+    }
+
+    /// Starts blending into `kind` now, whatever the weather was doing.
+    pub fn set(&mut self, kind: WeatherKind) {
+        self.previous = self.current;
+        self.current = kind;
+        self.since_change = 0.0;
+        self.duration = self.rng.random_range(WEATHER_DURATION);
+    }
+
+    /// Moves the weather on by `delta` seconds. Returns the new weather if it changed.
+    pub fn tick(&mut self, delta: f32) -> Option<WeatherKind> {
+        self.since_change += delta;
+        let changed = if self.since_change >= self.duration {
+            let next = self
+                .current
+                .next()
+                .choose_weighted(&mut self.rng, |(_, weight)| *weight)
+                .map_or(WeatherKind::Clear, |(kind, _)| *kind);
+            self.set(next);
+            Some(next)
+        } else {
+            None
+        };
+
+        let conditions = self.conditions();
+        self.since_rain = if conditions.rain >= HEAVY_RAIN {
+            Some(0.0)
+        } else {
+            self.since_rain.map(|since| since + delta)
+        };
+
+        self.flash = (self.flash - FLASH_DECAY * delta).max(0.0);
+        if self.rng.random::<f32>() < conditions.storm * LIGHTNING_RATE * delta {
+            self.flash = 1.0;
+        }
+
+        changed
+    }
+
+    /// The weather right now, part way from the last weather to the current one.
+    pub fn conditions(&self) -> WeatherConditions {
+        let blend = (self.since_change / TRANSITION_TIME).min(1.0);
+        WeatherConditions {
+            lightning: self.flash,
+            ..self
+                .previous
+                .conditions()
+                .lerp(self.current.conditions(), blend)
+        }
+    }
+
+    /// How freshly a heavy rain has stopped: 1 just as it eases off, fading to 0 over
+    /// [`AFTER_RAIN`] seconds, and 0 while it's still pouring.
+    pub fn after_rain(&self) -> f32 {
+        match self.since_rain {
+            Some(since) if since > 0.0 => (1.0 - since / AFTER_RAIN).max(0.0),
+            _ => 0.0,
+        }
+    }
+}
+
+fn advance_weather(time: Res<Time>, mut weather: ResMut<Weather>) {
+    if let Some(kind) = weather.tick(time.delta_secs()) {
+        debug!("Weather turning to {kind:?}");
+    }
+}
+
+/// Blows the clouds along and pulls the fog in to match the weather.
+fn apply_weather(
+    weather: Res<Weather>,
+    mut clouds: ResMut<Clouds>,
+    mut fogs: Query<&mut DistanceFog>,
+) {
+    let conditions = weather.conditions();
+    clouds.cover = conditions.cloud_cover;
+    clouds.wind = WIND_DIRECTION * CLOUD_SPEED.start.lerp(CLOUD_SPEED.end, conditions.wind);
+
+    for mut fog in fogs.iter_mut() {
+        fog.falloff = FogFalloff::Linear {
+            start: CLEAR_FOG.0.lerp(THICK_FOG.0, conditions.fog),
+            end: CLEAR_FOG.1.lerp(THICK_FOG.1, conditions.fog),
+        };
+    }
+}
+
+/// One streak of falling rain. Only the first `rain * RAIN_DROPS` drops are shown.
+#[derive(Component)]
+struct Raindrop(usize);
+
+fn setup_rain(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Cuboid::new(0.012, 0.4, 0.012));
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgba(0.75, 0.8, 0.9, 0.45),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    });
+    let mut rng = rand::rng();
+
+    for index in 0..RAIN_DROPS {
+        let half = RAIN_AREA / 2.0;
+        commands.spawn((
+            Raindrop(index),
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(
+                rng.random_range(-half..half),
+                rng.random_range(0.0..RAIN_HEIGHT),
+                rng.random_range(-half..half),
+            ),
+            Visibility::Hidden,
+            NotShadowCaster,
+        ));
+    }
+}
+
+/// Drops rain through the clearing, slanted by the wind, starting each drop again from the
+/// top once it reaches the ground.
+fn fall_rain(
+    time: Res<Time>,
+    weather: Res<Weather>,
+    mut drops: Query<(&Raindrop, &mut Transform, &mut Visibility)>,
+) {
+    let conditions = weather.conditions();
+    let shown = (conditions.rain * RAIN_DROPS as f32) as usize;
+    let drift = WIND_DIRECTION * RAIN_DRIFT * conditions.wind;
+    let velocity = Vec3::new(drift.x, -RAIN_SPEED, drift.y);
+    let tilt = Quat::from_rotation_arc(Vec3::NEG_Y, velocity.normalize());
+    let half = RAIN_AREA / 2.0;
+    let mut rng = rand::rng();
+
+    for (drop, mut transform, mut visibility) in drops.iter_mut() {
+        if drop.0 >= shown {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        *visibility = Visibility::Inherited;
+        transform.rotation = tilt;
+        transform.translation += velocity * time.delta_secs();
+        if transform.translation.y < 0.0 {
+            transform.translation = Vec3::new(
+                rng.random_range(-half..half),
+                transform.translation.y + RAIN_HEIGHT,
+                rng.random_range(-half..half),
+            );
+        }
+    }
+}
+
+/// The looping rain, faded in and out with the weather.
+#[derive(Resource)]
+struct WeatherSounds {
+    rain: Handle<AudioInstance>,
+}
+
+fn start_weather_sounds(mut commands: Commands, audio_assets: Res<AudioAssets>, audio: Res<Audio>) {
+    commands.insert_resource(WeatherSounds {
+        rain: audio
+            .play(audio_assets.rain.clone())
+            .looped()
+            .with_volume(Decibels(SILENT))
+            .handle(),
+    });
+}
+
+fn mix_weather_sounds(
+    weather: Res<Weather>,
+    sounds: Option<Res<WeatherSounds>>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    mut last: Local<f32>,
+) {
+    let Some(sounds) = sounds else {
+        return;
+    };
+    let volume = decibels(RAIN_VOLUME * weather.conditions().rain);
+    // Only retune the sound once the change is loud enough to hear
+    if (volume - *last).abs() < 0.5 {
+        return;
+    }
+    *last = volume;
+
+    if let Some(instance) = instances.get_mut(&sounds.rain) {
+        instance.set_decibels(Decibels(volume), AudioTween::linear(VOLUME_TWEEN));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the weather for `seconds`, listing every change.
+    fn changes(weather: &mut Weather, seconds: usize) -> Vec<WeatherKind> {
+        (0..seconds).filter_map(|_| weather.tick(1.0)).collect()
+    }
+
+    #[test]
+    fn test_same_seed_same_weather() {
+        let first = changes(&mut Weather::new(7), 20_000);
+        let second = changes(&mut Weather::new(7), 20_000);
+        assert!(first.len() > 50);
+        assert_eq!(first, second);
+        assert_ne!(first, changes(&mut Weather::new(8), 20_000));
+    }
+
+    #[test]
+    fn test_weather_builds_gradually() {
+        let mut weather = Weather::new(3);
+        let mut previous = weather.current();
+        for _ in 0..100_000 {
+            if let Some(next) = weather.tick(1.0) {
+                assert!(
+                    previous.next().iter().any(|(kind, _)| *kind == next),
+                    "{previous:?} can't turn into {next:?}"
+                );
+                previous = next;
+            }
+        }
+    }
+
+    #[test]
+    fn test_new_weather_blends_in() {
+        let mut weather = Weather::new(1);
+        weather.set(WeatherKind::HeavyRain);
+        assert_eq!(weather.conditions().rain, 0.0);
+        weather.tick(TRANSITION_TIME / 2.0);
+        let halfway = weather.conditions();
+        assert!((halfway.rain - 0.5).abs() < 0.01, "{halfway:?}");
+        assert!(halfway.sunlight < 1.0 && halfway.sunlight > 0.2);
+        weather.tick(TRANSITION_TIME);
+        assert_eq!(weather.conditions().cloud_cover, 1.0);
+    }
+
+    #[test]
+    fn test_birds_sing_after_the_rain() {
+        let mut weather = Weather::new(2);
+        weather.set(WeatherKind::HeavyRain);
+        weather.tick(TRANSITION_TIME);
+        assert_eq!(weather.after_rain(), 0.0, "Still pouring");
+
+        weather.set(WeatherKind::Overcast);
+        for _ in 0..20 {
+            weather.tick(1.0);
+        }
+        let fresh = weather.after_rain();
+        assert!(fresh > 0.5, "{fresh}");
+        for _ in 0..AFTER_RAIN as usize {
+            weather.tick(1.0);
+        }
+        assert_eq!(weather.after_rain(), 0.0);
+    }
+
+    #[test]
+    fn test_lightning_only_in_storms() {
+        let mut weather = Weather::new(5);
+        weather.set(WeatherKind::HeavyRain);
+        weather.duration = f32::INFINITY;
+        for _ in 0..10_000 {
+            weather.tick(0.1);
+            assert_eq!(weather.conditions().lightning, 0.0);
+        }
+
+        weather.set(WeatherKind::Thunderstorm);
+        weather.duration = f32::INFINITY;
+        let flashes = (0..10_000)
+            .filter(|_| {
+                weather.tick(0.1);
+                weather.conditions().lightning == 1.0
+            })
+            .count();
+        assert!(flashes > 10, "{flashes}");
+    }
+}
@@ -0,0 +1,728 @@
+//! Animals that come through the clearing on foot: squirrels that run up the trunks and
+//! chatter from them, chipmunks feeding around the seed patches, and now and then a deer
+//! stopping at the pond at dawn or dusk.
+//!
+//! Each animal arrives from the edge of the forest with a [`Plan`] of steps for one visit,
+//! makes a few visits and leaves again. Animals move with the same fixed-timestep pipeline as
+//! the birds, see [`crate::movement`], and birds take notice of them: jays scold squirrels
+//! (see `scolds` in `assets/birds.species.ron`).
+
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+use std::ops::{Range, RangeInclusive};
+
+use bevy::prelude::*;
+use bevy_kira_audio::SpatialRadius;
+use bevy_kira_audio::prelude::*;
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use serde::Deserialize;
+
+use crate::GameState;
+use crate::loading::AudioAssets;
+use crate::movement::{PhysicalTranslation, PreviousPhysicalTranslation, Velocity};
+use crate::scene::{DayClock, Destination, FoodSource, Obstacle, SeasonClock};
+use crate::terrain::{Terrain, smoothstep};
+use crate::weather::{HEAVY_RAIN, Weather};
+
+pub struct WildlifePlugin;
+
+impl Plugin for WildlifePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WildlifeSpawnTimer>()
+            .add_systems(Startup, setup_animal_bodies)
+            .add_systems(
+                Update,
+                (
+                    spawn_animals,
+                    follow_plans,
+                    (face_travel_direction, bound),
+                    despawn_departed_animals,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// Animals arrive and leave this far from the middle of the clearing, out of sight.
+const EDGE_DISTANCE: f32 = 24.0;
+/// Seconds between arrivals.
+const ARRIVAL_INTERVAL: Range<f32> = 15.0..40.0;
+/// How close an animal gets to a spot before it counts as there.
+const ARRIVAL_DISTANCE: f32 = 0.08;
+/// How quickly animals on the ground follow it up and down hills.
+const GROUND_FOLLOW: f32 = 10.0;
+/// Animals on the ground give trunks this much room.
+const TRUNK_CLEARANCE: f32 = 0.25;
+/// Squirrels look for trees within this distance of where they are.
+const TREE_SEARCH: f32 = 10.0;
+/// How far up a trunk squirrels climb, as a share of the bare trunk.
+const CLING_HEIGHT: Range<f32> = 0.4..1.0;
+/// How far from the bark a climbing squirrel's body is.
+const BARK_CLEARANCE: f32 = 0.06;
+/// Chipmunks feed around this far from the middle of a seed patch.
+const PATCH_RADIUS: f32 = 0.9;
+/// Deer drink this far out from the edge of the pond.
+const DRINKING_REACH: f32 = 0.5;
+
+/// Small animals bound along; the body rises this high at each leap, in units, and leaps
+/// this many times a second.
+const BOUND_HEIGHT: f32 = 0.05;
+const BOUND_RATE: f32 = 6.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum AnimalKind {
+    Squirrel,
+    Chipmunk,
+    Deer,
+}
+
+impl AnimalKind {
+    const ALL: [Self; 3] = [Self::Squirrel, Self::Chipmunk, Self::Deer];
+
+    /// Most of them in the clearing at once.
+    fn most(self) -> usize {
+        match self {
+            Self::Squirrel => 2,
+            Self::Chipmunk => 2,
+            Self::Deer => 1,
+        }
+    }
+
+    /// Running or walking speed in units/second.
+    fn speed(self) -> f32 {
+        match self {
+            Self::Squirrel => 1.2,
+            Self::Chipmunk => 1.0,
+            Self::Deer => 0.5,
+        }
+    }
+
+    /// How many visits each makes before leaving again.
+    fn visits(self) -> RangeInclusive<u32> {
+        match self {
+            Self::Squirrel => 2..=4,
+            Self::Chipmunk => 3..=6,
+            Self::Deer => 1..=1,
+        }
+    }
+
+    /// Seconds spent feeding at each stop.
+    fn feeding(self) -> Range<f32> {
+        match self {
+            Self::Squirrel => 3.0..8.0,
+            Self::Chipmunk => 4.0..10.0,
+            Self::Deer => 8.0..15.0,
+        }
+    }
+
+    /// How likely each is to turn up, from 0 to 1. Squirrels and chipmunks are out by day,
+    /// though chipmunks sleep through the winter, and deer only come by around sunrise and
+    /// sunset, and rarely at that.
+    fn arrival_rate(self, sun_elevation: f32, day_of_year: f32) -> f32 {
+        let daytime = smoothstep(0.0, 0.2, sun_elevation);
+        match self {
+            Self::Squirrel => daytime,
+            Self::Chipmunk => {
+                let awake = smoothstep(70.0, 90.0, day_of_year)
+                    * (1.0 - smoothstep(290.0, 310.0, day_of_year));
+                daytime * awake
+            }
+            Self::Deer => 0.3 * (1.0 - smoothstep(0.1, 0.25, sun_elevation.abs())),
+        }
+    }
+}
+
+/// A squirrel, chipmunk or deer.
+#[derive(Component, Debug)]
+pub struct Animal {
+    pub kind: AnimalKind,
+    /// Visits made so far; the animal leaves after `max_visits`
+    visits: u32,
+    max_visits: u32,
+}
+
+/// One thing an animal does on a visit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Step {
+    /// Over the ground to a spot
+    Run(Vec3),
+    /// Up or down a trunk. `out` points away from the bark, where the animal's back is
+    Climb { to: Vec3, out: Vec3 },
+    /// Sitting on a trunk, chattering
+    Cling { seconds: f32, out: Vec3 },
+    /// Nibbling, digging or grazing. Deer drink at the pond the same way
+    Feed { seconds: f32 },
+    /// Off into the forest, never to return
+    Leave(Vec3),
+}
+
+/// What an animal is doing, and what it will do next.
+#[derive(Component, Debug)]
+struct Plan {
+    step: Step,
+    upcoming: VecDeque<Step>,
+    /// Seconds since the current step started
+    elapsed: f32,
+}
+
+impl Plan {
+    fn new(steps: Vec<Step>) -> Self {
+        let mut upcoming = VecDeque::from(steps);
+        let step = upcoming.pop_front().unwrap_or(Step::Feed { seconds: 1.0 });
+        Self {
+            step,
+            upcoming,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Moves on to the next step, if there is one.
+    fn advance(&mut self) -> bool {
+        let Some(next) = self.upcoming.pop_front() else {
+            return false;
+        };
+        self.step = next;
+        self.elapsed = 0.0;
+        true
+    }
+}
+
+/// A trunk to climb.
+#[derive(Clone, Copy, Debug)]
+struct Trunk {
+    /// The base of the trunk, on the ground
+    foot: Vec3,
+    radius: f32,
+    /// Height of the bare trunk, below the lowest limb
+    height: f32,
+}
+
+/// What the clearing offers animals: trunks to climb and seed to pick over.
+struct Surroundings<'a> {
+    trunks: &'a [Trunk],
+    seed_patches: &'a [Vec3],
+    terrain: &'a Terrain,
+}
+
+/// A point `distance` from the middle of the clearing, on the ground.
+fn edge_point(terrain: &Terrain, angle: f32, distance: f32) -> Vec3 {
+    terrain.on_ground(angle.cos() * distance, angle.sin() * distance)
+}
+
+/// The steps of one visit, starting from `at`.
+fn plan_visit(
+    kind: AnimalKind,
+    rng: &mut impl Rng,
+    at: Vec3,
+    surroundings: &Surroundings,
+) -> Vec<Step> {
+    let feed = Step::Feed {
+        seconds: rng.random_range(kind.feeding()),
+    };
+    match kind {
+        // Up a nearby tree to chatter from the trunk, then down again to dig about at its foot
+        AnimalKind::Squirrel => {
+            let mut nearby: Vec<&Trunk> = surroundings
+                .trunks
+                .iter()
+                .filter(|trunk| trunk.foot.xz().distance(at.xz()) < TREE_SEARCH)
+                .collect();
+            if nearby.is_empty() {
+                nearby = surroundings.trunks.iter().collect();
+            }
+            let Some(trunk) = nearby.choose(rng).copied() else {
+                return vec![feed];
+            };
+            let out = Vec2::from_angle(rng.random_range(0.0..TAU));
+            let out = Vec3::new(out.x, 0.0, out.y);
+            let foot = trunk.foot + out * (trunk.radius + BARK_CLEARANCE);
+            let cling = foot + Vec3::Y * trunk.height * rng.random_range(CLING_HEIGHT);
+            let dig = foot + out * rng.random_range(0.5..1.5);
+            vec![
+                Step::Run(foot),
+                Step::Climb { to: cling, out },
+                Step::Cling {
+                    seconds: rng.random_range(4.0..10.0),
+                    out,
+                },
+                Step::Climb { to: foot, out },
+                Step::Run(surroundings.terrain.on_ground(dig.x, dig.z)),
+                feed,
+            ]
+        }
+        // Over to a seed patch, to pick up what the birds dropped
+        AnimalKind::Chipmunk => {
+            let Some(patch) = surroundings.seed_patches.choose(rng).copied() else {
+                return vec![feed];
+            };
+            let spot = patch.xz() + Vec2::from_angle(rng.random_range(0.0..TAU)) * PATCH_RADIUS;
+            vec![
+                Step::Run(surroundings.terrain.on_ground(spot.x, spot.y)),
+                feed,
+            ]
+        }
+        // Down to the pond for a drink, and back off into the trees
+        AnimalKind::Deer => {
+            let pond = surroundings.terrain.pond;
+            let from_pond = (at.xz() - pond.center).normalize_or(Vec2::X);
+            let shore = pond.center + from_pond * (pond.radius + DRINKING_REACH);
+            // Away from the water, so as not to wade through it
+            let onward = from_pond.to_angle() + rng.random_range(-1.0..1.0);
+            vec![
+                Step::Run(surroundings.terrain.on_ground(shore.x, shore.y)),
+                feed,
+                Step::Leave(edge_point(
+                    surroundings.terrain,
+                    onward,
+                    EDGE_DISTANCE + 2.0,
+                )),
+            ]
+        }
+    }
+}
+
+// -- Spawning --
+
+#[derive(Resource)]
+struct WildlifeSpawnTimer(Timer);
+
+impl Default for WildlifeSpawnTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(10.0, TimerMode::Once))
+    }
+}
+
+/// A body part: its mesh and color, and where it sits on the animal.
+type BodyPart = (Handle<Mesh>, Handle<StandardMaterial>, Transform);
+
+/// Shapes and colors for each kind of animal, made once at startup. Animals face -Z.
+#[derive(Resource)]
+struct AnimalBodies {
+    squirrel: Vec<BodyPart>,
+    chipmunk: Vec<BodyPart>,
+    deer: Vec<BodyPart>,
+}
+
+impl AnimalBodies {
+    fn get(&self, kind: AnimalKind) -> &[BodyPart] {
+        match kind {
+            AnimalKind::Squirrel => &self.squirrel,
+            AnimalKind::Chipmunk => &self.chipmunk,
+            AnimalKind::Deer => &self.deer,
+        }
+    }
+}
+
+fn setup_animal_bodies(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut color = |r, g, b| {
+        materials.add(StandardMaterial {
+            base_color: Color::srgb(r, g, b),
+            perceptual_roughness: 0.9,
+            ..default()
+        })
+    };
+    let gray = color(0.45, 0.42, 0.38);
+    let pale_gray = color(0.62, 0.6, 0.56);
+    let chestnut = color(0.55, 0.36, 0.2);
+    let dark = color(0.25, 0.17, 0.1);
+    let fawn = color(0.58, 0.42, 0.28);
+
+    let mut shape = |mesh: Mesh| meshes.add(mesh);
+    // Bodies lie along Z, tails curl up behind
+    let lying = Quat::from_rotation_x(std::f32::consts::FRAC_PI_2);
+    let mut small =
+        |size: f32, body: &Handle<StandardMaterial>, tail: &Handle<StandardMaterial>| {
+            vec![
+                (
+                    shape(Capsule3d::new(0.05 * size, 0.12 * size).into()),
+                    body.clone(),
+                    Transform::from_xyz(0.0, 0.06 * size, 0.0).with_rotation(lying),
+                ),
+                (
+                    shape(Sphere::new(0.045 * size).into()),
+                    body.clone(),
+                    Transform::from_xyz(0.0, 0.1 * size, -0.11 * size),
+                ),
+                (
+                    shape(Capsule3d::new(0.045 * size, 0.14 * size).into()),
+                    tail.clone(),
+                    Transform::from_xyz(0.0, 0.14 * size, 0.12 * size)
+                        .with_rotation(Quat::from_rotation_x(0.5)),
+                ),
+            ]
+        };
+    let squirrel = small(1.5, &gray, &pale_gray);
+    let mut chipmunk = small(0.9, &chestnut, &dark);
+    // A chipmunk's tail is thin
+    chipmunk[2].2.scale = Vec3::new(0.35, 1.0, 0.35);
+
+    let leg = shape(Cylinder::new(0.035, 0.55).into());
+    let mut deer = vec![
+        (
+            shape(Capsule3d::new(0.16, 0.45).into()),
+            fawn.clone(),
+            Transform::from_xyz(0.0, 0.7, 0.0).with_rotation(lying),
+        ),
+        (
+            shape(Capsule3d::new(0.06, 0.28).into()),
+            fawn.clone(),
+            Transform::from_xyz(0.0, 0.92, -0.36).with_rotation(Quat::from_rotation_x(-0.6)),
+        ),
+        (
+            shape(Capsule3d::new(0.07, 0.12).into()),
+            fawn.clone(),
+            Transform::from_xyz(0.0, 1.08, -0.48).with_rotation(lying),
+        ),
+    ];
+    for (x, z) in [(-0.1, -0.25), (0.1, -0.25), (-0.1, 0.25), (0.1, 0.25)] {
+        deer.push((leg.clone(), dark.clone(), Transform::from_xyz(x, 0.3, z)));
+    }
+
+    commands.insert_resource(AnimalBodies {
+        squirrel,
+        chipmunk,
+        deer,
+    });
+}
+
+/// The part of an animal that bounds up and down as it runs.
+#[derive(Component)]
+struct Body;
+
+/// Every so often another animal turns up from the edge of the forest, if it's the time of
+/// day and year for it.
+#[allow(clippy::too_many_arguments)]
+fn spawn_animals(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut spawn_timer: ResMut<WildlifeSpawnTimer>,
+    day_clock: Res<DayClock>,
+    season: Res<SeasonClock>,
+    weather: Res<Weather>,
+    terrain: Res<Terrain>,
+    bodies: Res<AnimalBodies>,
+    animals: Query<&Animal>,
+    spots: Query<(&Transform, &Destination, Option<&Obstacle>)>,
+) {
+    if !spawn_timer.0.tick(time.delta()).is_finished() {
+        return;
+    }
+    let mut rng = rand::rng();
+    spawn_timer.0 = Timer::from_seconds(rng.random_range(ARRIVAL_INTERVAL), TimerMode::Once);
+    if weather.conditions().rain >= HEAVY_RAIN {
+        return;
+    }
+
+    let sun_elevation = day_clock.sun_elevation();
+    let welcome: Vec<(AnimalKind, f32)> = AnimalKind::ALL
+        .into_iter()
+        .filter(|kind| animals.iter().filter(|animal| animal.kind == *kind).count() < kind.most())
+        .map(|kind| (kind, kind.arrival_rate(sun_elevation, season.day)))
+        .collect();
+    let Ok(&(kind, _)) = welcome.choose_weighted(&mut rng, |(_, rate)| *rate) else {
+        return;
+    };
+
+    let (trunks, seed_patches) = surroundings(&spots);
+    let start = edge_point(&terrain, rng.random_range(0.0..TAU), EDGE_DISTANCE);
+    let plan = plan_visit(
+        kind,
+        &mut rng,
+        start,
+        &Surroundings {
+            trunks: &trunks,
+            seed_patches: &seed_patches,
+            terrain: &terrain,
+        },
+    );
+    commands
+        .spawn((
+            Name::new(format!("{kind:?}")),
+            Animal {
+                kind,
+                visits: 0,
+                max_visits: rng.random_range(kind.visits()),
+            },
+            Plan::new(plan),
+            Transform::from_translation(start),
+            Visibility::default(),
+            PhysicalTranslation(start),
+            PreviousPhysicalTranslation(start),
+            Velocity::default(),
+            SpatialAudioEmitter { instances: vec![] },
+            SpatialRadius { radius: 40.0 },
+        ))
+        .with_children(|animal| {
+            animal
+                .spawn((Body, Transform::default(), Visibility::default()))
+                .with_children(|body| {
+                    for (mesh, material, transform) in bodies.get(kind) {
+                        body.spawn((
+                            Mesh3d(mesh.clone()),
+                            MeshMaterial3d(material.clone()),
+                            *transform,
+                        ));
+                    }
+                });
+        });
+}
+
+/// Trunks and seed patches, from the spots birds visit.
+fn surroundings(
+    spots: &Query<(&Transform, &Destination, Option<&Obstacle>)>,
+) -> (Vec<Trunk>, Vec<Vec3>) {
+    let mut trunks = Vec::new();
+    let mut seed_patches = Vec::new();
+    for (transform, destination, obstacle) in spots.iter() {
+        match (destination, obstacle) {
+            (
+                Destination::Food(FoodSource::Trunk),
+                Some(Obstacle::Cylinder {
+                    radius,
+                    half_height,
+                }),
+            ) => trunks.push(Trunk {
+                foot: transform.translation - Vec3::Y * *half_height,
+                radius: *radius,
+                height: half_height * 2.0,
+            }),
+            (Destination::Food(FoodSource::Ground), _) => seed_patches.push(transform.translation),
+            _ => {}
+        }
+    }
+    (trunks, seed_patches)
+}
+
+// -- Moving --
+
+/// Steers each animal through its plan, planning another visit when one ends, or the way out
+/// after the last. Squirrels chatter when they stop on a trunk and chipmunks call as they
+/// start feeding.
+#[allow(clippy::too_many_arguments)]
+fn follow_plans(
+    time: Res<Time>,
+    audio: Res<Audio>,
+    audio_assets: Res<AudioAssets>,
+    terrain: Res<Terrain>,
+    spots: Query<(&Transform, &Destination, Option<&Obstacle>)>,
+    mut animals: Query<(
+        &mut Animal,
+        &mut Plan,
+        &PhysicalTranslation,
+        &mut Velocity,
+        &mut SpatialAudioEmitter,
+    )>,
+    audio_instances: Res<Assets<AudioInstance>>,
+) {
+    let mut rng = rand::rng();
+    let dt = time.delta_secs();
+    let mut nearby = None;
+
+    for (mut animal, mut plan, position, mut velocity, mut emitter) in animals.iter_mut() {
+        let (trunks, seed_patches) = nearby.get_or_insert_with(|| surroundings(&spots));
+        plan.elapsed += dt;
+        let speed = animal.kind.speed();
+        let done = match plan.step {
+            Step::Run(target) | Step::Leave(target) => {
+                let to_target = (target - position.0).xz();
+                let heading = to_target.normalize_or_zero();
+                let mut ground_velocity = heading * speed.min(to_target.length() / dt.max(0.001));
+                ground_velocity += steer_around(position.0, trunks, heading) * speed;
+                let ahead = position.0.xz() + ground_velocity * dt;
+                let rise = (terrain.height(ahead.x, ahead.y) - position.0.y) * GROUND_FOLLOW;
+                velocity.0 = Vec3::new(ground_velocity.x, rise, ground_velocity.y);
+                matches!(plan.step, Step::Run(_)) && to_target.length() < ARRIVAL_DISTANCE
+            }
+            Step::Climb { to, .. } => {
+                let to_target = to - position.0;
+                velocity.0 =
+                    to_target.normalize_or_zero() * speed.min(to_target.length() / dt.max(0.001));
+                to_target.length() < ARRIVAL_DISTANCE
+            }
+            Step::Cling { seconds, .. } | Step::Feed { seconds } => {
+                velocity.0 = Vec3::ZERO;
+                plan.elapsed >= seconds
+            }
+        };
+        if !done {
+            continue;
+        }
+
+        if !plan.advance() {
+            animal.visits += 1;
+            let steps = if animal.visits >= animal.max_visits {
+                let away = position.0.xz().to_angle() + rng.random_range(-0.5..0.5);
+                vec![Step::Leave(edge_point(&terrain, away, EDGE_DISTANCE + 2.0))]
+            } else {
+                plan_visit(
+                    animal.kind,
+                    &mut rng,
+                    position.0,
+                    &Surroundings {
+                        trunks,
+                        seed_patches,
+                        terrain: &terrain,
+                    },
+                )
+            };
+            *plan = Plan::new(steps);
+        }
+
+        let call = match (animal.kind, plan.step) {
+            (AnimalKind::Squirrel, Step::Cling { .. }) => Some(&audio_assets.squirrel),
+            (AnimalKind::Chipmunk, Step::Feed { .. }) if rng.random_bool(0.5) => {
+                Some(&audio_assets.chipmunk)
+            }
+            _ => None,
+        };
+        if let Some(call) = call {
+            emitter
+                .instances
+                .retain(|handle| audio_instances.contains(handle));
+            emitter.instances.push(audio.play(call.clone()).handle());
+        }
+    }
+}
+
+/// Which way to sidestep the trunks just ahead, pushing away from each in proportion to how
+/// close it is.
+fn steer_around(position: Vec3, trunks: &[Trunk], heading: Vec2) -> Vec2 {
+    trunks
+        .iter()
+        .filter_map(|trunk| {
+            let away = position.xz() - trunk.foot.xz();
+            let gap = away.length() - trunk.radius - TRUNK_CLEARANCE;
+            (gap < 0.0 && away.dot(heading) < 0.0)
+                .then(|| away.normalize_or_zero() * (-gap / TRUNK_CLEARANCE).min(1.0))
+        })
+        .sum()
+}
+
+/// Animals face where they're going, and climbers face up or down the trunk with their back
+/// to the open.
+fn face_travel_direction(mut animals: Query<(&Plan, &Velocity, &mut Transform)>) {
+    for (plan, velocity, mut transform) in animals.iter_mut() {
+        let (facing, up) = match plan.step {
+            Step::Climb { out, .. } => (Vec3::Y * velocity.y.signum(), out),
+            Step::Cling { out, .. } => (Vec3::Y, out),
+            _ => (Vec3::new(velocity.x, 0.0, velocity.z), Vec3::Y),
+        };
+        if facing.length_squared() > 1e-6 {
+            transform.look_to(facing, up);
+        }
+    }
+}
+
+/// Squirrels and chipmunks bound as they run; deer step along more smoothly.
+fn bound(
+    time: Res<Time>,
+    animals: Query<(&Animal, &Velocity)>,
+    mut bodies: Query<(&ChildOf, &mut Transform), With<Body>>,
+) {
+    let t = time.elapsed_secs();
+    for (child_of, mut transform) in bodies.iter_mut() {
+        let Ok((animal, velocity)) = animals.get(child_of.parent()) else {
+            continue;
+        };
+        let moving = velocity.xz().length() > 0.05;
+        let (height, rate) = match animal.kind {
+            AnimalKind::Deer => (0.02, BOUND_RATE / 3.0),
+            _ => (BOUND_HEIGHT, BOUND_RATE),
+        };
+        transform.translation.y = if moving {
+            (t * rate * std::f32::consts::PI).sin().abs() * height
+        } else {
+            0.0
+        };
+    }
+}
+
+fn despawn_departed_animals(
+    mut commands: Commands,
+    animals: Query<(Entity, &Plan, &PhysicalTranslation, &SpatialAudioEmitter), With<Animal>>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    for (entity, plan, position, emitter) in animals.iter() {
+        if matches!(plan.step, Step::Leave(_)) && position.0.xz().length() > EDGE_DISTANCE + 1.0 {
+            for handle in &emitter.instances {
+                if let Some(instance) = audio_instances.get_mut(handle) {
+                    instance.stop(AudioTween::default());
+                }
+            }
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    const NOON: f32 = 1.0;
+    const MIDSUMMER: f32 = 172.0;
+
+    #[test]
+    fn test_animals_keep_their_hours() {
+        assert!(AnimalKind::Squirrel.arrival_rate(NOON, MIDSUMMER) > 0.9);
+        assert_eq!(AnimalKind::Squirrel.arrival_rate(-0.5, MIDSUMMER), 0.0);
+        assert_eq!(
+            AnimalKind::Chipmunk.arrival_rate(NOON, 10.0),
+            0.0,
+            "Chipmunks sleep through the winter"
+        );
+        assert_eq!(AnimalKind::Deer.arrival_rate(NOON, MIDSUMMER), 0.0);
+        assert!(AnimalKind::Deer.arrival_rate(0.0, MIDSUMMER) > 0.0);
+    }
+
+    #[test]
+    fn test_visits() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let terrain = Terrain::generate(&mut rng);
+        let trunk = Trunk {
+            foot: terrain.on_ground(3.0, -3.0),
+            radius: 0.2,
+            height: 2.0,
+        };
+        let surroundings = Surroundings {
+            trunks: &[trunk],
+            seed_patches: &[terrain.on_ground(-2.5, -2.0)],
+            terrain: &terrain,
+        };
+        let start = edge_point(&terrain, 1.0, EDGE_DISTANCE);
+
+        let squirrel = plan_visit(AnimalKind::Squirrel, &mut rng, start, &surroundings);
+        let Step::Climb { to, out } = squirrel[1] else {
+            panic!("Squirrels climb the trunk: {squirrel:?}");
+        };
+        assert!(to.y > trunk.foot.y && to.y <= trunk.foot.y + trunk.height);
+        assert!((to.xz().distance(trunk.foot.xz()) - trunk.radius - BARK_CLEARANCE).abs() < 1e-4);
+        assert!(out.dot(to - trunk.foot) > 0.0, "Back to the open");
+        assert!(matches!(squirrel[3], Step::Climb { to, .. } if to.y < trunk.foot.y + 1e-4));
+
+        let chipmunk = plan_visit(AnimalKind::Chipmunk, &mut rng, start, &surroundings);
+        let Step::Run(spot) = chipmunk[0] else {
+            panic!("Chipmunks run to the seed: {chipmunk:?}");
+        };
+        assert!(spot.xz().distance(Vec2::new(-2.5, -2.0)) < PATCH_RADIUS + 1e-4);
+
+        let deer = plan_visit(AnimalKind::Deer, &mut rng, start, &surroundings);
+        let Step::Run(shore) = deer[0] else {
+            panic!("Deer come to drink: {deer:?}");
+        };
+        let pond = terrain.pond;
+        assert!(
+            (shore.xz().distance(pond.center) - pond.radius - DRINKING_REACH).abs() < 1e-3,
+            "Deer drink at the water's edge"
+        );
+        assert!(matches!(deer.last(), Some(Step::Leave(_))));
+    }
+}